@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE github_programs ADD required_asset_pattern VARCHAR(256);
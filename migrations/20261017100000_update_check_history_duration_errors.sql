@@ -0,0 +1,6 @@
+-- Add migration script here
+ALTER TABLE update_check_history
+ADD duration_ms INTEGER;
+
+ALTER TABLE update_check_history
+ADD errors INTEGER;
@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE github_programs ADD access_token_env VARCHAR(256);
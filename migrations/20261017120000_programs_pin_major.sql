@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE programs ADD pin_major INTEGER;
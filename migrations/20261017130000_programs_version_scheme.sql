@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE programs ADD version_scheme VARCHAR(16) NOT NULL DEFAULT 'none';
@@ -0,0 +1,6 @@
+-- Add migration script here
+ALTER TABLE programs
+ADD allow_rollback BOOLEAN NOT NULL DEFAULT 0;
+
+ALTER TABLE programs
+ADD older_upstream_version VARCHAR(256);
@@ -0,0 +1,6 @@
+-- Add migration script here
+CREATE TABLE version_history (
+    'date' DATETIME NOT NULL,
+    'name' VARCHAR(256) NOT NULL,
+    'version' VARCHAR(256) NOT NULL
+);
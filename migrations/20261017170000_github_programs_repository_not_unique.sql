@@ -0,0 +1,16 @@
+-- Add migration script here
+-- the same repository can be tracked under multiple names, so the unique constraint is removed
+CREATE TABLE github_programs_new (
+    'name' VARCHAR(256) NOT NULL,
+    repository VARCHAR(256) NOT NULL,
+    required_asset_pattern VARCHAR(256),
+    access_token_env VARCHAR(256),
+    FOREIGN KEY ('name') REFERENCES programs('name')
+);
+
+INSERT INTO github_programs_new (name, repository, required_asset_pattern, access_token_env)
+SELECT name, repository, required_asset_pattern, access_token_env FROM github_programs;
+
+DROP TABLE github_programs;
+
+ALTER TABLE github_programs_new RENAME TO github_programs;
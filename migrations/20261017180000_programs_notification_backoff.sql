@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE programs ADD COLUMN notification_backoff_until DATETIME;
+ALTER TABLE programs ADD COLUMN notification_backoff_secs INTEGER;
+ALTER TABLE programs ADD COLUMN notified_version TEXT;
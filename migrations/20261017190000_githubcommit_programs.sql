@@ -0,0 +1,7 @@
+-- Add migration script here
+CREATE TABLE githubcommit_programs (
+    'name' VARCHAR(256) NOT NULL,
+    repository VARCHAR(256) NOT NULL,
+    branch VARCHAR(256),
+    FOREIGN KEY ('name') REFERENCES programs('name')
+);
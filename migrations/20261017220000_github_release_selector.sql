@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE github_programs ADD COLUMN release_selector TEXT NOT NULL DEFAULT 'latest';
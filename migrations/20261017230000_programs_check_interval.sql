@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE programs ADD COLUMN check_interval_secs INTEGER;
+ALTER TABLE programs ADD COLUMN last_checked DATETIME;
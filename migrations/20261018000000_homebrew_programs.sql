@@ -0,0 +1,7 @@
+-- Add migration script here
+CREATE TABLE homebrew_programs (
+    'name' VARCHAR(256) NOT NULL,
+    package VARCHAR(256) NOT NULL,
+    kind VARCHAR(16) NOT NULL,
+    FOREIGN KEY ('name') REFERENCES programs('name')
+);
@@ -0,0 +1,6 @@
+-- Add migration script here
+CREATE TABLE fdroid_programs (
+    'name' VARCHAR(256) NOT NULL,
+    package VARCHAR(256) NOT NULL,
+    FOREIGN KEY ('name') REFERENCES programs('name')
+);
@@ -0,0 +1,7 @@
+-- Add migration script here
+CREATE TABLE command_programs (
+    'name' VARCHAR(256) NOT NULL,
+    command TEXT NOT NULL,
+    regex TEXT NOT NULL,
+    FOREIGN KEY ('name') REFERENCES programs('name')
+);
@@ -0,0 +1,7 @@
+-- Add migration script here
+CREATE TABLE program_providers (
+    'name' VARCHAR(256) NOT NULL,
+    component VARCHAR(256) NOT NULL UNIQUE,
+    FOREIGN KEY ('name') REFERENCES programs('name'),
+    FOREIGN KEY (component) REFERENCES programs('name')
+);
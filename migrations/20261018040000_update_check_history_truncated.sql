@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE update_check_history
+ADD truncated BOOLEAN NOT NULL DEFAULT 0;
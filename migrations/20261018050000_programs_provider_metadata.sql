@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE programs ADD COLUMN provider_metadata TEXT;
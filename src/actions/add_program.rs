@@ -1,43 +1,121 @@
-use std::process;
+use anyhow::{Context, Result};
 
 use crate::{
-    DbConfig, Program, Provider,
-    cli::{AddGithubProgramArgs, AddProgramArgs},
+    CommandProvider, DbConfig, FDroidProvider, GithubCommitProvider, GithubProvider, HomebrewKind,
+    HomebrewProvider, Program, Provider, VersionHistoryEntry, VersionScheme,
+    cli::{AddProgramArgs, UpdateProviderAdd},
     db::Db,
+    http,
+    provider::AuthContext,
 };
 
-pub async fn add_program_github(
+use super::ActionOutcome;
+
+/// Creates the provider that is described by the provider subcommand.
+#[must_use]
+pub fn provider(update_provider: &UpdateProviderAdd) -> Provider {
+    match update_provider {
+        UpdateProviderAdd::Github(add_github_program_args) => Provider::Github(GithubProvider {
+            repository: add_github_program_args.repository.to_string(),
+            required_asset_pattern: add_github_program_args.required_asset_pattern.clone(),
+            access_token_env: add_github_program_args.access_token_env.clone(),
+            release_selector: add_github_program_args.release_selector,
+        }),
+        UpdateProviderAdd::GithubCommit(add_github_commit_program_args) => {
+            Provider::GithubCommit(GithubCommitProvider {
+                repository: add_github_commit_program_args.repository.to_string(),
+                branch: add_github_commit_program_args.branch.clone(),
+            })
+        }
+        UpdateProviderAdd::Homebrew(add_homebrew_program_args) => {
+            Provider::Homebrew(HomebrewProvider {
+                package: add_homebrew_program_args.package.to_string(),
+                kind: if add_homebrew_program_args.cask {
+                    HomebrewKind::Cask
+                } else {
+                    HomebrewKind::Formula
+                },
+            })
+        }
+        UpdateProviderAdd::FDroid(add_fdroid_program_args) => Provider::FDroid(FDroidProvider {
+            package: add_fdroid_program_args.package.to_string(),
+        }),
+        UpdateProviderAdd::Command(add_command_program_args) => {
+            Provider::Command(CommandProvider {
+                command: add_command_program_args.command.to_string(),
+                regex: add_command_program_args.regex.to_string(),
+            })
+        }
+    }
+}
+
+/// Inserts a new program into the database and stores its latest version in the version history.
+pub async fn insert(db: &Db, program: &Program) -> Result<()> {
+    db.insert_program(program).await?;
+    db.insert_found_version(&VersionHistoryEntry {
+        date: program.latest_version_last_updated,
+        name: program.name.clone(),
+        version: program.latest_version.clone(),
+    })
+    .await
+}
+
+/// Checks for the latest version of the program and adds it to the database.
+pub async fn add_program(
     db_config: DbConfig,
     add_program_args: &AddProgramArgs,
-    add_github_program_args: &AddGithubProgramArgs,
-    github_access_token: Option<String>,
-) {
-    let db = Db::connect(&db_config.db_path).await.unwrap();
-
-    if db
-        .get_program(&add_program_args.name)
-        .await
-        .unwrap()
-        .is_some()
+    auth: AuthContext,
+) -> Result<ActionOutcome> {
+    let update_provider = add_program_args.update_provider()?;
+    // commit hashes have no order, so every commit that differs is an update
+    if matches!(update_provider, UpdateProviderAdd::GithubCommit(_))
+        && (add_program_args.pin_major.is_some()
+            || add_program_args.version_constraint.is_some()
+            || add_program_args.version_scheme != VersionScheme::None
+            || add_program_args.allow_rollback)
     {
-        println!(
+        anyhow::bail!(
+            "'--pin-major', '--version-constraint', '--version-scheme' and '--allow-rollback' can not be used when the latest commit is used as version."
+        );
+    }
+    let provider = provider(&update_provider);
+    let db = db_config
+        .connect()
+        .await
+        .context("Unable to open database")?;
+
+    if db.get_program(&add_program_args.name).await?.is_some() {
+        return Ok(ActionOutcome::NothingToDo(format!(
             "Program named {} already exists in database.",
             &add_program_args.name
-        );
-        process::exit(0);
+        )));
+    }
+    if let Some(product) = &add_program_args.component_of {
+        if db.get_program(product).await?.is_none() {
+            anyhow::bail!("Program named {product} does not exist in database.");
+        }
+        if let Some(parent) = db.get_product(product).await? {
+            anyhow::bail!(
+                "Program {product} is a component of {parent} itself, add the program as component of {parent} instead."
+            );
+        }
     }
 
-    let program = Program::init(
-        &add_program_args.name,
-        Provider::Github(add_github_program_args.repository.to_string()),
-        github_access_token,
-    )
-    .await
-    .unwrap();
+    let program = Program::init(provider, add_program_args, &http::client(), &auth)
+        .await
+        .with_context(|| format!("Unable to add program {}", &add_program_args.name))?;
 
-    db.insert_program(&program).await.unwrap();
+    insert(&db, &program)
+        .await
+        .with_context(|| format!("Unable to add program {}", &add_program_args.name))?;
+    if let Some(product) = &add_program_args.component_of {
+        db.add_component(product, &program.name)
+            .await
+            .with_context(|| format!("Unable to add {} as component of {product}", program.name))?;
+    }
     println!(
         "Program {} successfully added to database!",
         &add_program_args.name
     );
+    Ok(ActionOutcome::Done)
 }
@@ -0,0 +1,376 @@
+//! Subcommands to validate the config file, to show the settings that result from it and to add the programs
+//! defined in it to the database.
+
+use std::{fs, path::Path, time::Duration};
+
+use anyhow::{Context, Result};
+use reqwest::Client;
+
+use crate::{
+    Program, Secret, Settings,
+    cli::{Cli, ConfigInitArgs, ConfigValidateArgs, RunTimedArgs},
+    config::{self, ConfigFile, ProgramConfig},
+    db::Db,
+    http,
+    provider::AuthContext,
+};
+
+use super::{ActionOutcome, add_program};
+
+/// Time after which the ntfy server is treated as unreachable.
+const CONNECTIVITY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Checks the config file at `path`, or the default config file when `path` is not set, and prints all problems.
+///
+/// Returns [`ActionOutcome::Failed`] when a problem is found.
+pub async fn validate(
+    path: Option<&Path>,
+    validate_args: &ConfigValidateArgs,
+) -> Result<ActionOutcome> {
+    let path = match path {
+        Some(path) => path.to_path_buf(),
+        None => config::default_config_path(),
+    };
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Unable to read config file {}", path.display()))?;
+    println!("Validating config file {}", path.display());
+    let mut problems = config::validate(&content);
+    if validate_args.check_connectivity {
+        // an invalid config file is already reported, the default server is checked instead
+        let config_file = ConfigFile::try_parse(Some(&path)).ok().flatten();
+        let run_timed_args = run_timed_args(config_file.as_ref());
+        let server = run_timed_args.ntfy_server();
+        if let Err(e) = http::client_with_timeout(Some(CONNECTIVITY_TIMEOUT))
+            .get(server)
+            .send()
+            .await
+        {
+            problems.push(format!("ntfy server {server} can not be reached: {e}"));
+        }
+    }
+    if problems.is_empty() {
+        println!("No problems found.");
+        return Ok(ActionOutcome::Done);
+    }
+    println!("Found {} problems:", problems.len());
+    for problem in problems {
+        println!("- {problem}");
+    }
+    Ok(ActionOutcome::Failed)
+}
+
+/// Writes a new config file to `path`, or to the default config file when `path` is not set.
+///
+/// Fails when the file already exists and `--force` is not set.
+pub fn init(path: Option<&Path>, init_args: &ConfigInitArgs) -> Result<ActionOutcome> {
+    let path = match path {
+        Some(path) => path.to_path_buf(),
+        None => config::default_config_path(),
+    };
+    if path.exists() && !init_args.force {
+        anyhow::bail!(
+            "Config file {} already exists, use --force to overwrite it.",
+            path.display()
+        );
+    }
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Unable to create directory {}", parent.display()))?;
+    }
+    let db_path = path.with_file_name("programs.db");
+    fs::write(&path, config::template(&db_path.to_string_lossy()))
+        .with_context(|| format!("Unable to write config file {}", path.display()))?;
+    println!("Config file written to {}", path.display());
+    Ok(ActionOutcome::Done)
+}
+
+/// Prints the settings that result from cli, environment variables and config file, secrets are redacted.
+pub async fn show(mut cli: Cli) -> Result<ActionOutcome> {
+    let config_file = ConfigFile::try_parse(cli.config.as_deref()).map_err(|e| {
+        anyhow::anyhow!(
+            "Unable to load config file: {e}\nUse 'config validate' to find all problems of the config file."
+        )
+    })?;
+    let run_timed_args = run_timed_args(config_file.as_ref());
+    let settings = Settings::resolve_with_config(&mut cli, config_file.as_ref())?;
+    match &config_file {
+        Some(config_file) => println!("Config file: {}", config_file.path),
+        None => println!("Config file: none"),
+    }
+    println!(
+        "Database: {}{}",
+        settings.db_config.db_path,
+        if settings.db_config.read_only {
+            " (read-only)"
+        } else {
+            ""
+        }
+    );
+    println!(
+        "Github access token: {}",
+        redacted(
+            settings
+                .auth
+                .github
+                .access_token
+                .as_ref()
+                .map(Secret::expose)
+        )
+    );
+    println!(
+        "Github fallback access tokens: {}",
+        settings.auth.github.fallback_access_tokens.len()
+    );
+    println!("Github max pages: {}", settings.auth.github.max_pages);
+    println!(
+        "Follow renamed repositories: {}",
+        settings.auth.github.auto_follow_renames
+    );
+    println!(
+        "Request timeout: {}",
+        settings
+            .request_timeout
+            .map_or("none".to_string(), |timeout| format!(
+                "{} seconds",
+                timeout.as_secs()
+            ))
+    );
+    println!(
+        "Default provider: {}",
+        config_file
+            .as_ref()
+            .and_then(|config_file| config_file.default_provider.as_deref())
+            .unwrap_or("none")
+    );
+    println!("\nrun-timed:");
+    println!(
+        "ntfy topic: {}",
+        redacted(run_timed_args.ntfy_topic.as_deref())
+    );
+    println!("ntfy server: {}", run_timed_args.ntfy_server());
+    println!(
+        "ntfy email: {}",
+        redacted(run_timed_args.ntfy_email.as_deref())
+    );
+    println!(
+        "Check interval: {} seconds",
+        run_timed_args.check_interval()
+    );
+    Ok(ActionOutcome::Done)
+}
+
+/// Result of syncing the programs defined in the config file into the database.
+#[derive(Debug, Default, PartialEq)]
+pub struct SyncReport {
+    /// Programs that where added to the database.
+    pub added: Vec<String>,
+    /// Programs that could not be added, together with the error.
+    pub failed: Vec<(String, String)>,
+    /// Programs in the database that are not defined in the config file and where kept.
+    pub not_defined: Vec<String>,
+    /// Programs in the database that are not defined in the config file and where removed, as `prune` is set.
+    pub removed: Vec<String>,
+}
+
+/// Adds the programs defined in the config file to the database and prints the result.
+///
+/// Fails when no programs are defined, returns [`ActionOutcome::Failed`] when a program could not be added.
+pub async fn sync_config(settings: Settings, assume_yes: bool) -> Result<ActionOutcome> {
+    let Some(programs) = &settings.config_programs else {
+        anyhow::bail!("No programs are defined in the config file.");
+    };
+    let db = settings
+        .db_config
+        .connect()
+        .await
+        .context("Unable to open database")?;
+    let mut prune = settings.prune;
+    if prune {
+        let mut not_defined = db
+            .get_all_programs()
+            .await?
+            .into_iter()
+            .map(|program| program.name)
+            .filter(|name| programs.iter().all(|defined| defined.name != *name))
+            .collect::<Vec<_>>();
+        not_defined.sort();
+        prune = not_defined.is_empty()
+            || super::confirm(
+                &format!(
+                    "Are you sure that {} should be removed from the database, as they are not defined in the config file?",
+                    not_defined.join(", ")
+                ),
+                assume_yes,
+            );
+    }
+    let report = sync_programs(&db, &settings.client(), &settings.auth, programs, prune)
+        .await
+        .context("Unable to sync the programs of the config file")?;
+    for name in &report.added {
+        println!("Added {name} to the database.");
+    }
+    for name in &report.removed {
+        println!("Removed {name} from the database, as it is not defined in the config file.");
+    }
+    for name in &report.not_defined {
+        if settings.prune {
+            println!("Note: {name} is not defined in the config file, it was not removed.");
+        } else {
+            println!(
+                "Note: {name} is not defined in the config file, set 'prune = true' to remove it from the database."
+            );
+        }
+    }
+    for (name, e) in &report.failed {
+        println!("Unable to add {name}: {e}");
+    }
+    if report.added.is_empty() && report.removed.is_empty() && report.failed.is_empty() {
+        println!("The database already contains all programs defined in the config file.");
+    }
+    if !report.failed.is_empty() {
+        return Ok(ActionOutcome::Failed);
+    }
+    Ok(ActionOutcome::Done)
+}
+
+/// Adds the programs in `programs` that do not yet exist to the database, existing programs are not changed.
+///
+/// Programs in the database that are not in `programs` are removed when `prune` is set.
+pub async fn sync_programs(
+    db: &Db,
+    client: &Client,
+    auth: &AuthContext,
+    programs: &[ProgramConfig],
+    prune: bool,
+) -> Result<SyncReport> {
+    let mut report = SyncReport::default();
+    for program_config in programs {
+        if db.get_program(&program_config.name).await?.is_some() {
+            continue;
+        }
+        let program = async {
+            let add_program_args = program_config.add_program_args()?;
+            let provider = add_program::provider(&add_program_args.update_provider()?);
+            Program::init(provider, &add_program_args, client, auth).await
+        }
+        .await;
+        match program {
+            Ok(program) => {
+                add_program::insert(db, &program).await?;
+                report.added.push(program.name);
+            }
+            Err(e) => report
+                .failed
+                .push((program_config.name.clone(), e.to_string())),
+        }
+    }
+    let mut db_programs = db.get_all_programs().await?;
+    db_programs.sort_by(|a, b| a.name.cmp(&b.name));
+    for program in db_programs {
+        if programs.iter().any(|defined| defined.name == program.name) {
+            continue;
+        }
+        if prune {
+            db.remove_program(&program.name).await?;
+            report.removed.push(program.name);
+        } else {
+            report.not_defined.push(program.name);
+        }
+    }
+    Ok(report)
+}
+
+/// Returns the settings of `run-timed` that result from environment variables and the config file.
+fn run_timed_args(config_file: Option<&ConfigFile>) -> RunTimedArgs {
+    let mut run_timed_args = RunTimedArgs::from_env();
+    if let Some(config_file) = config_file {
+        run_timed_args.apply_config_file(config_file);
+    }
+    run_timed_args
+}
+
+/// Returns the text that is shown instead of a secret.
+fn redacted(secret: Option<&str>) -> &'static str {
+    match secret {
+        Some(_) => "set (redacted)",
+        None => "not set",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::SqlitePool;
+
+    use super::{SyncReport, sync_programs};
+    use crate::{config::ProgramConfig, db, http, provider::AuthContext};
+
+    fn program(name: &str, version: &str) -> ProgramConfig {
+        ProgramConfig {
+            name: name.to_string(),
+            provider: "command".to_string(),
+            command: Some(format!("echo {version}")),
+            regex: Some(r"v[0-9.]+".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_sync_programs(pool: SqlitePool) {
+        let db = db::tests::db(pool);
+        let client = http::client();
+        let auth = AuthContext::default();
+        let mut programs = vec![
+            program("alpha_tui", "v1.7.0"),
+            program("simple_update_checker", "v0.3.0"),
+            ProgramConfig {
+                name: "invalid".to_string(),
+                provider: "github".to_string(),
+                ..Default::default()
+            },
+        ];
+        let report = sync_programs(&db, &client, &auth, &programs, false)
+            .await
+            .unwrap();
+        assert_eq!(
+            vec!["alpha_tui".to_string(), "simple_update_checker".to_string()],
+            report.added
+        );
+        assert_eq!(1, report.failed.len());
+        assert_eq!("invalid", report.failed[0].0);
+        let alpha_tui = db.get_program("alpha_tui").await.unwrap().unwrap();
+        assert_eq!("v1.7.0", alpha_tui.current_version);
+
+        // existing programs are not changed and programs that are not defined are only removed with prune
+        programs = vec![program("alpha_tui", "v1.8.0")];
+        let report = sync_programs(&db, &client, &auth, &programs, false)
+            .await
+            .unwrap();
+        assert_eq!(
+            SyncReport {
+                not_defined: vec!["simple_update_checker".to_string()],
+                ..Default::default()
+            },
+            report
+        );
+        let alpha_tui = db.get_program("alpha_tui").await.unwrap().unwrap();
+        assert_eq!("v1.7.0", alpha_tui.current_version);
+        let report = sync_programs(&db, &client, &auth, &programs, true)
+            .await
+            .unwrap();
+        assert_eq!(
+            SyncReport {
+                removed: vec!["simple_update_checker".to_string()],
+                ..Default::default()
+            },
+            report
+        );
+        assert!(
+            db.get_program("simple_update_checker")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
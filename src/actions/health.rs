@@ -0,0 +1,109 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::Result;
+use axum::{Json, Router, extract::State, http::StatusCode, routing::get};
+use serde_json::Value;
+use sqlx::types::chrono::{DateTime, Utc};
+use tokio::{net::TcpListener, sync::oneshot};
+
+use super::run_timed::CheckStatus;
+
+#[derive(Clone)]
+struct HealthState {
+    status: Arc<Mutex<CheckStatus>>,
+    check_interval: Duration,
+}
+
+/// Serves `/healthz` and `/status` on `listener` until `shutdown` receives a value or is dropped.
+pub async fn serve(
+    listener: TcpListener,
+    status: Arc<Mutex<CheckStatus>>,
+    check_interval: Duration,
+    shutdown: oneshot::Receiver<()>,
+) -> Result<()> {
+    let app = Router::new()
+        .route("/healthz", get(healthz))
+        .route("/status", get(status_json))
+        .with_state(HealthState {
+            status,
+            check_interval,
+        });
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            let _ = shutdown.await;
+        })
+        .await?;
+    Ok(())
+}
+
+async fn healthz(State(state): State<HealthState>) -> StatusCode {
+    let status = state.status.lock().unwrap();
+    if is_healthy(&status, state.check_interval, Utc::now()) {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+}
+
+async fn status_json(State(state): State<HealthState>) -> Json<Value> {
+    Json(state.status.lock().unwrap().to_json())
+}
+
+/// Checks if the last update check was completed within two check intervals before `now`.
+///
+/// Before the first update check was completed, the time at which the update checker was started is used instead.
+/// While the check interval is increased because update checks fail for all programs, the update checker is never healthy.
+fn is_healthy(status: &CheckStatus, check_interval: Duration, now: DateTime<Utc>) -> bool {
+    let last_check = status.last_check.unwrap_or(status.started);
+    status.failed_checks == 0 && last_check + check_interval * 2 >= now
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use sqlx::types::chrono::DateTime;
+
+    use crate::actions::run_timed::CheckStatus;
+
+    use super::is_healthy;
+
+    #[test]
+    fn test_is_healthy() {
+        let started = DateTime::from_timestamp(1741787100, 0).unwrap();
+        let mut status = CheckStatus::new(started);
+        let check_interval = Duration::from_secs(3600);
+        // the first check is not yet due
+        assert!(is_healthy(
+            &status,
+            check_interval,
+            started + Duration::from_secs(7200)
+        ));
+        assert!(!is_healthy(
+            &status,
+            check_interval,
+            started + Duration::from_secs(7201)
+        ));
+        status.last_check = Some(started + Duration::from_secs(7000));
+        assert!(is_healthy(
+            &status,
+            check_interval,
+            started + Duration::from_secs(14000)
+        ));
+        assert!(!is_healthy(
+            &status,
+            check_interval,
+            started + Duration::from_secs(14201)
+        ));
+        // the check interval is increased because update checks fail
+        status.failed_checks = 1;
+        assert!(!is_healthy(
+            &status,
+            check_interval,
+            started + Duration::from_secs(14000)
+        ));
+    }
+}
@@ -0,0 +1,144 @@
+//! Prevents that multiple instances of `run-timed` use the same database at the same time.
+//!
+//! The lock is an advisory lock on a lockfile next to the database that contains the PID of the instance holding it.
+//! The operating system releases the lock when the process exits, even when it was killed.
+
+use std::{
+    fs::{self, File, OpenOptions, TryLockError},
+    io::{Read, Seek, Write},
+    path::{Path, PathBuf},
+    process,
+};
+
+use anyhow::Result;
+
+/// Lock that is held while `run-timed` is running, the lockfile is removed when it is dropped.
+#[derive(Debug)]
+pub struct InstanceLock {
+    path: PathBuf,
+    // the lock is released when the file is closed
+    _file: File,
+}
+
+impl InstanceLock {
+    /// Acquires the lock for the database at `db_path`.
+    ///
+    /// Fails with a message naming the PID of the process that holds the lock.
+    /// If `force` is set, a lock that is held although the process that acquired it no longer exists is taken over.
+    pub fn acquire(db_path: &str, force: bool) -> Result<Self> {
+        let path = PathBuf::from(format!("{db_path}.lock"));
+        let file = match try_lock(&path)? {
+            Some(file) => file,
+            None => {
+                let pid = locking_pid(&path);
+                let pid_display = pid.map_or("unknown".to_string(), |pid| pid.to_string());
+                if !force || pid.is_none_or(process_exists) {
+                    anyhow::bail!(
+                        "Another instance (PID {pid_display}) is already running on this database, lockfile: {}{}",
+                        path.display(),
+                        if force {
+                            ""
+                        } else {
+                            "\nUse '--force' to take over the lock if that process no longer exists."
+                        }
+                    );
+                }
+                tracing::warn!(
+                    "Taking over the lock of process {pid_display} that no longer exists"
+                );
+                fs::remove_file(&path)?;
+                match try_lock(&path)? {
+                    Some(file) => file,
+                    None => anyhow::bail!("Unable to take over the lock {}", path.display()),
+                }
+            }
+        };
+        Ok(Self { path, _file: file })
+    }
+}
+
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.path) {
+            tracing::warn!("Unable to remove lockfile {}: {e}", self.path.display());
+        }
+    }
+}
+
+/// Opens the lockfile at `path` and locks it, `None` if it is locked by another process.
+///
+/// The PID of this process is written to the lockfile when the lock was acquired.
+fn try_lock(path: &Path) -> Result<Option<File>> {
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)?;
+    match file.try_lock() {
+        Ok(()) => (),
+        Err(TryLockError::WouldBlock) => return Ok(None),
+        Err(TryLockError::Error(e)) => return Err(e.into()),
+    }
+    file.set_len(0)?;
+    file.rewind()?;
+    write!(file, "{}", process::id())?;
+    file.flush()?;
+    Ok(Some(file))
+}
+
+/// Returns the PID that is stored in the lockfile at `path`.
+fn locking_pid(path: &Path) -> Option<u32> {
+    let mut content = String::new();
+    File::open(path).ok()?.read_to_string(&mut content).ok()?;
+    content.trim().parse().ok()
+}
+
+/// Checks if a process with `pid` exists, always `true` when this can not be determined.
+pub(super) fn process_exists(pid: u32) -> bool {
+    if cfg!(target_os = "linux") {
+        Path::new(&format!("/proc/{pid}")).exists()
+    } else {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, process};
+
+    use super::{InstanceLock, process_exists};
+
+    #[test]
+    fn test_instance_lock() {
+        let db_path = std::env::temp_dir()
+            .join(format!("simple_update_checker_lock_{}.db", process::id()))
+            .to_string_lossy()
+            .to_string();
+        let lock_path = format!("{db_path}.lock");
+        let lock = InstanceLock::acquire(&db_path, false).unwrap();
+        assert_eq!(
+            process::id().to_string(),
+            fs::read_to_string(&lock_path).unwrap()
+        );
+        let e = InstanceLock::acquire(&db_path, false).unwrap_err();
+        assert!(e.to_string().contains(&format!("PID {}", process::id())));
+        // the process holding the lock still exists
+        assert!(InstanceLock::acquire(&db_path, true).is_err());
+        drop(lock);
+        assert!(!fs::exists(&lock_path).unwrap());
+        // a lockfile that is left over but not locked does not prevent acquiring the lock
+        fs::write(&lock_path, "1234567").unwrap();
+        let lock = InstanceLock::acquire(&db_path, false).unwrap();
+        drop(lock);
+    }
+
+    #[test]
+    fn test_process_exists() {
+        assert!(process_exists(process::id()));
+        if cfg!(target_os = "linux") {
+            // larger than the maximum PID on linux
+            assert!(!process_exists(u32::MAX));
+        }
+    }
+}
@@ -1,48 +1,228 @@
-use std::process;
+use std::{
+    collections::BTreeMap,
+    io::{self, BufRead, IsTerminal, Write},
+    time::Duration,
+};
 
+use anyhow::{Context, Result};
 use sqlx::types::chrono::Utc;
-use tabled::Table;
+use tabled::{Tabled, builder::Builder, settings::Style};
 
 use crate::{
-    DbConfig, Identifier, UpdateCheckType, UpdateHistoryEntry,
-    cli::{CheckArgs, RemoveProgramArgs, UpdateArgs, UpdateCheckHistoryArgs, UpdateHistoryArgs},
-    db::Db,
-    update_check,
+    DbConfig, GithubApiConfig, Identifier, Program, Provider, Settings, UpdateCheckType,
+    UpdateHistoryEntry,
+    cli::{
+        CheckArgs, CloneProgramArgs, HistorySort, ListProgramsArgs, OpenArgs, OutputFormat,
+        ProgramField, ReadDbArgs, RefreshLatestArgs, RemoveProgramArgs, ResolveArgs, SetTokenArgs,
+        ShowProgramArgs, TableStyle, UpdateArgs, UpdateCheckHistoryArgs, UpdateHistoryArgs,
+        VerifyDbArgs, VersionHistoryArgs,
+    },
+    credentials,
+    db::{Db, DbInconsistency},
+    http,
+    provider::AuthContext,
+    update_check::{self, MessageOutput},
 };
 
 pub mod add_program;
+pub mod config;
+#[cfg(feature = "health")]
+mod health;
+mod instance_lock;
+mod pid_file;
 pub mod run_timed;
+#[cfg(feature = "systemd")]
+mod systemd;
+#[cfg(feature = "trigger")]
+mod trigger;
 
-pub async fn remove_program(db_config: DbConfig, remove_program_args: RemoveProgramArgs) {
-    let db = Db::connect(&db_config.db_path).await.unwrap();
-    if db
-        .get_program(&remove_program_args.name)
-        .await
-        .unwrap()
-        .is_none()
+/// Outcome of an action that did not fail.
+///
+/// The outcome determines the exit code of the process and the message that is printed when the action returns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActionOutcome {
+    /// The action was performed.
+    Done,
+    /// Nothing had to be done, the message explains why.
+    NothingToDo(String),
+    /// The program with this name does not exist in the database.
+    NotFound(String),
+    /// The update check found updates for this number of programs.
+    UpdatesFound(usize),
+    /// The action was performed but found problems or failed for some programs, the details are already printed.
+    Failed,
+    /// The user did not confirm the action, nothing was changed.
+    Aborted,
+}
+
+impl ActionOutcome {
+    /// Returns the exit code of the process, it is not zero when the user has to look into the output.
+    #[must_use]
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Done | Self::NothingToDo(_) | Self::NotFound(_) | Self::UpdatesFound(_) => 0,
+            Self::Failed | Self::Aborted => 1,
+        }
+    }
+
+    /// Returns the message that is printed after the action returned.
+    #[must_use]
+    pub fn message(&self) -> Option<String> {
+        match self {
+            Self::NothingToDo(message) => Some(message.clone()),
+            Self::NotFound(name) => Some(format!("Program {name} does not exist in database.")),
+            Self::Aborted => Some("Aborted, nothing was changed.".to_string()),
+            Self::Done | Self::UpdatesFound(_) | Self::Failed => None,
+        }
+    }
+}
+
+/// Connects to the database of a command that writes to the database.
+async fn connect(db_config: &DbConfig) -> Result<Db> {
+    db_config.connect().await.context("Unable to open database")
+}
+
+/// Connects to the database of a command that only reads from the database.
+///
+/// If `--no-migrate` or `--read-only` is set, the database is opened read-only and migrations are not applied.
+async fn connect_read(db_config: &DbConfig, read_db_args: &ReadDbArgs) -> Result<Db> {
+    let res = if read_db_args.no_migrate {
+        Db::connect_read_only(&db_config.db_path).await
+    } else {
+        db_config.connect().await
+    };
+    res.context("Unable to open database")
+}
+
+/// Asks the user to confirm a destructive action.
+///
+/// Returns `true` without asking when `assume_yes` is set or stdout is not a terminal, so that scripts keep working.
+fn confirm(question: &str, assume_yes: bool) -> bool {
+    if assume_yes || !io::stdout().is_terminal() {
+        return true;
+    }
+    confirm_with(question, io::stdin().lock(), io::stdout())
+}
+
+/// Writes `question` to `output` and reads the answer from `input`, only `y` and `yes` confirm the action.
+fn confirm_with(question: &str, mut input: impl BufRead, mut output: impl Write) -> bool {
+    let mut answer = String::new();
+    if write!(output, "{question} [y/N] ")
+        .and_then(|()| output.flush())
+        .and_then(|()| input.read_line(&mut answer))
+        .is_err()
     {
-        println!(
-            "Program {} did not exist in database.",
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+pub async fn remove_program(
+    db_config: DbConfig,
+    remove_program_args: RemoveProgramArgs,
+    assume_yes: bool,
+) -> Result<ActionOutcome> {
+    let db = connect(&db_config).await?;
+    if db.get_program(&remove_program_args.name).await?.is_none() {
+        return Ok(ActionOutcome::NotFound(remove_program_args.name));
+    }
+    if !confirm(
+        &format!(
+            "Are you sure that {} should be removed from the database?",
             &remove_program_args.name
-        );
-        process::exit(0);
+        ),
+        assume_yes,
+    ) {
+        return Ok(ActionOutcome::Aborted);
     }
-    db.remove_program(&remove_program_args.name).await.unwrap();
+    db.remove_program(&remove_program_args.name)
+        .await
+        .context("Unable to remove program")?;
     println!(
         "Program {} has been removed from the database.",
         &remove_program_args.name
     );
+    Ok(ActionOutcome::Done)
+}
+
+pub async fn clone_program(
+    db_config: DbConfig,
+    clone_program_args: CloneProgramArgs,
+) -> Result<ActionOutcome> {
+    let db = connect(&db_config).await?;
+    db.clone_program(&clone_program_args.name, &clone_program_args.new_name)
+        .await
+        .context("Unable to copy program")?;
+    println!(
+        "Program {} has been copied to {}.",
+        &clone_program_args.name, &clone_program_args.new_name
+    );
+    Ok(ActionOutcome::Done)
 }
 
-pub async fn list_programs(db_config: DbConfig) {
-    let db = Db::connect(&db_config.db_path).await.unwrap();
-    let mut programs = db.get_all_programs().await.unwrap();
+pub async fn list_programs(
+    db_config: DbConfig,
+    list_programs_args: ListProgramsArgs,
+    style: TableStyle,
+) -> Result<ActionOutcome> {
+    let db = connect_read(&db_config, &list_programs_args.read_db_args).await?;
+    let mut programs = match &list_programs_args.provider {
+        Some(provider) => db.get_programs_by_provider(provider).await?,
+        None => db.get_all_programs().await?,
+    };
     programs.sort_by(|a, b| a.name.cmp(&b.name));
+    if let Some(name_pattern) = &list_programs_args.name_pattern {
+        programs.retain(|program| name_pattern.is_match(&program.name));
+    }
+    let fields = list_programs_args
+        .fields
+        .as_deref()
+        .unwrap_or(&ProgramField::DEFAULT);
+    let headers = fields
+        .iter()
+        .map(|field| field.header())
+        .collect::<Vec<_>>();
+    let rows = programs
+        .iter()
+        .map(|program| {
+            fields
+                .iter()
+                .map(|field| field.display_value(program, list_programs_args.normalize_display))
+                .collect()
+        })
+        .collect::<Vec<Vec<_>>>();
+    if list_programs_args.output == OutputFormat::Csv {
+        println!(
+            "{}",
+            render_records(&headers, rows, OutputFormat::Csv, style)?
+        );
+        return Ok(ActionOutcome::Done);
+    }
     println!("The following programs are currently stored in the database:\n");
-    let table = Table::new(programs);
-    println!("{table}\n");
+    if list_programs_args.group_by_provider {
+        for (provider, rows) in group_by_provider(&programs, rows) {
+            println!("{provider} ({} programs):", rows.len());
+            println!(
+                "{}\n",
+                render_records(&headers, rows, OutputFormat::Table, style)?
+            );
+        }
+    } else {
+        println!(
+            "{}\n",
+            render_records(&headers, rows, OutputFormat::Table, style)?
+        );
+    }
+    if let Some(stale_after) = list_programs_args.stale_after
+        && print_stale_programs(&programs, stale_after)
+    {
+        println!();
+    }
+    if print_check_intervals(&programs) {
+        println!();
+    }
 
-    if let Some(last_update_check) = db.get_latest_update_check_from_history().await.unwrap() {
+    if let Some(last_update_check) = db.get_latest_update_check_from_history().await? {
         println!(
             "Last update check performed on: {} ({} update check)",
             crate::format_datetime(&last_update_check.date),
@@ -52,47 +232,445 @@ pub async fn list_programs(db_config: DbConfig) {
         println!("Last update check performed on: never");
     }
     println!("\nUse command 'check' to check all programs for updates.");
+    Ok(ActionOutcome::Done)
+}
+
+pub async fn show_program(
+    db_config: DbConfig,
+    show_program_args: ShowProgramArgs,
+) -> Result<ActionOutcome> {
+    let db = connect_read(&db_config, &show_program_args.read_db_args).await?;
+    let Some(program) = db.get_program(&show_program_args.name).await? else {
+        return Ok(ActionOutcome::NotFound(show_program_args.name));
+    };
+    println!("Name: {}", program.name);
+    println!(
+        "Current version: {} (last updated: {})",
+        program.current_version,
+        crate::format_datetime(&program.current_version_last_updated)
+    );
+    println!(
+        "Latest version: {} (last updated: {})",
+        program.latest_version,
+        crate::format_datetime(&program.latest_version_last_updated)
+    );
+    if let Some(older_upstream_version) = &program.older_upstream_version {
+        println!(
+            "Warning: {} is reported as latest version by the provider, which is older than the latest version. The release of {} was probably removed.",
+            older_upstream_version, program.latest_version
+        );
+    }
+    if program.allow_rollback {
+        println!("Rollback of latest version: allowed");
+    }
+    if let Some(pin_major) = program.pin_major {
+        println!("Pinned major version: {pin_major}");
+    }
+    if let Some(check_interval_secs) = program.check_interval_secs {
+        println!("Check interval: {check_interval_secs} seconds");
+    }
+    if let Some(last_checked) = &program.last_checked {
+        println!("Last checked: {}", crate::format_datetime(last_checked));
+    }
+    if let Some(version_constraint) = &program.version_constraint {
+        println!("Version constraint: {version_constraint}");
+    }
+    println!("Version scheme: {}", program.version_scheme);
+    println!("Provider: {}", program.provider);
+    match &program.provider {
+        Provider::Github(github) => {
+            println!("Repository: {}", github.repository);
+            println!(
+                "Required asset pattern: {}",
+                github.required_asset_pattern.as_deref().unwrap_or("none")
+            );
+            println!(
+                "Access token environment variable: {}",
+                github.access_token_env.as_deref().unwrap_or("none")
+            );
+            println!("Release selector: {}", github.release_selector);
+        }
+        Provider::GithubCommit(github_commit) => {
+            println!("Repository: {}", github_commit.repository);
+            println!(
+                "Branch: {}",
+                github_commit.branch.as_deref().unwrap_or("default branch")
+            );
+        }
+        Provider::Homebrew(homebrew) => {
+            println!("Homebrew {}: {}", homebrew.kind, homebrew.package);
+        }
+        Provider::FDroid(fdroid) => println!("F-Droid package: {}", fdroid.package),
+        Provider::Command(command) => {
+            println!("Command: {}", command.command);
+            println!("Regex: {}", command.regex);
+        }
+        Provider::Custom(custom) => {
+            println!("Settings: {}", custom.provider().serialize_config());
+        }
+    }
+    let components = db.get_components(&program.name).await?;
+    if !components.is_empty() {
+        println!("Components: {}", components.join(", "));
+    }
+    if let Some(product) = db.get_product(&program.name).await? {
+        println!("Component of: {product}");
+    }
+    Ok(ActionOutcome::Done)
+}
+
+pub async fn open(db_config: DbConfig, open_args: OpenArgs) -> Result<ActionOutcome> {
+    let db = connect(&db_config).await?;
+    let Some(program) = db.get_program(&open_args.name).await? else {
+        return Ok(ActionOutcome::NotFound(open_args.name));
+    };
+    let Some(url) = program.provider.web_url(&program.latest_version) else {
+        return Ok(ActionOutcome::NothingToDo(format!(
+            "Program {} uses provider {} which has no release page.",
+            program.name, program.provider
+        )));
+    };
+    // the url is always printed, so that it can be opened manually when no browser is available
+    println!(
+        "Release page of {} {}: {url}",
+        program.name, program.latest_version
+    );
+    webbrowser::open(&url).context("Unable to open browser")?;
+    Ok(ActionOutcome::Done)
 }
 
-pub async fn check(db_args: DbConfig, check_args: CheckArgs, github_access_token: Option<String>) {
-    let db = Db::connect(&db_args.db_path).await.unwrap();
-    let mut programs = db.get_all_programs().await.unwrap();
+pub async fn check(settings: Settings, check_args: CheckArgs) -> Result<ActionOutcome> {
+    let db = connect(&settings.db_config).await?;
+    let mut programs = match &check_args.provider {
+        Some(provider) => db.get_programs_by_provider(provider).await?,
+        None => db.get_all_programs().await?,
+    };
     programs.sort_by(|a, b| a.name.cmp(&b.name));
-    println!("Checking {} programs for updates...", programs.len());
+    if let Some(shard) = check_args.shard {
+        programs.retain(|program| shard.contains(&program.name));
+    }
+    if let Some(name_pattern) = &check_args.name_pattern {
+        programs.retain(|program| name_pattern.is_match(&program.name));
+    }
+    // the template replaces the summary line
+    let output_template = check_args.output_template.clone();
+    let summary_only = check_args.summary_only || output_template.is_some();
+    let stale_after = check_args.stale_after;
+    let concurrent = check_args.concurrent;
+    if !summary_only {
+        if settings.db_config.read_only {
+            println!("The database is opened read-only, found updates are not stored.");
+        }
+        println!("Checking {} programs for updates...", programs.len());
+    }
 
-    let programs_with_available_updates = update_check::check_for_updates(
+    let report = update_check::check_for_updates(
         &db,
+        &settings.client(),
         Some(check_args),
-        &github_access_token,
-        true,
+        &settings.auth,
+        if summary_only {
+            MessageOutput::Silent
+        } else {
+            MessageOutput::Print
+        }
+        .callback(),
         UpdateCheckType::Manual,
+        update_check::CheckOptions {
+            concurrent,
+            read_only: settings.db_config.read_only,
+            ..Default::default()
+        },
     )
-    .await
-    .unwrap();
+    .await?;
 
-    if !programs_with_available_updates.is_empty() {
+    if report.offline {
+        if summary_only {
+            println!("Checked 0 programs: network is unreachable");
+        }
+        return Ok(ActionOutcome::Failed);
+    }
+    let outcome = match report.programs_with_available_updates.len() {
+        0 => ActionOutcome::Done,
+        updates => ActionOutcome::UpdatesFound(updates),
+    };
+    if let Some(output_template) = output_template {
+        println!(
+            "{}",
+            output_template.render(
+                programs.len(),
+                report.programs_with_available_updates.len(),
+                report.errors.len(),
+                &crate::format_datetime(&Utc::now().naive_utc()),
+            )
+        );
+        return Ok(outcome);
+    }
+    if summary_only {
+        println!(
+            "Checked {} programs: {} updates available, {} {}",
+            programs.len(),
+            report.programs_with_available_updates.len(),
+            report.errors.len(),
+            if report.errors.len() == 1 {
+                "error"
+            } else {
+                "errors"
+            }
+        );
+        return Ok(outcome);
+    }
+
+    if !report.programs_with_available_updates.is_empty() {
         println!("\nSummary of programs that have updates available:\n");
-        let table = Table::new(programs_with_available_updates);
-        println!("{table}");
+        println!(
+            "{}",
+            render_table(
+                &report.programs_with_available_updates,
+                OutputFormat::Table,
+                settings.table_style
+            )?
+        );
+    }
+    if !report.updated_products.is_empty() {
+        println!("\nPrograms with multiple providers that have updates available:");
+        for (product, components) in &report.updated_products {
+            println!("{product}: {}", components.join(", "));
+        }
+    }
+    if !report.errors.is_empty() {
+        println!(
+            "\nUpdate check failed for {} programs:",
+            report.errors.len()
+        );
+        for (name, e) in report.errors {
+            println!("{name}: {e}");
+        }
+    }
+    if let Some(stale_after) = stale_after {
+        // the programs are loaded again, as the publish dates may have been updated by the update check
+        let checked_programs = db
+            .get_all_programs()
+            .await?
+            .into_iter()
+            .filter(|program| programs.iter().any(|checked| checked.name == program.name))
+            .collect::<Vec<Program>>();
+        println!();
+        print_stale_programs(&checked_programs, stale_after);
+    }
+    Ok(outcome)
+}
+
+/// Prints a note for all programs whose latest version was published longer than `stale_after` ago.
+///
+/// Returns `true` if at least one note was printed.
+fn print_stale_programs(programs: &[Program], stale_after: Duration) -> bool {
+    let now = Utc::now().naive_utc();
+    let mut found = false;
+    for program in programs
+        .iter()
+        .filter(|program| program.is_stale(stale_after, now))
+    {
+        println!(
+            "{}: latest version {} was published on {}, possibly unmaintained",
+            program.name,
+            program.latest_version,
+            crate::format_datetime(&program.latest_version_date())
+        );
+        found = true;
+    }
+    found
+}
+
+/// Renders `rows` in the format `format`, tables are drawn in the style `style`.
+///
+/// The header row contains the same column names in all formats. The result does not end with a newline.
+fn render_table<T: Tabled>(rows: &[T], format: OutputFormat, style: TableStyle) -> Result<String> {
+    render_records(
+        &T::headers(),
+        rows.iter().map(Tabled::fields),
+        format,
+        style,
+    )
+}
+
+/// Renders the header row `headers` followed by `rows` in the format `format`, see [`render_table`].
+fn render_records<H: AsRef<str>, F: AsRef<str>>(
+    headers: &[H],
+    rows: impl IntoIterator<Item = Vec<F>>,
+    format: OutputFormat,
+    style: TableStyle,
+) -> Result<String> {
+    match format {
+        OutputFormat::Table => {
+            let mut builder = Builder::default();
+            builder.push_record(headers.iter().map(AsRef::as_ref));
+            for row in rows {
+                builder.push_record(row.iter().map(AsRef::as_ref));
+            }
+            let mut table = builder.build();
+            match style {
+                TableStyle::Ascii => table.with(Style::ascii()),
+                TableStyle::Rounded => table.with(Style::rounded()),
+                TableStyle::Markdown => table.with(Style::markdown()),
+                TableStyle::Psql => table.with(Style::psql()),
+                TableStyle::Blank => table.with(Style::blank()),
+            };
+            Ok(table.to_string())
+        }
+        OutputFormat::Csv => {
+            let mut output = Vec::new();
+            write_csv_records(&mut output, headers, rows)?;
+            let output = String::from_utf8(output)?;
+            Ok(output.strip_suffix('\n').unwrap_or(&output).to_string())
+        }
     }
 }
 
-pub async fn update(db_config: DbConfig, update_args: UpdateArgs) {
-    let db = Db::connect(&db_config.db_path).await.unwrap();
-    if db.get_program(&update_args.name).await.unwrap().is_none() {
+/// Groups the rows of `programs` by the identifier of the provider of the program, the groups are sorted by provider.
+///
+/// `rows` contains one row for every program, in the same order as `programs`.
+fn group_by_provider<T>(programs: &[Program], rows: Vec<T>) -> BTreeMap<String, Vec<T>> {
+    let mut groups = BTreeMap::<String, Vec<T>>::new();
+    for (program, row) in programs.iter().zip(rows) {
+        groups
+            .entry(program.provider.identifier())
+            .or_default()
+            .push(row);
+    }
+    groups
+}
+
+/// Writes the header row `headers` followed by `rows` as csv.
+fn write_csv_records<H: AsRef<str>, F: AsRef<str>>(
+    writer: impl io::Write,
+    headers: &[H],
+    rows: impl IntoIterator<Item = Vec<F>>,
+) -> csv::Result<()> {
+    let mut writer = csv::Writer::from_writer(writer);
+    writer.write_record(headers.iter().map(|header| header.as_ref().as_bytes()))?;
+    for row in rows {
+        writer.write_record(row.iter().map(|field| field.as_ref().as_bytes()))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Prints the check interval and the time of the next check of all programs that have a check interval.
+///
+/// Returns `true` if at least one program has a check interval.
+fn print_check_intervals(programs: &[Program]) -> bool {
+    let programs = programs
+        .iter()
+        .filter(|program| program.check_interval_secs.is_some())
+        .collect::<Vec<_>>();
+    if programs.is_empty() {
+        return false;
+    }
+    println!("Check intervals (all other programs are checked on every update check):");
+    for program in programs {
+        let next_check = match program.next_check_due() {
+            Some(due) => format!("next check due on {}", crate::format_datetime(&due)),
+            None => "not yet checked".to_string(),
+        };
         println!(
-            "Unable to update current_version: Program {} does not exist in database.",
-            &update_args.name
+            "{}: every {} seconds, {next_check}",
+            program.name,
+            program.check_interval_secs.unwrap_or_default()
         );
-        process::exit(0);
     }
-    let program = db.get_program(&update_args.name).await.unwrap().unwrap();
-    if program.current_version.eq(&program.latest_version) {
+    true
+}
+
+pub async fn resolve(resolve_args: &ResolveArgs, auth: AuthContext) -> Result<ActionOutcome> {
+    let provider = add_program::provider(&resolve_args.provider);
+    let latest = provider
+        .check_for_latest_version(&http::client(), &auth)
+        .await
+        .context("Unable to resolve latest version")?;
+    println!("Latest version: {}", latest.version);
+    if let Some(published_at) = latest.published_at {
+        println!("Published at: {}", crate::format_datetime(&published_at));
+    }
+    if let Some(release_url) = provider.web_url(&latest.version) {
+        println!("Release page: {release_url}");
+    }
+    if let Some(renamed_repository) = latest.renamed_repository {
+        println!("Note: the repository was renamed to {renamed_repository}");
+    }
+    Ok(ActionOutcome::Done)
+}
+
+pub async fn refresh_latest(
+    db_config: DbConfig,
+    refresh_latest_args: RefreshLatestArgs,
+    auth: AuthContext,
+) -> Result<ActionOutcome> {
+    let db = connect(&db_config).await?;
+    let programs = match refresh_latest_args.name {
+        Some(name) => match db.get_program(&name).await? {
+            Some(program) => vec![program],
+            None => return Ok(ActionOutcome::NotFound(name)),
+        },
+        None => {
+            let mut programs = db.get_all_programs().await?;
+            programs.sort_by(|a, b| a.name.cmp(&b.name));
+            programs
+        }
+    };
+    let client = http::client();
+    let mut failed = false;
+    for program in programs {
+        let latest = match update_check::latest_version_of(&program, &client, &auth).await {
+            Ok((latest, note)) => {
+                if let Some(note) = note {
+                    println!("{note}");
+                }
+                latest
+            }
+            Err(e) => {
+                println!("{}: unable to refresh latest version: {e}", program.name);
+                failed = true;
+                continue;
+            }
+        };
+        if latest.version == program.latest_version {
+            println!(
+                "{}: latest version {} is up to date",
+                program.name, program.latest_version
+            );
+            continue;
+        }
+        db.update_latest_version(&program.name, &latest.version, Utc::now().naive_utc())
+            .await
+            .with_context(|| format!("Unable to store latest version of {}", program.name))?;
+        db.set_latest_version_published_at(&program.name, latest.published_at)
+            .await
+            .with_context(|| format!("Unable to store latest version of {}", program.name))?;
         println!(
+            "{}: latest version refreshed {} -> {}",
+            program.name, program.latest_version, latest.version
+        );
+    }
+    if failed {
+        return Ok(ActionOutcome::Failed);
+    }
+    Ok(ActionOutcome::Done)
+}
+
+pub async fn update(db_config: DbConfig, update_args: UpdateArgs) -> Result<ActionOutcome> {
+    let db = connect(&db_config).await?;
+    let Some(program) = db.get_program(&update_args.name).await? else {
+        return Ok(ActionOutcome::NotFound(update_args.name));
+    };
+    if !program
+        .version_scheme
+        .is_newer(&program.latest_version, &program.current_version)
+    {
+        return Ok(ActionOutcome::NothingToDo(format!(
             "current_version of {} is already equal to latest_version",
             &program.name
-        );
-        process::exit(0);
+        )));
     }
     db.update_current_version(
         &update_args.name,
@@ -100,7 +678,7 @@ pub async fn update(db_config: DbConfig, update_args: UpdateArgs) {
         Utc::now().naive_utc(),
     )
     .await
-    .unwrap();
+    .context("Unable to update current_version")?;
     db.insert_performed_update(&UpdateHistoryEntry {
         date: Utc::now().naive_utc(),
         name: program.name.clone(),
@@ -108,42 +686,518 @@ pub async fn update(db_config: DbConfig, update_args: UpdateArgs) {
         updated_to: program.latest_version.clone(),
     })
     .await
-    .unwrap();
+    .context("Unable to store performed update")?;
     println!(
         "current_version of {} has been updated to latest version ({})",
         &program.name, &program.latest_version
     );
+    Ok(ActionOutcome::Done)
+}
+
+pub async fn verify_db(db_config: DbConfig, verify_db_args: VerifyDbArgs) -> Result<ActionOutcome> {
+    let db = connect(&db_config).await?;
+    let inconsistencies = db.find_inconsistencies().await?;
+    if inconsistencies.is_empty() {
+        println!("No inconsistencies found.");
+        return Ok(ActionOutcome::Done);
+    }
+    println!("Found {} inconsistencies:", inconsistencies.len());
+    for inconsistency in &inconsistencies {
+        match inconsistency {
+            DbInconsistency::OrphanedProviderEntry { table, name } => {
+                println!("{name}: entry in {table} has no matching program");
+            }
+            DbInconsistency::MissingProviderEntry { name, provider } => {
+                println!("{name}: program has no entry in the table of provider {provider}");
+            }
+            DbInconsistency::UnknownProvider { name, provider } => {
+                println!("{name}: program uses unknown provider {provider}");
+            }
+        }
+    }
+    if verify_db_args.fix {
+        let removed = db.remove_orphaned_provider_entries().await?;
+        println!("\nRemoved {removed} provider entries without matching program.");
+        if inconsistencies
+            .iter()
+            .all(|i| matches!(i, DbInconsistency::OrphanedProviderEntry { .. }))
+        {
+            return Ok(ActionOutcome::Done);
+        }
+    } else {
+        println!("\nUse '--fix' to remove provider entries without matching program.");
+    }
+    Ok(ActionOutcome::Failed)
+}
+
+pub async fn rate_limit(
+    db_config: DbConfig,
+    github_api_config: GithubApiConfig,
+    style: TableStyle,
+) -> Result<ActionOutcome> {
+    let db = connect(&db_config).await?;
+    let mut github_programs = 0;
+    for provider in ["github", "githubcommit"] {
+        github_programs += db.get_programs_by_provider(provider).await?.len();
+    }
+    let rate_limits = update_check::github_rate_limits(&http::client(), &github_api_config)
+        .await
+        .context("Unable to request rate limit")?;
+    if github_api_config.access_token.is_some() {
+        println!("Using github access token.\n");
+    } else {
+        println!("No github access token is used, requests are unauthenticated.\n");
+    }
+    let core_remaining = rate_limits
+        .iter()
+        .find(|rate_limit| rate_limit.resource == "core")
+        .map(|rate_limit| rate_limit.remaining);
+    println!(
+        "{}\n",
+        render_table(&rate_limits, OutputFormat::Table, style)?
+    );
+    if let Some(core_remaining) = core_remaining
+        && core_remaining < github_programs as u64
+    {
+        println!(
+            "Remaining core rate limit ({core_remaining}) is lower than the number of programs using github ({github_programs})."
+        );
+        return Ok(ActionOutcome::Failed);
+    }
+    Ok(ActionOutcome::Done)
+}
+
+pub async fn self_version_check(auth: AuthContext) -> Result<ActionOutcome> {
+    let provider = update_check::self_provider();
+    let latest = provider
+        .check_for_latest_version(&http::client(), &auth)
+        .await
+        .context("Unable to check for a newer release")?;
+    println!("Running version: v{}", env!("CARGO_PKG_VERSION"));
+    println!("Latest release: {}", latest.version);
+    if !update_check::is_newer_than_self(&latest.version) {
+        return Ok(ActionOutcome::NothingToDo(
+            "simple_update_checker is up to date.".to_string(),
+        ));
+    }
+    println!("A newer release of simple_update_checker is available.");
+    if let Some(release_url) = provider.web_url(&latest.version) {
+        println!("Release page: {release_url}");
+    }
+    Ok(ActionOutcome::UpdatesFound(1))
 }
 
-pub async fn update_history(db_config: DbConfig, update_history_args: UpdateHistoryArgs) {
-    let db = Db::connect(&db_config.db_path).await.unwrap();
+pub async fn set_token(set_token_args: SetTokenArgs) -> Result<ActionOutcome> {
+    let token = match set_token_args.token {
+        Some(token) => token,
+        None => {
+            println!("Enter github access token:");
+            let mut token = String::new();
+            io::stdin()
+                .read_line(&mut token)
+                .context("Unable to read github access token")?;
+            token.trim().to_string()
+        }
+    };
+    if token.is_empty() {
+        anyhow::bail!("Token is empty, nothing was stored.");
+    }
+    credentials::store_github_access_token(&token)
+        .await
+        .context("Unable to store github access token in keyring")?;
+    println!("Github access token has been stored in the keyring.");
+    Ok(ActionOutcome::Done)
+}
+
+pub async fn clear_token() -> Result<ActionOutcome> {
+    let removed = credentials::clear_github_access_token()
+        .await
+        .context("Unable to remove github access token from keyring")?;
+    if !removed {
+        return Ok(ActionOutcome::NothingToDo(
+            "No github access token was stored in the keyring.".to_string(),
+        ));
+    }
+    println!("Github access token has been removed from the keyring.");
+    Ok(ActionOutcome::Done)
+}
+
+pub async fn update_history(
+    db_config: DbConfig,
+    update_history_args: UpdateHistoryArgs,
+    style: TableStyle,
+) -> Result<ActionOutcome> {
+    let db = connect_read(&db_config, &update_history_args.read_db_args).await?;
     let mut updates = db
         .get_all_updates(Some(update_history_args.max_entries))
-        .await
-        .unwrap();
-    updates.reverse();
+        .await?;
+    sort_updates(&mut updates, update_history_args.sort);
+    if update_history_args.output == OutputFormat::Table {
+        println!(
+            "Showing the latest {} performed updates:\n({})\n",
+            update_history_args.max_entries,
+            match update_history_args.sort {
+                HistorySort::DateAsc => "Newest update at the bottom",
+                HistorySort::DateDesc => "Newest update at the top",
+                HistorySort::Name => "Sorted by name",
+            }
+        );
+    }
+    println!(
+        "{}",
+        render_table(&updates, update_history_args.output, style)?
+    );
+    Ok(ActionOutcome::Done)
+}
+
+/// Sorts the entries of the update history in the order defined by `sort`.
+fn sort_updates(updates: &mut [UpdateHistoryEntry], sort: HistorySort) {
+    match sort {
+        HistorySort::DateAsc => updates.sort_by_key(|update| update.date),
+        HistorySort::DateDesc => updates.sort_by_key(|update| std::cmp::Reverse(update.date)),
+        HistorySort::Name => updates.sort_by(|a, b| a.name.cmp(&b.name).then(a.date.cmp(&b.date))),
+    }
+}
+
+pub async fn version_history(
+    db_config: DbConfig,
+    version_history_args: VersionHistoryArgs,
+    style: TableStyle,
+) -> Result<ActionOutcome> {
+    let db = connect_read(&db_config, &version_history_args.read_db_args).await?;
+    if db.get_program(&version_history_args.name).await?.is_none() {
+        return Ok(ActionOutcome::NotFound(version_history_args.name));
+    }
+    let mut versions = db
+        .get_version_history(
+            &version_history_args.name,
+            Some(version_history_args.max_entries),
+        )
+        .await?;
+    versions.reverse();
+    if version_history_args.output == OutputFormat::Table {
+        println!(
+            "Showing the latest {} versions found for {}:\n(Newest version at the bottom)\n",
+            version_history_args.max_entries, version_history_args.name
+        );
+    }
     println!(
-        "Showing the latest {} performed updates:\n(Newest update at the bottom)\n",
-        update_history_args.max_entries
+        "{}",
+        render_table(&versions, version_history_args.output, style)?
     );
-    let table = Table::new(updates);
-    println!("{table}\n");
+    Ok(ActionOutcome::Done)
 }
 
 pub async fn update_check_history(
     db_config: DbConfig,
     update_check_history_args: UpdateCheckHistoryArgs,
-) {
-    let db = Db::connect(&db_config.db_path).await.unwrap();
+    style: TableStyle,
+) -> Result<ActionOutcome> {
+    let db = connect_read(&db_config, &update_check_history_args.read_db_args).await?;
     let mut updates = db
-        .get_all_update_checks(Some(update_check_history_args.max_entries))
-        .await
-        .unwrap();
+        .get_all_update_checks(
+            Some(update_check_history_args.max_entries),
+            update_check_history_args.since_last_manual,
+        )
+        .await?;
     updates.reverse();
+    if update_check_history_args.output == OutputFormat::Table {
+        if update_check_history_args.since_last_manual {
+            println!(
+                "Showing the latest {} update checks performed since the last manual update check:\n(Newest update check at the bottom)\n",
+                update_check_history_args.max_entries
+            );
+        } else {
+            println!(
+                "Showing the latest {} performed update checks:\n(Newest update check at the bottom)\n",
+                update_check_history_args.max_entries
+            );
+        }
+    }
     println!(
-        "Showing the latest {} performed update checks:\n(Newest update check at the bottom)\n",
-        update_check_history_args.max_entries
+        "{}",
+        render_table(&updates, update_check_history_args.output, style)?
     );
-    let table = Table::new(updates);
-    println!("{table}\n");
+    Ok(ActionOutcome::Done)
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::types::chrono::NaiveDateTime;
+
+    use clap::Parser;
+
+    use crate::{
+        DbConfig, FDroidProvider, GithubProvider, Program, Provider, UpdateCheckHistoryEntry,
+        UpdateCheckType, UpdateHistoryEntry,
+        cli::{Cli, Command, HistorySort, OutputFormat, RemoveProgramArgs, TableStyle, UpdateArgs},
+        provider::AuthContext,
+    };
+
+    use super::{
+        ActionOutcome, add_program, confirm_with, group_by_provider, remove_program,
+        render_records, render_table, sort_updates, update,
+    };
+
+    #[test]
+    fn test_group_by_provider() {
+        let program = |name: &str, provider: Provider| Program::new(name, "v1.0.0", provider);
+        let github = || Provider::Github(GithubProvider::new("LMH01/alpha_tui"));
+        let fdroid = || {
+            Provider::FDroid(FDroidProvider {
+                package: "org.fdroid.fdroid".to_string(),
+            })
+        };
+        let programs = vec![
+            program("alpha_tui", github()),
+            program("fdroid", fdroid()),
+            program("simple_update_checker", github()),
+        ];
+        let rows = programs
+            .iter()
+            .map(|program| program.name.clone())
+            .collect::<Vec<_>>();
+        let groups = group_by_provider(&programs, rows);
+        assert_eq!(
+            vec!["fdroid", "github"],
+            groups.keys().map(String::as_str).collect::<Vec<_>>()
+        );
+        assert_eq!(vec!["fdroid".to_string()], groups["fdroid"]);
+        assert_eq!(
+            vec!["alpha_tui".to_string(), "simple_update_checker".to_string()],
+            groups["github"]
+        );
+    }
+
+    #[test]
+    fn test_action_outcome() {
+        assert_eq!(0, ActionOutcome::Done.exit_code());
+        assert_eq!(None, ActionOutcome::Done.message());
+        let nothing_to_do = ActionOutcome::NothingToDo("Nothing changed.".to_string());
+        assert_eq!(0, nothing_to_do.exit_code());
+        assert_eq!(
+            Some("Nothing changed.".to_string()),
+            nothing_to_do.message()
+        );
+        let not_found = ActionOutcome::NotFound("alpha_tui".to_string());
+        assert_eq!(0, not_found.exit_code());
+        assert_eq!(
+            Some("Program alpha_tui does not exist in database.".to_string()),
+            not_found.message()
+        );
+        assert_eq!(0, ActionOutcome::UpdatesFound(2).exit_code());
+        assert_eq!(None, ActionOutcome::UpdatesFound(2).message());
+        assert_eq!(1, ActionOutcome::Failed.exit_code());
+        assert_eq!(None, ActionOutcome::Failed.message());
+        assert_eq!(1, ActionOutcome::Aborted.exit_code());
+        assert!(ActionOutcome::Aborted.message().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_action_outcomes_of_program_actions() {
+        let db_path = std::env::temp_dir().join(format!(
+            "simple_update_checker_actions_{}.db",
+            std::process::id()
+        ));
+        let db_config = || DbConfig {
+            db_path: db_path.to_string_lossy().to_string(),
+            read_only: false,
+        };
+        let cli = Cli::parse_from([
+            "suc",
+            "add-program",
+            "--name",
+            "local",
+            "command",
+            "--command",
+            "echo v1.0.0",
+            "--regex",
+            "v[0-9.]+",
+        ]);
+        let Command::AddProgram(add_program_args) = cli.command else {
+            panic!("add-program was not parsed");
+        };
+        let add =
+            || add_program::add_program(db_config(), &add_program_args, AuthContext::default());
+        assert_eq!(ActionOutcome::Done, add().await.unwrap());
+        assert!(matches!(
+            add().await.unwrap(),
+            ActionOutcome::NothingToDo(_)
+        ));
+        let update_args = |name: &str| UpdateArgs {
+            name: name.to_string(),
+        };
+        assert!(matches!(
+            update(db_config(), update_args("local")).await.unwrap(),
+            ActionOutcome::NothingToDo(_)
+        ));
+        assert_eq!(
+            ActionOutcome::NotFound("missing".to_string()),
+            update(db_config(), update_args("missing")).await.unwrap()
+        );
+        let remove_program_args = |name: &str| RemoveProgramArgs {
+            name: name.to_string(),
+        };
+        assert_eq!(
+            ActionOutcome::Done,
+            remove_program(db_config(), remove_program_args("local"), true)
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            ActionOutcome::NotFound("local".to_string()),
+            remove_program(db_config(), remove_program_args("local"), true)
+                .await
+                .unwrap()
+        );
+        std::fs::remove_file(&db_path).unwrap();
+
+        // the database can not be opened, as its directory does not exist
+        let db_config = DbConfig {
+            db_path: "/simple_update_checker_missing/programs.db".to_string(),
+            read_only: false,
+        };
+        let e = update(db_config, update_args("local")).await.unwrap_err();
+        assert!(format!("{e:#}").starts_with("Unable to open database"));
+    }
+
+    #[test]
+    fn test_confirm_with() {
+        let confirm = |answer: &str| {
+            let mut output = Vec::new();
+            let confirmed = confirm_with("Remove?", answer.as_bytes(), &mut output);
+            assert_eq!("Remove? [y/N] ", String::from_utf8(output).unwrap());
+            confirmed
+        };
+        assert!(confirm("y\n"));
+        assert!(confirm("Yes\n"));
+        assert!(confirm(" YES "));
+        assert!(!confirm("n\n"));
+        assert!(!confirm("\n"));
+        assert!(!confirm("yep\n"));
+        // stdin was closed
+        assert!(!confirm(""));
+    }
+
+    #[test]
+    fn test_sort_updates() {
+        let entry = |date: &str, name: &str| UpdateHistoryEntry {
+            date: NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S").unwrap(),
+            name: name.to_string(),
+            old_version: "v1.0.0".to_string(),
+            updated_to: "v1.1.0".to_string(),
+        };
+        let mut updates = vec![
+            entry("2025-03-14 10:00:00", "alpha_tui"),
+            entry("2025-03-13 10:00:00", "simple_update_checker"),
+            entry("2025-03-12 10:00:00", "alpha_tui"),
+        ];
+        let order = |updates: &[UpdateHistoryEntry]| {
+            updates
+                .iter()
+                .map(|update| format!("{} {}", update.name, update.date.format("%d")))
+                .collect::<Vec<String>>()
+        };
+        sort_updates(&mut updates, HistorySort::DateAsc);
+        assert_eq!(
+            vec!["alpha_tui 12", "simple_update_checker 13", "alpha_tui 14"],
+            order(&updates)
+        );
+        sort_updates(&mut updates, HistorySort::DateDesc);
+        assert_eq!(
+            vec!["alpha_tui 14", "simple_update_checker 13", "alpha_tui 12"],
+            order(&updates)
+        );
+        sort_updates(&mut updates, HistorySort::Name);
+        assert_eq!(
+            vec!["alpha_tui 12", "alpha_tui 14", "simple_update_checker 13"],
+            order(&updates)
+        );
+    }
+
+    #[test]
+    fn test_render_table() {
+        let headers = ["Name", "Latest version"];
+        let rows = || {
+            vec![
+                vec!["alpha_tui", "v1.9.0"],
+                vec!["simple_update_checker", "v2.0.0"],
+            ]
+        };
+        assert_eq!(
+            "\
++-----------------------+----------------+
+| Name                  | Latest version |
++-----------------------+----------------+
+| alpha_tui             | v1.9.0         |
++-----------------------+----------------+
+| simple_update_checker | v2.0.0         |
++-----------------------+----------------+",
+            render_records(&headers, rows(), OutputFormat::Table, TableStyle::Ascii).unwrap()
+        );
+        assert_eq!(
+            "\
+| Name                  | Latest version |
+|-----------------------|----------------|
+| alpha_tui             | v1.9.0         |
+| simple_update_checker | v2.0.0         |",
+            render_records(&headers, rows(), OutputFormat::Table, TableStyle::Markdown).unwrap()
+        );
+        assert_eq!(
+            "Name,Latest version\nalpha_tui,v1.9.0\nsimple_update_checker,v2.0.0",
+            render_records(&headers, rows(), OutputFormat::Csv, TableStyle::Ascii).unwrap()
+        );
+        let update = UpdateHistoryEntry {
+            date: NaiveDateTime::parse_from_str("2025-03-12 13:45:00", "%Y-%m-%d %H:%M:%S")
+                .unwrap(),
+            name: "alpha_tui".to_string(),
+            old_version: "v1.8.0".to_string(),
+            updated_to: "v1.9.0".to_string(),
+        };
+        let table = render_table(&[update], OutputFormat::Table, TableStyle::Psql).unwrap();
+        let mut lines = table.lines();
+        assert!(
+            lines
+                .next()
+                .unwrap()
+                .contains(" Name      | Old version | Updated to ")
+        );
+        assert!(lines.next().unwrap().starts_with("-----"));
+        assert!(
+            lines
+                .next()
+                .unwrap()
+                .ends_with(" alpha_tui | v1.8.0      | v1.9.0     ")
+        );
+        assert_eq!(None, lines.next());
+    }
+
+    #[test]
+    fn test_render_table_csv() {
+        let entry = UpdateCheckHistoryEntry {
+            date: NaiveDateTime::parse_from_str("2025-03-12 13:45:00", "%Y-%m-%d %H:%M:%S")
+                .unwrap(),
+            r#type: UpdateCheckType::Timed,
+            updates_available: 2,
+            programs: "alpha_tui, simple_update_checker".to_string(),
+            duration_ms: Some(120),
+            errors: None,
+            shard: None,
+            truncated: false,
+        };
+        let output = render_table(&[entry], OutputFormat::Csv, TableStyle::default()).unwrap();
+        let mut lines = output.lines();
+        assert_eq!(
+            Some("Date,Type,Updates available,Programs,Duration (ms),Errors,Shard,Truncated"),
+            lines.next()
+        );
+        // fields that contain commas are quoted
+        assert!(
+            lines
+                .next()
+                .unwrap()
+                .ends_with(r#",timed,2,"alpha_tui, simple_update_checker",120,,,false"#)
+        );
+        assert_eq!(None, lines.next());
+    }
 }
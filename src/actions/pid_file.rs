@@ -0,0 +1,89 @@
+//! PID file for classic init systems and scripts that need to send signals to `run-timed`.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+use anyhow::Result;
+
+use super::instance_lock::process_exists;
+
+/// PID file that contains the PID of this process, the file is removed when it is dropped.
+#[derive(Debug)]
+pub struct PidFile {
+    path: PathBuf,
+}
+
+impl PidFile {
+    /// Writes the PID of this process to `path`.
+    ///
+    /// Fails when the file already contains the PID of another process that is still running.
+    /// A file left over by a process that no longer exists is overwritten.
+    pub fn create(path: &Path) -> Result<Self> {
+        if let Some(pid) = fs::read_to_string(path)
+            .ok()
+            .and_then(|content| content.trim().parse::<u32>().ok())
+            && pid != process::id()
+        {
+            if process_exists(pid) {
+                anyhow::bail!(
+                    "Another instance (PID {pid}) is already running, pid file: {}\nRemove the pid file if that process no longer exists.",
+                    path.display()
+                );
+            }
+            tracing::warn!(
+                "Overwriting pid file {} of process {pid} that no longer exists",
+                path.display()
+            );
+        }
+        if let Err(e) = fs::write(path, format!("{}\n", process::id())) {
+            anyhow::bail!("Unable to write pid file {}: {e}", path.display());
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+        })
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.path) {
+            tracing::warn!("Unable to remove pid file {}: {e}", self.path.display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, process};
+
+    use super::PidFile;
+
+    #[test]
+    fn test_pid_file() {
+        let path =
+            std::env::temp_dir().join(format!("simple_update_checker_pid_{}.pid", process::id()));
+        let pid_file = PidFile::create(&path).unwrap();
+        assert_eq!(
+            format!("{}\n", process::id()),
+            fs::read_to_string(&path).unwrap()
+        );
+        drop(pid_file);
+        assert!(!fs::exists(&path).unwrap());
+        // a pid file of a process that no longer exists is overwritten
+        if cfg!(target_os = "linux") {
+            fs::write(&path, u32::MAX.to_string()).unwrap();
+            let pid_file = PidFile::create(&path).unwrap();
+            drop(pid_file);
+            // pid 1 always exists
+            fs::write(&path, "1").unwrap();
+            let e = PidFile::create(&path).unwrap_err();
+            assert!(e.to_string().contains("PID 1"));
+            fs::remove_file(&path).unwrap();
+        }
+        // the directory of the pid file does not exist
+        assert!(PidFile::create(&path.join("suc.pid")).is_err());
+    }
+}
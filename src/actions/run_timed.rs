@@ -1,126 +1,940 @@
-use std::{process, time::Duration};
+use std::{
+    fs, io,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
-use anyhow::Result;
-use sqlx::types::chrono::Utc;
-use tabled::Table;
-use tokio::signal::unix::{SignalKind, signal};
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde_json::{Value, json};
+use sqlx::types::chrono::{DateTime, Local, NaiveDateTime, NaiveTime, Utc};
+use tokio::{
+    signal::unix::{SignalKind, signal},
+    task::JoinHandle,
+};
+use tokio_util::sync::CancellationToken;
 
 use crate::{
-    DbConfig, Program, UpdateCheckType, cli::RunTimedArgs, db::Db, notification, update_check,
+    ActiveHours, DbConfig, NotificationBackoff, Program, Settings, UpdateCheckType,
+    cli::{OutputFormat, RunTimedArgs, TableStyle},
+    config::ProgramConfig,
+    db::{Db, ProgramStore},
+    notification::Ntfy,
+    provider::AuthContext,
+    update_check::{self, CheckOptions, MessageOutput, UpdateCheckReport},
+    version::is_newer_release,
 };
 
+#[cfg(feature = "health")]
+use super::health;
+#[cfg(feature = "systemd")]
+use super::systemd;
+#[cfg(feature = "trigger")]
+use super::trigger;
+use super::{ActionOutcome, config, instance_lock::InstanceLock, pid_file::PidFile, render_table};
+
 const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Upper bound for the notification backoff interval (30 days).
+const MAX_NOTIFICATION_BACKOFF_SECS: u32 = 30 * 24 * 60 * 60;
+
+/// Result of the last completed update check, served by the health and trigger endpoints.
+#[derive(Debug, Clone)]
+#[cfg_attr(not(any(feature = "health", feature = "trigger")), allow(dead_code))]
+pub struct CheckStatus {
+    /// Time at which the update checker was started.
+    pub started: DateTime<Utc>,
+    /// Time at which the last update check was completed.
+    pub last_check: Option<DateTime<Utc>>,
+    /// Number of programs that where checked during the last update check.
+    pub programs_checked: usize,
+    /// Number of programs for which the last update check failed.
+    pub errors: usize,
+    /// Set when the last update check was skipped because the network was unreachable.
+    pub offline: bool,
+    /// Programs for which an update is available.
+    pub pending_updates: Vec<Program>,
+    /// Error of the last update check, `None` if it was successful.
+    pub last_error: Option<String>,
+    /// Number of consecutive update checks that failed for all programs, the check interval is increased while it is not zero.
+    pub failed_checks: u32,
+}
+
+impl CheckStatus {
+    #[must_use]
+    pub fn new(started: DateTime<Utc>) -> Self {
+        Self {
+            started,
+            last_check: None,
+            programs_checked: 0,
+            errors: 0,
+            offline: false,
+            pending_updates: Vec::new(),
+            last_error: None,
+            failed_checks: 0,
+        }
+    }
+
+    /// Returns the status as json, as it is returned by the http endpoints.
+    #[cfg_attr(not(any(feature = "health", feature = "trigger")), allow(dead_code))]
+    #[must_use]
+    pub fn to_json(&self) -> Value {
+        json!({
+            "started": self.started.to_rfc3339(),
+            "last_check": self.last_check.map(|last_check| last_check.to_rfc3339()),
+            "programs_checked": self.programs_checked,
+            "updates_available": self.pending_updates.len(),
+            "errors": self.errors,
+            "offline": self.offline,
+            "failed_checks": self.failed_checks,
+            "pending_updates": self
+                .pending_updates
+                .iter()
+                .map(|program| json!({
+                    "name": program.name,
+                    "current_version": program.current_version,
+                    "latest_version": program.latest_version,
+                }))
+                .collect::<Vec<Value>>(),
+        })
+    }
+
+    /// Stores the result of the update check that was completed at `now`.
+    fn record(&mut self, report: &UpdateCheckReport, now: DateTime<Utc>) {
+        self.offline = report.offline;
+        if report.offline {
+            // the update check was not completed
+            return;
+        }
+        self.last_check = Some(now);
+        self.programs_checked = report.checked;
+        self.errors = report.errors.len();
+        self.pending_updates
+            .clone_from(&report.programs_with_available_updates);
+        if report.all_failed() {
+            self.failed_checks += 1;
+        } else {
+            self.failed_checks = 0;
+        }
+    }
+
+    /// Records an update check that failed before any program was checked, e.g. because the database is unavailable.
+    fn record_failure(&mut self) {
+        self.failed_checks += 1;
+    }
+}
 
-pub async fn run(
+/// Runs the update checks, shared by the update checker loop and the trigger endpoint.
+pub struct Checker {
+    client: Client,
     db_config: DbConfig,
     run_timed_args: RunTimedArgs,
-    github_access_token: Option<String>,
-) {
+    auth: AuthContext,
+    /// Style in which the borders of the logged tables are drawn.
+    table_style: TableStyle,
+    /// Topic to which notifications are sent, `None` if notifications are disabled.
+    ntfy: Option<Ntfy>,
+    status: Arc<Mutex<CheckStatus>>,
+    /// Held while an update check is running, so that update checks never overlap.
+    running: tokio::sync::Mutex<()>,
+    /// Cancelled when the update checker is shut down.
+    cancel: CancellationToken,
+    /// Interval in which the systemd watchdog is notified, `None` if the watchdog is not enabled.
+    watchdog_interval: Option<Duration>,
+}
+
+impl Checker {
+    /// Checks all programs for updates, waits until a running update check is completed first.
+    ///
+    /// Returns `false` if the update check was skipped because the network is unreachable.
+    ///
+    /// Writes the status file afterwards, if it is configured.
+    pub async fn check(&self, update_check_type: UpdateCheckType) -> Result<bool> {
+        let running = self.running.lock().await;
+        self.check_locked(running, update_check_type).await
+    }
+
+    /// Checks all programs for updates like [`Checker::check`], `None` if an update check is already running.
+    pub async fn try_check(&self, update_check_type: UpdateCheckType) -> Option<Result<bool>> {
+        let running = self.running.try_lock().ok()?;
+        Some(self.check_locked(running, update_check_type).await)
+    }
+
+    /// Tells the systemd watchdog that the update checker is still alive, if the watchdog is enabled.
+    fn notify_watchdog(&self) {
+        #[cfg(feature = "systemd")]
+        if self.watchdog_interval.is_some() {
+            systemd::watchdog();
+        }
+    }
+
+    /// Sleeps for `duration`, returns `false` when the update checker is shut down before.
+    ///
+    /// The watchdog is notified at least once per watchdog interval while sleeping.
+    async fn sleep(&self, duration: Duration) -> bool {
+        let end = Instant::now() + duration;
+        loop {
+            self.notify_watchdog();
+            let remaining = end.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return true;
+            }
+            let chunk = self
+                .watchdog_interval
+                .map_or(remaining, |interval| remaining.min(interval));
+            if !sleep_unless_cancelled(chunk, &self.cancel).await {
+                return false;
+            }
+        }
+    }
+
+    /// Performs the update check while `_running` is held.
+    async fn check_locked(
+        &self,
+        _running: tokio::sync::MutexGuard<'_, ()>,
+        update_check_type: UpdateCheckType,
+    ) -> Result<bool> {
+        tracing::info!("Starting update check");
+        let start = Instant::now();
+        let result = check_for_updates(self, update_check_type).await;
+        let status_file = {
+            let mut status = self.status.lock().unwrap();
+            status.last_error = result.as_ref().err().map(ToString::to_string);
+            status_file_json(&status, Utc::now(), start.elapsed())
+        };
+        if let Some(path) = &self.run_timed_args.status_file
+            && let Err(e) = write_atomically(path, &status_file.to_string())
+        {
+            tracing::warn!("Unable to write status file {}: {e}", path.display());
+        }
+        result
+    }
+
+    /// Returns the result of the last update check.
+    pub fn status(&self) -> CheckStatus {
+        self.status.lock().unwrap().clone()
+    }
+}
+
+/// Periodically checks all programs for updates until a shutdown signal is received.
+///
+/// Returns an error when the update checker can not be started, main logs it.
+pub async fn run(settings: Settings, run_timed_args: RunTimedArgs) -> Result<ActionOutcome> {
+    let client = settings.client();
+    let Settings {
+        db_config,
+        auth,
+        config_programs,
+        prune,
+        table_style,
+        ..
+    } = settings;
     tracing::info!("simple_update_checker version {VERSION} starting in timed mode");
+    if db_config.read_only {
+        anyhow::bail!(
+            "run-timed can not be used with --read-only, as the results of the update checks are stored in the database"
+        );
+    }
+    // written first, so that scripts can find the process while it waits for the database
+    // removed when run returns after the shutdown signal was received
+    let _pid_file = match &run_timed_args.pid_file {
+        Some(path) => Some(PidFile::create(path)?),
+        None => None,
+    };
+    let ntfy = run_timed_args.ntfy_topic.as_deref().map(|topic| {
+        Ntfy::new(
+            run_timed_args.ntfy_server(),
+            topic,
+            run_timed_args.ntfy_email.as_deref(),
+            Duration::from_secs(u64::from(run_timed_args.notification_throttle)),
+        )
+    });
+    if ntfy.is_none() {
+        tracing::info!("No ntfy topic is set, notifications are disabled");
+    }
     // check connection with database before starting thread
     tracing::info!("Checking database connection");
-    match Db::connect(&db_config.db_path).await {
+    match connect_with_retries(
+        &db_config.db_path,
+        run_timed_args.db_connect_retries,
+        Duration::from_secs(u64::from(run_timed_args.db_connect_timeout)),
+    )
+    .await
+    {
         Err(e) => {
-            tracing::error!("Error while connecting to database: {e}");
-            process::exit(1);
+            let e = e.context("Error while connecting to database");
+            send_error_notification(&client, ntfy.as_ref(), &format!("{e:#}")).await;
+            return Err(e);
         }
         Ok(db) => {
             tracing::info!("Database connection successful. Currently watched programs:");
-            let mut programs = db.get_all_programs().await.unwrap();
+            let mut programs = db.get_all_programs().await?;
             programs.sort_by(|a, b| a.name.cmp(&b.name));
-            let table = Table::new(programs);
+            let table = render_table(&programs, OutputFormat::Table, table_style)?;
             tracing::info!("\n{table}");
         }
     }
+    // acquired after the database connection, as the lockfile is placed next to the database
+    // released when run returns after the shutdown signal was received
+    let _instance_lock = InstanceLock::acquire(&db_config.db_path, run_timed_args.force)?;
+    if let Some(config_programs) = &config_programs {
+        sync_config_programs(
+            &client,
+            &db_config,
+            &auth,
+            ntfy.as_ref(),
+            config_programs,
+            prune,
+        )
+        .await;
+    }
+
+    #[cfg(feature = "systemd")]
+    let watchdog_interval = systemd::watchdog_interval();
+    #[cfg(not(feature = "systemd"))]
+    let watchdog_interval: Option<Duration> = None;
+    if let Some(interval) = watchdog_interval {
+        tracing::info!(
+            "Notifying systemd watchdog at least every {} milliseconds",
+            interval.as_millis()
+        );
+        // no progress is reported while the latest versions are requested concurrently
+        if !run_timed_args.no_concurrent
+            && interval * 2 <= Duration::from_secs(u64::from(run_timed_args.check_deadline))
+        {
+            tracing::warn!(
+                "WatchdogSec should be longer than the check deadline ({} seconds), as the watchdog is not notified while the programs are checked concurrently",
+                run_timed_args.check_deadline
+            );
+        }
+    }
+    let status = Arc::new(Mutex::new(CheckStatus::new(Utc::now())));
+    let checker = Arc::new(Checker {
+        client,
+        db_config,
+        run_timed_args,
+        auth,
+        table_style,
+        ntfy,
+        status: status.clone(),
+        running: tokio::sync::Mutex::new(()),
+        cancel: CancellationToken::new(),
+        watchdog_interval,
+    });
+    #[cfg(feature = "health")]
+    let health_server = match checker.run_timed_args.health_listen {
+        Some(address) => {
+            let listener = tokio::net::TcpListener::bind(address)
+                .await
+                .with_context(|| format!("Unable to start health endpoint on {address}"))?;
+            tracing::info!("Serving health endpoint on {address}");
+            let (shutdown, shutdown_receiver) = tokio::sync::oneshot::channel();
+            let server = tokio::spawn(health::serve(
+                listener,
+                status.clone(),
+                Duration::from_secs(u64::from(checker.run_timed_args.check_interval())),
+                shutdown_receiver,
+            ));
+            Some((shutdown, server))
+        }
+        None => None,
+    };
+    #[cfg(feature = "trigger")]
+    let trigger_server = match checker.run_timed_args.trigger_addr {
+        Some(address) => {
+            let listener = tokio::net::TcpListener::bind(address)
+                .await
+                .with_context(|| format!("Unable to start trigger endpoint on {address}"))?;
+            tracing::info!("Serving trigger endpoint on {address}");
+            let (shutdown, shutdown_receiver) = tokio::sync::oneshot::channel();
+            let server = tokio::spawn(trigger::serve(
+                listener,
+                checker.clone(),
+                checker.run_timed_args.trigger_token.clone(),
+                shutdown_receiver,
+            ));
+            Some((shutdown, server))
+        }
+        None => None,
+    };
+
+    let worker = spawn(checker.clone());
 
-    spawn(db_config, run_timed_args, github_access_token);
+    #[cfg(feature = "systemd")]
+    systemd::ready();
 
     // setup signal handlers
     let mut sigterm =
-        signal(SignalKind::terminate()).expect("Unable to setup SIGTERM signal handler");
+        signal(SignalKind::terminate()).context("Unable to setup SIGTERM signal handler")?;
     let mut sigint =
-        signal(SignalKind::interrupt()).expect("Unable to setup SIGINT signal handler");
+        signal(SignalKind::interrupt()).context("Unable to setup SIGINT signal handler")?;
+    let mut sigusr1 =
+        signal(SignalKind::user_defined1()).context("Unable to setup SIGUSR1 signal handler")?;
 
-    // wait for signals
+    // wait for signals, SIGUSR1 starts an update check immediately
     tracing::info!("Waiting for shutdown signal");
-    tokio::select! {
-        _ = sigterm.recv() => tracing::info!("Received SIGTERM"),
-        _ = sigint.recv() => tracing::info!("Received SIGINT"),
+    loop {
+        tokio::select! {
+            _ = sigterm.recv() => {
+                tracing::info!("Received SIGTERM");
+                break;
+            }
+            _ = sigint.recv() => {
+                tracing::info!("Received SIGINT");
+                break;
+            }
+            _ = sigusr1.recv() => {
+                tracing::info!("Received SIGUSR1, starting update check");
+                let checker = checker.clone();
+                tokio::spawn(async move {
+                    match checker.try_check(UpdateCheckType::Triggered).await {
+                        None => tracing::info!("Update check is already running, ignoring SIGUSR1"),
+                        Some(Err(e)) => tracing::error!("Error while checking for updates: {e}"),
+                        Some(Ok(_)) => (),
+                    }
+                });
+            }
+        }
     }
     tracing::info!("Received shutdown signal, shutting down");
+    #[cfg(feature = "systemd")]
+    systemd::stopping();
+    checker.cancel.cancel();
+    if checker.running.try_lock().is_err() {
+        tracing::info!("Waiting for current update check to finish");
+    }
+    let shutdown_timeout = Duration::from_secs(u64::from(checker.run_timed_args.shutdown_timeout));
+    // update checks that where started by the trigger endpoint are awaited as well
+    let finished = tokio::time::timeout(shutdown_timeout, async {
+        let _ = worker.await;
+        let _ = checker.running.lock().await;
+    })
+    .await;
+    if finished.is_err() {
+        tracing::warn!(
+            "Current update check did not finish within {} seconds, shutting down anyway",
+            shutdown_timeout.as_secs()
+        );
+    }
+    #[cfg(feature = "health")]
+    if let Some((shutdown, server)) = health_server {
+        let _ = shutdown.send(());
+        match server.await {
+            Ok(Ok(())) => (),
+            Ok(Err(e)) => tracing::error!("Error in health endpoint: {e}"),
+            Err(e) => tracing::error!("Error while stopping health endpoint: {e}"),
+        }
+    }
+    #[cfg(feature = "trigger")]
+    if let Some((shutdown, server)) = trigger_server {
+        let _ = shutdown.send(());
+        match server.await {
+            Ok(Ok(())) => (),
+            Ok(Err(e)) => tracing::error!("Error in trigger endpoint: {e}"),
+            Err(e) => tracing::error!("Error while stopping trigger endpoint: {e}"),
+        }
+    }
+    Ok(ActionOutcome::Done)
 }
 
-/// Spawn the tread that periodically checks for updates
-fn spawn(db_config: DbConfig, run_timed_args: RunTimedArgs, github_access_token: Option<String>) {
+/// Spawn the tread that periodically checks for updates, it stops when the checker is cancelled.
+fn spawn(checker: Arc<Checker>) -> JoinHandle<()> {
     tokio::spawn(async move {
+        let client = &checker.client;
+        let run_timed_args = &checker.run_timed_args;
         tracing::info!(
             "Starting update checker loop, check interval: {} seconds",
-            run_timed_args.check_interval
+            run_timed_args.check_interval()
+        );
+        let (first_check, reason) = if !run_timed_args.check_on_start {
+            (
+                jittered_interval(
+                    run_timed_args.check_interval(),
+                    run_timed_args.interval_jitter,
+                    rand::random_range(-1.0..=1.0),
+                ),
+                "not checking on start",
+            )
+        } else if run_timed_args.skip_initial_check {
+            match remaining_interval(
+                last_update_check(&checker.db_config).await,
+                run_timed_args.check_interval(),
+                Utc::now().naive_utc(),
+            ) {
+                Some(remaining) => (
+                    remaining,
+                    "check interval since the last update check has not yet elapsed",
+                ),
+                None => (
+                    Duration::ZERO,
+                    "check interval since the last update check has elapsed",
+                ),
+            }
+        } else {
+            (Duration::ZERO, "checking on start")
+        };
+        let first_check = within_active_hours(
+            with_random_delay(
+                first_check + Duration::from_secs(u64::from(run_timed_args.initial_delay)),
+                run_timed_args.jitter,
+            ),
+            run_timed_args.active_hours,
+            Local::now().time(),
         );
+        tracing::info!(
+            "Starting first update check in {} seconds ({}), {reason}",
+            first_check.as_secs(),
+            next_check_time(first_check)
+        );
+        if !checker.sleep(first_check).await {
+            return;
+        }
+        // number of consecutive update checks that where skipped because the network was unreachable
+        let mut offline_checks = 0;
+        let mut previous_check_interval = run_timed_args.check_interval();
         loop {
-            tracing::info!("Starting update check");
-            if let Err(e) =
-                check_for_updates(&db_config, &run_timed_args, &github_access_token).await
-            {
-                tracing::error!("Error while checking for updates: {e}");
-                if let Err(e) = notification::send_error_notifictaion(
-                    &run_timed_args.ntfy_topic,
-                    &e.to_string(),
-                )
-                .await
-                {
-                    tracing::error!("Error while sending notification: {e}");
+            // the update check may have been delayed, e.g. when the system was suspended
+            if let Some(active_hours) = run_timed_args.active_hours {
+                let until_open = active_hours.until_open(Local::now().time());
+                if !until_open.is_zero() {
+                    tracing::info!(
+                        "Outside of active hours {active_hours}, starting next update check in {} seconds ({})",
+                        until_open.as_secs(),
+                        next_check_time(until_open)
+                    );
+                    if !checker.sleep(until_open).await {
+                        return;
+                    }
+                    continue;
                 }
             }
+            let result = checker.check(UpdateCheckType::Timed).await;
+            if checker.cancel.is_cancelled() {
+                return;
+            }
+            // increased while update checks keep failing for all programs
+            let check_interval = backoff_interval(
+                run_timed_args.check_interval(),
+                checker.status().failed_checks,
+                run_timed_args.max_backoff,
+            );
+            match result {
+                Ok(true) => offline_checks = 0,
+                Ok(false) => {
+                    offline_checks += 1;
+                    if offline_checks == run_timed_args.offline_notify_after {
+                        let message = format!(
+                            "Network was unreachable for {offline_checks} consecutive update checks"
+                        );
+                        tracing::error!("{message}");
+                        send_error_notification(client, checker.ntfy.as_ref(), &message).await;
+                    }
+                    let interval = within_active_hours(
+                        with_random_delay(
+                            Duration::from_secs(u64::from(run_timed_args.offline_retry_interval)),
+                            run_timed_args.jitter,
+                        ),
+                        run_timed_args.active_hours,
+                        Local::now().time(),
+                    );
+                    tracing::info!(
+                        "Network is unreachable, retrying update check in {} seconds ({})",
+                        interval.as_secs(),
+                        next_check_time(interval)
+                    );
+                    if !checker.sleep(interval).await {
+                        return;
+                    }
+                    continue;
+                }
+                Err(e) => {
+                    offline_checks = 0;
+                    tracing::error!("Error while checking for updates: {e}");
+                    let mut message = e.to_string();
+                    if check_interval > run_timed_args.check_interval() {
+                        let backoff =
+                            format_interval(Duration::from_secs(u64::from(check_interval)));
+                        tracing::warn!(
+                            "{} consecutive update checks failed for all programs, backing off to a check interval of {backoff}",
+                            checker.status().failed_checks
+                        );
+                        message.push_str(&format!(
+                            "\n\nUpdate checks keep failing, next attempt in {backoff}"
+                        ));
+                    }
+                    send_error_notification(client, checker.ntfy.as_ref(), &message).await;
+                }
+            }
+            if check_interval == run_timed_args.check_interval()
+                && previous_check_interval > run_timed_args.check_interval()
+            {
+                tracing::info!(
+                    "Update check no longer fails for all programs, check interval is reset to {} seconds",
+                    run_timed_args.check_interval()
+                );
+            }
+            previous_check_interval = check_interval;
+            let interval = within_active_hours(
+                with_random_delay(
+                    jittered_interval(
+                        check_interval,
+                        run_timed_args.interval_jitter,
+                        rand::random_range(-1.0..=1.0),
+                    ),
+                    run_timed_args.jitter,
+                ),
+                run_timed_args.active_hours,
+                Local::now().time(),
+            );
             tracing::info!(
-                "Starting next update check in {} seconds",
-                run_timed_args.check_interval
+                "Starting next update check in {} seconds ({})",
+                interval.as_secs(),
+                next_check_time(interval)
             );
-            tokio::time::sleep(Duration::from_secs(u64::from(
-                run_timed_args.check_interval,
-            )))
-            .await;
+            if !checker.sleep(interval).await {
+                return;
+            }
         }
-    });
+    })
+}
+
+/// Connects to the database, a failed connection is retried up to `retries` times with an increasing delay,
+/// as long as the retry would start within `timeout` after the first attempt.
+async fn connect_with_retries(db_path: &str, retries: u32, timeout: Duration) -> Result<Db> {
+    let start = Instant::now();
+    let mut attempt = 0;
+    loop {
+        let e = match Db::connect(db_path).await {
+            Ok(db) => return Ok(db),
+            Err(e) => e,
+        };
+        let delay = connect_retry_delay(attempt);
+        if attempt >= retries || start.elapsed() + delay > timeout {
+            return Err(e);
+        }
+        attempt += 1;
+        tracing::warn!(
+            "Error while connecting to database: {e}, retrying in {} seconds (retry {attempt} of {retries})",
+            delay.as_secs()
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Returns the delay before the database connection is retried after `attempt` failed retries.
+///
+/// The delay doubles with every retry, starting at one second and limited to one minute.
+fn connect_retry_delay(attempt: u32) -> Duration {
+    Duration::from_secs(2_u64.saturating_pow(attempt).min(60))
+}
+
+/// Sleeps for `duration`, returns `false` when `cancel` is cancelled before.
+async fn sleep_unless_cancelled(duration: Duration, cancel: &CancellationToken) -> bool {
+    tokio::select! {
+        () = tokio::time::sleep(duration) => true,
+        () = cancel.cancelled() => false,
+    }
+}
+
+/// Returns the content of the status file for the update check that was completed at `timestamp` and took `duration`.
+fn status_file_json(status: &CheckStatus, timestamp: DateTime<Utc>, duration: Duration) -> Value {
+    json!({
+        "timestamp": timestamp.to_rfc3339(),
+        "duration_secs": duration.as_secs_f64(),
+        "success": status.last_error.is_none() && !status.offline,
+        "programs_checked": status.programs_checked,
+        "updates_available": status.pending_updates.len(),
+        "errors": status.errors,
+        "offline": status.offline,
+        "pending_updates": status
+            .pending_updates
+            .iter()
+            .map(|program| json!({
+                "name": program.name,
+                "current_version": program.current_version,
+                "latest_version": program.latest_version,
+            }))
+            .collect::<Vec<Value>>(),
+        "last_error": status.last_error,
+    })
+}
+
+/// Writes `content` to a temporary file next to `path` and renames it to `path`,
+/// so that readers never see a partially written file.
+fn write_atomically(path: &Path, content: &str) -> io::Result<()> {
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    fs::write(&tmp_path, content)?;
+    fs::rename(&tmp_path, path)
+}
+
+/// Returns the check interval shifted by `factor` (in range -1.0 to 1.0) times `jitter_percent` percent.
+fn jittered_interval(check_interval: u32, jitter_percent: u8, factor: f64) -> Duration {
+    let jitter = f64::from(check_interval) * f64::from(jitter_percent) / 100.0 * factor;
+    Duration::from_secs_f64((f64::from(check_interval) + jitter).max(0.0).round())
+}
+
+/// Returns the date of the last update check, errors are only logged.
+async fn last_update_check(db_config: &DbConfig) -> Option<NaiveDateTime> {
+    let last_update_check = match db_config.connect().await {
+        Ok(db) => db.get_latest_update_check_from_history().await,
+        Err(e) => Err(e),
+    };
+    match last_update_check {
+        Ok(last_update_check) => last_update_check.map(|entry| entry.date),
+        Err(e) => {
+            tracing::warn!("Unable to read last update check: {e}");
+            None
+        }
+    }
+}
+
+/// Returns how long it takes at `now` until the check interval since `last_check` has elapsed.
+///
+/// `None` if there was no update check yet or the interval has already elapsed.
+fn remaining_interval(
+    last_check: Option<NaiveDateTime>,
+    check_interval: u32,
+    now: NaiveDateTime,
+) -> Option<Duration> {
+    let next_check = last_check? + Duration::from_secs(u64::from(check_interval));
+    (next_check - now)
+        .to_std()
+        .ok()
+        .filter(|remaining| !remaining.is_zero())
+}
+
+/// Extends `interval` so that the update check that is started after it at `now` falls into `active_hours`.
+fn within_active_hours(
+    interval: Duration,
+    active_hours: Option<ActiveHours>,
+    now: NaiveTime,
+) -> Duration {
+    match active_hours {
+        Some(active_hours) => interval + active_hours.until_open(now + interval),
+        None => interval,
+    }
 }
 
-async fn check_for_updates(
+/// Returns the check interval after `failed_checks` consecutive update checks that failed for all programs.
+///
+/// The interval doubles with every failed update check, limited to `max_backoff` seconds but never shorter than `check_interval`.
+fn backoff_interval(check_interval: u32, failed_checks: u32, max_backoff: u32) -> u32 {
+    check_interval
+        .saturating_mul(2_u32.saturating_pow(failed_checks))
+        .min(max_backoff)
+        .max(check_interval)
+}
+
+/// Formats `interval` in hours and minutes (e.g. `4h`, `1h 30m`), intervals below one minute in seconds.
+fn format_interval(interval: Duration) -> String {
+    let secs = interval.as_secs();
+    let (hours, minutes) = (secs / 3600, secs % 3600 / 60);
+    match (hours, minutes) {
+        (0, 0) => format!("{secs}s"),
+        (0, minutes) => format!("{minutes}m"),
+        (hours, 0) => format!("{hours}h"),
+        (hours, minutes) => format!("{hours}h {minutes}m"),
+    }
+}
+
+/// Adds a random delay of up to `jitter` seconds to `interval`.
+fn with_random_delay(interval: Duration, jitter: u32) -> Duration {
+    interval + Duration::from_secs(rand::random_range(0..=u64::from(jitter)))
+}
+
+/// Returns the formatted time at which the next update check is started, when it is started after `interval`.
+fn next_check_time(interval: Duration) -> String {
+    crate::format_datetime(&(Utc::now().naive_utc() + interval))
+}
+
+/// Sends an error notification, errors while sending are only logged.
+///
+/// Does nothing when no topic is configured.
+async fn send_error_notification(client: &Client, ntfy: Option<&Ntfy>, message: &str) {
+    let Some(ntfy) = ntfy else {
+        return;
+    };
+    if let Err(e) = ntfy.send_error_notification(client, message).await {
+        tracing::error!("Error while sending notification: {e}");
+    }
+}
+
+/// Adds the programs defined in the config file to the database, see [`config::sync_programs`].
+///
+/// Errors are logged and sent as error notification, `run-timed` continues with the programs in the database.
+async fn sync_config_programs(
+    client: &Client,
     db_config: &DbConfig,
-    run_timed_args: &RunTimedArgs,
-    github_access_token: &Option<String>,
-) -> Result<()> {
-    let db = Db::connect(&db_config.db_path).await?;
-    let mut programs = db.get_all_programs().await?;
-    programs.sort_by(|a, b| a.name.cmp(&b.name));
-    tracing::info!("Checking {} programs for updates...", programs.len());
-
-    let programs_with_available_updates = update_check::check_for_updates(
-        &db,
-        None,
-        github_access_token,
-        false,
-        UpdateCheckType::Timed,
-    )
-    .await?;
+    auth: &AuthContext,
+    ntfy: Option<&Ntfy>,
+    config_programs: &[ProgramConfig],
+    prune: bool,
+) {
+    tracing::info!("Syncing the programs defined in the config file with the database");
+    let report = match db_config.connect().await {
+        Ok(db) => config::sync_programs(&db, client, auth, config_programs, prune).await,
+        Err(e) => Err(e),
+    };
+    let message = match report {
+        Ok(report) => {
+            for name in &report.added {
+                tracing::info!("Added {name} to the database");
+            }
+            for name in &report.removed {
+                tracing::info!(
+                    "Removed {name} from the database, as it is not defined in the config file"
+                );
+            }
+            for name in &report.not_defined {
+                tracing::warn!(
+                    "{name} is not defined in the config file, set 'prune = true' to remove it from the database"
+                );
+            }
+            if report.failed.is_empty() {
+                return;
+            }
+            let failed = report
+                .failed
+                .iter()
+                .map(|(name, e)| format!("{name}: {e}"))
+                .collect::<Vec<String>>()
+                .join("\n");
+            format!("Unable to add programs defined in the config file:\n{failed}")
+        }
+        Err(e) => format!("Error while syncing the programs defined in the config file: {e}"),
+    };
+    tracing::error!("{message}");
+    send_error_notification(client, ntfy, &message).await;
+}
+
+/// Checks all programs for updates and sends a notification when updates are found.
+///
+/// Returns `false` if the update check was skipped because the network is unreachable.
+/// The result of the update check is stored in the status of `checker`.
+async fn check_for_updates(checker: &Checker, update_check_type: UpdateCheckType) -> Result<bool> {
+    let Checker {
+        client,
+        db_config,
+        run_timed_args,
+        auth,
+        table_style,
+        ntfy,
+        status,
+        cancel,
+        ..
+    } = checker;
+    let checked = async {
+        let db = db_config.connect().await?;
+        tracing::info!("Checking programs for updates...");
+
+        let report = update_check::check_for_updates(
+            &db,
+            client,
+            None,
+            auth,
+            |event| {
+                // a check that hangs stops the notifications, so that systemd restarts the update checker
+                checker.notify_watchdog();
+                MessageOutput::Log.handle(&event);
+            },
+            update_check_type,
+            CheckOptions {
+                concurrent: !run_timed_args.no_concurrent,
+                cancel: Some(cancel.clone()),
+                deadline: Some(Duration::from_secs(u64::from(
+                    run_timed_args.check_deadline,
+                ))),
+                ..Default::default()
+            },
+        )
+        .await?;
+        anyhow::Ok((db, report))
+    }
+    .await;
+    let (db, report) = match checked {
+        Ok(checked) => checked,
+        Err(e) => {
+            status.lock().unwrap().record_failure();
+            return Err(e);
+        }
+    };
+    status.lock().unwrap().record(&report, Utc::now());
+    if report.offline {
+        return Ok(false);
+    }
+    let programs_with_available_updates = report.programs_with_available_updates;
 
     let available_updates = programs_with_available_updates.len();
 
     if !programs_with_available_updates.is_empty() {
         tracing::info!("Found updates for the following programs:");
-        let table = Table::new(&programs_with_available_updates);
-        tracing::info!("\n{table}");
-        send_update_notification(
-            &db,
-            &run_timed_args.ntfy_topic,
+        let table = render_table(
             &programs_with_available_updates,
-        )
-        .await?;
+            OutputFormat::Table,
+            *table_style,
+        )?;
+        tracing::info!("\n{table}");
+        // without notifications, the programs are not marked as notification sent, so that a single notification
+        // is sent for all pending updates once a topic is configured
+        if let Some(ntfy) = ntfy {
+            send_update_notification(
+                client,
+                &db,
+                run_timed_args,
+                ntfy,
+                &programs_with_available_updates,
+            )
+            .await?;
+        }
     }
     tracing::info!("Found {} updates", available_updates);
-    Ok(())
+
+    let mut failures = Vec::new();
+    // a cancelled update check is expected on shutdown
+    if report.not_checked > 0 && !cancel.is_cancelled() {
+        failures.push(format!(
+            "Update check exceeded its deadline of {} seconds, {} programs where not checked",
+            run_timed_args.check_deadline, report.not_checked
+        ));
+    }
+    if !report.errors.is_empty() {
+        let errors = report
+            .errors
+            .iter()
+            .map(|(name, e)| format!("{name}: {e}"))
+            .collect::<Vec<String>>()
+            .join("\n");
+        failures.push(format!(
+            "Update check failed for {} programs:\n{errors}",
+            report.errors.len()
+        ));
+    }
+    if !failures.is_empty() {
+        anyhow::bail!("{}", failures.join("\n\n"));
+    }
+    Ok(true)
 }
 
-async fn send_update_notification(db: &Db, topic: &str, programs: &Vec<Program>) -> Result<()> {
+/// Sends a notification for all programs for which a notification was not yet sent.
+///
+/// If `--remind-pending` is set, all programs are included, regardless if a notification was already sent.
+///
+/// If `--notification-backoff` is set, programs for which the backoff interval is not yet over are not included,
+/// unless a version with a newer version core than the last notified version was found.
+///
+/// If `--ntfy-email` is set, ntfy also forwards the notification to it.
+async fn send_update_notification(
+    client: &Client,
+    db: &impl ProgramStore,
+    run_timed_args: &RunTimedArgs,
+    ntfy: &Ntfy,
+    programs: &Vec<Program>,
+) -> Result<()> {
+    let remind_pending = run_timed_args.remind_pending;
+    let notification_backoff = run_timed_args.notification_backoff;
+    let now = Utc::now().naive_utc();
+    let products = db.get_products().await?;
     let mut message = String::new();
     let mut programs_with_notifications_to_sent = Vec::new();
     for program in programs {
@@ -129,7 +943,7 @@ async fn send_update_notification(db: &Db, topic: &str, programs: &Vec<Program>)
             Some(notification_sent) => notification_sent,
             None => anyhow::bail!("Unable to find program {} in database", program.name),
         };
-        if notification_info.sent {
+        if notification_info.sent && !remind_pending {
             if let Some(sent_on) = notification_info.sent_on {
                 tracing::debug!(
                     "Not adding {} to notification as notification was already sent on {}",
@@ -142,10 +956,23 @@ async fn send_update_notification(db: &Db, topic: &str, programs: &Vec<Program>)
                     program.name
                 );
             }
+        } else if notification_backoff.is_some()
+            && let Some(until) = backoff_active(db, program, now).await?
+        {
+            tracing::debug!(
+                "Not adding {} to notification as notifications are suppressed until {}",
+                program.name,
+                crate::format_datetime(&until),
+            );
         } else {
+            // components are listed under the name of their product
+            let name = match products.get(&program.name) {
+                Some(product) => format!("{product}: {}", program.name),
+                None => program.name.clone(),
+            };
             message.push_str(&format!(
-                "{}: {} -> {}\n",
-                program.name, program.current_version, program.latest_version
+                "{name}: {} -> {}\n",
+                program.current_version, program.latest_version
             ));
             programs_with_notifications_to_sent.push(program);
         }
@@ -155,14 +982,17 @@ async fn send_update_notification(db: &Db, topic: &str, programs: &Vec<Program>)
             "Not sending push notifications as no updates are available for which notifications where not already sent"
         );
     } else {
-        tracing::info!("Sending push notification to topic {}", topic);
-        match notification::send_update_notification(topic, &message).await {
+        tracing::info!("Sending push notification to topic {}", ntfy.topic());
+        match ntfy.send_update_notification(client, &message).await {
             Ok(()) => {
                 // mark programs with updates available as notification sent
                 for program in programs_with_notifications_to_sent {
                     db.set_notification_sent(&program.name, true).await?;
                     db.set_notification_sent_on(&program.name, Some(Utc::now().naive_utc()))
                         .await?;
+                    if let Some(base_secs) = notification_backoff {
+                        escalate_backoff(db, program, base_secs, now).await?;
+                    }
                 }
             }
             Err(e) => {
@@ -173,3 +1003,440 @@ async fn send_update_notification(db: &Db, topic: &str, programs: &Vec<Program>)
     }
     Ok(())
 }
+
+/// Returns the end of the backoff interval of `program`, if notifications for it are currently suppressed.
+async fn backoff_active(
+    db: &impl ProgramStore,
+    program: &Program,
+    now: NaiveDateTime,
+) -> Result<Option<NaiveDateTime>> {
+    let backoff = db
+        .get_notification_backoff(&program.name)
+        .await?
+        .unwrap_or_default();
+    let Some(until) = backoff.until.filter(|until| *until > now) else {
+        return Ok(None);
+    };
+    if let Some(notified_version) = &backoff.notified_version
+        && is_newer_release(&program.latest_version, notified_version)
+    {
+        return Ok(None);
+    }
+    Ok(Some(until))
+}
+
+/// Starts a new backoff interval for `program` after a notification was sent for it.
+async fn escalate_backoff(
+    db: &impl ProgramStore,
+    program: &Program,
+    base_secs: u32,
+    now: NaiveDateTime,
+) -> Result<()> {
+    let previous = db
+        .get_notification_backoff(&program.name)
+        .await?
+        .unwrap_or_default();
+    let secs = next_backoff_secs(&previous, base_secs, now);
+    db.set_notification_backoff(
+        &program.name,
+        &NotificationBackoff {
+            until: Some(now + Duration::from_secs(u64::from(secs))),
+            secs: Some(secs),
+            notified_version: Some(program.latest_version.clone()),
+        },
+    )
+    .await
+}
+
+/// Returns the length of the next backoff interval.
+///
+/// The interval is doubled when the notification is sent before the previous interval has been over for as long as
+/// it lasted, otherwise the program is considered quiet again and the interval is reset to `base_secs`.
+fn next_backoff_secs(previous: &NotificationBackoff, base_secs: u32, now: NaiveDateTime) -> u32 {
+    match (previous.until, previous.secs) {
+        (Some(until), Some(secs)) if now < until + Duration::from_secs(u64::from(secs)) => secs
+            .saturating_mul(2)
+            .clamp(base_secs, MAX_NOTIFICATION_BACKOFF_SECS.max(base_secs)),
+        _ => base_secs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{str::FromStr, time::Duration};
+
+    use serde_json::json;
+    use sqlx::types::chrono::{DateTime, NaiveDateTime, NaiveTime};
+
+    use clap::Parser;
+    use mockito::Matcher;
+
+    use super::{
+        CheckStatus, MAX_NOTIFICATION_BACKOFF_SECS, backoff_interval, connect_retry_delay,
+        format_interval, jittered_interval, next_backoff_secs, remaining_interval,
+        send_update_notification, status_file_json, with_random_delay, within_active_hours,
+        write_atomically,
+    };
+    use crate::{
+        ActiveHours, NotificationBackoff, Program, Provider,
+        cli::RunTimedArgs,
+        db::{
+            ProgramStore,
+            memory::{MemoryStore, StaticProvider},
+        },
+        http,
+        notification::Ntfy,
+        provider::CustomProvider,
+        update_check::UpdateCheckReport,
+    };
+
+    #[test]
+    fn test_with_random_delay() {
+        let interval = Duration::from_secs(3600);
+        assert_eq!(interval, with_random_delay(interval, 0));
+        for _ in 0..100 {
+            let res = with_random_delay(interval, 60);
+            assert!(res >= interval && res <= interval + Duration::from_secs(60));
+        }
+    }
+
+    #[test]
+    fn test_remaining_interval() {
+        let now =
+            NaiveDateTime::parse_from_str("2025-03-12 13:45:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(None, remaining_interval(None, 3600, now));
+        assert_eq!(
+            Some(Duration::from_secs(600)),
+            remaining_interval(Some(now - Duration::from_secs(3000)), 3600, now)
+        );
+        assert_eq!(
+            None,
+            remaining_interval(Some(now - Duration::from_secs(3600)), 3600, now)
+        );
+        assert_eq!(
+            None,
+            remaining_interval(Some(now - Duration::from_secs(7200)), 3600, now)
+        );
+    }
+
+    #[test]
+    fn test_next_backoff_secs() {
+        let now =
+            NaiveDateTime::parse_from_str("2025-03-12 13:45:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let backoff = |ended_ago: u64, secs: u32| NotificationBackoff {
+            until: Some(now - Duration::from_secs(ended_ago)),
+            secs: Some(secs),
+            notified_version: None,
+        };
+        assert_eq!(
+            3600,
+            next_backoff_secs(&NotificationBackoff::default(), 3600, now)
+        );
+        // backoff ended recently
+        assert_eq!(7200, next_backoff_secs(&backoff(60, 3600), 3600, now));
+        // backoff ended longer ago than it lasted
+        assert_eq!(3600, next_backoff_secs(&backoff(7200, 7200), 3600, now));
+        assert_eq!(
+            MAX_NOTIFICATION_BACKOFF_SECS,
+            next_backoff_secs(&backoff(0, MAX_NOTIFICATION_BACKOFF_SECS), 3600, now)
+        );
+    }
+
+    #[test]
+    fn test_status_file_json() {
+        let started = DateTime::from_timestamp(1741787100, 0).unwrap();
+        let mut status = CheckStatus::new(started);
+        status.programs_checked = 2;
+        status.errors = 1;
+        status.pending_updates.push(
+            Program::builder()
+                .name("alacritty")
+                .current_version("v0.15.0")
+                .current_version_last_updated(started.naive_utc())
+                .latest_version("v0.15.1")
+                .latest_version_last_updated(started.naive_utc())
+                .provider(Provider::GithubCommit(crate::GithubCommitProvider {
+                    repository: "alacritty/alacritty".to_string(),
+                    branch: None,
+                }))
+                .build()
+                .unwrap(),
+        );
+        status.last_error = Some("Update check failed".to_string());
+        assert_eq!(
+            json!({
+                "timestamp": "2025-03-12T13:45:30+00:00",
+                "duration_secs": 1.5,
+                "success": false,
+                "programs_checked": 2,
+                "updates_available": 1,
+                "errors": 1,
+                "offline": false,
+                "pending_updates": [{
+                    "name": "alacritty",
+                    "current_version": "v0.15.0",
+                    "latest_version": "v0.15.1",
+                }],
+                "last_error": "Update check failed",
+            }),
+            status_file_json(
+                &status,
+                started + Duration::from_secs(30),
+                Duration::from_millis(1500)
+            )
+        );
+    }
+
+    #[test]
+    fn test_write_atomically() {
+        let path = std::env::temp_dir().join(format!(
+            "simple_update_checker_status_{}.json",
+            std::process::id()
+        ));
+        write_atomically(&path, "{}").unwrap();
+        write_atomically(&path, "{\"offline\":true}").unwrap();
+        assert_eq!(
+            "{\"offline\":true}",
+            std::fs::read_to_string(&path).unwrap()
+        );
+        let mut tmp_path = path.as_os_str().to_owned();
+        tmp_path.push(".tmp");
+        assert!(!std::path::Path::new(&tmp_path).exists());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_within_active_hours() {
+        let now = NaiveTime::from_hms_opt(20, 0, 0).unwrap();
+        let interval = Duration::from_secs(4 * 3600);
+        assert_eq!(interval, within_active_hours(interval, None, now));
+        let active_hours = ActiveHours::from_str("07:00-23:00").unwrap();
+        assert_eq!(
+            Duration::from_secs(3600),
+            within_active_hours(Duration::from_secs(3600), Some(active_hours), now)
+        );
+        // the check would start at 00:00, so it is delayed until 07:00
+        assert_eq!(
+            Duration::from_secs(11 * 3600),
+            within_active_hours(interval, Some(active_hours), now)
+        );
+        // the window is shorter than the check interval
+        let active_hours = ActiveHours::from_str("07:00-08:00").unwrap();
+        assert_eq!(
+            Duration::from_secs(24 * 3600),
+            within_active_hours(
+                interval,
+                Some(active_hours),
+                NaiveTime::from_hms_opt(7, 0, 0).unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn test_backoff_interval() {
+        assert_eq!(3600, backoff_interval(3600, 0, 86400));
+        assert_eq!(7200, backoff_interval(3600, 1, 86400));
+        assert_eq!(14400, backoff_interval(3600, 2, 86400));
+        assert_eq!(86400, backoff_interval(3600, 5, 86400));
+        assert_eq!(86400, backoff_interval(3600, 100, 86400));
+        // the maximum is shorter than the check interval
+        assert_eq!(3600, backoff_interval(3600, 2, 600));
+    }
+
+    #[test]
+    fn test_format_interval() {
+        assert_eq!("4h", format_interval(Duration::from_secs(4 * 3600)));
+        assert_eq!("1h 30m", format_interval(Duration::from_secs(5400)));
+        assert_eq!("5m", format_interval(Duration::from_secs(300)));
+        assert_eq!("45s", format_interval(Duration::from_secs(45)));
+    }
+
+    #[test]
+    fn test_failed_checks() {
+        let now = DateTime::from_timestamp(1741787100, 0).unwrap();
+        let mut status = CheckStatus::new(now);
+        let mut report = UpdateCheckReport {
+            programs_with_available_updates: Vec::new(),
+            errors: vec![(
+                "alpha_tui".to_string(),
+                crate::ProviderError::RepositoryNotFound("LMH01/alpha_tui".to_string()),
+            )],
+            offline: false,
+            checked: 1,
+            not_checked: 0,
+            updated_products: Vec::new(),
+        };
+        status.record(&report, now);
+        status.record_failure();
+        assert_eq!(2, status.failed_checks);
+        // offline update checks do not change the number of failed checks
+        report.offline = true;
+        status.record(&report, now);
+        assert_eq!(2, status.failed_checks);
+        // the update check succeeded for at least one program
+        report.offline = false;
+        report.checked = 2;
+        status.record(&report, now);
+        assert_eq!(0, status.failed_checks);
+    }
+
+    #[test]
+    fn test_programs_checked() {
+        let now = DateTime::from_timestamp(1741787100, 0).unwrap();
+        let mut status = CheckStatus::new(now);
+        // two programs where skipped or not reached before the deadline, they do not count as checked
+        let report = UpdateCheckReport {
+            programs_with_available_updates: Vec::new(),
+            errors: Vec::new(),
+            offline: false,
+            checked: 1,
+            not_checked: 2,
+            updated_products: Vec::new(),
+        };
+        status.record(&report, now);
+        assert_eq!(1, status.programs_checked);
+        assert_eq!(
+            1,
+            status_file_json(&status, now, Duration::from_secs(1))["programs_checked"]
+        );
+    }
+
+    #[test]
+    fn test_connect_retry_delay() {
+        assert_eq!(Duration::from_secs(1), connect_retry_delay(0));
+        assert_eq!(Duration::from_secs(2), connect_retry_delay(1));
+        assert_eq!(Duration::from_secs(32), connect_retry_delay(5));
+        assert_eq!(Duration::from_secs(60), connect_retry_delay(6));
+        assert_eq!(Duration::from_secs(60), connect_retry_delay(100));
+    }
+
+    #[test]
+    fn test_jittered_interval() {
+        assert_eq!(Duration::from_secs(3600), jittered_interval(3600, 0, 1.0));
+        assert_eq!(Duration::from_secs(3960), jittered_interval(3600, 10, 1.0));
+        assert_eq!(Duration::from_secs(3240), jittered_interval(3600, 10, -1.0));
+        assert_eq!(Duration::from_secs(3780), jittered_interval(3600, 10, 0.5));
+    }
+
+    async fn notification_store() -> MemoryStore {
+        let store = MemoryStore::default();
+        for name in ["alpha", "beta", "gamma"] {
+            store
+                .insert_program(
+                    &Program::builder()
+                        .name(name)
+                        .current_version("v1.0.0")
+                        .latest_version("v1.1.0")
+                        .provider(Provider::Custom(CustomProvider::new(StaticProvider(
+                            "v1.1.0".to_string(),
+                        ))))
+                        .build()
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+        }
+        store.add_component("suite", "gamma");
+        store.set_notification_sent("beta", true).await.unwrap();
+        store
+    }
+
+    #[tokio::test]
+    async fn test_send_update_notification() {
+        let store = notification_store().await;
+        let programs = store.get_all_programs().await.unwrap();
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/topic")
+            .match_body(Matcher::Exact(
+                "alpha: v1.0.0 -> v1.1.0\nsuite: gamma: v1.0.0 -> v1.1.0\n".to_string(),
+            ))
+            .with_status(200)
+            .create_async()
+            .await;
+        let ntfy = Ntfy::new(&server.url(), "topic", None, Duration::ZERO);
+        let run_timed_args =
+            RunTimedArgs::parse_from(["run-timed", "--notification-backoff", "3600"]);
+        send_update_notification(&http::client(), &store, &run_timed_args, &ntfy, &programs)
+            .await
+            .unwrap();
+        mock.assert_async().await;
+        for name in ["alpha", "gamma"] {
+            let notification_info = store.get_notification_info(name).await.unwrap().unwrap();
+            assert!(notification_info.sent);
+            assert!(notification_info.sent_on.is_some());
+            let backoff = store.get_notification_backoff(name).await.unwrap().unwrap();
+            assert_eq!(Some(3600), backoff.secs);
+            assert_eq!(Some("v1.1.0".to_string()), backoff.notified_version);
+        }
+        // the notification for beta was already sent before
+        let notification_info = store.get_notification_info("beta").await.unwrap().unwrap();
+        assert_eq!(None, notification_info.sent_on);
+        assert_eq!(None, store.get_notification_backoff("beta").await.unwrap());
+
+        // beta is reminded of again, alpha and gamma are suppressed by their backoff
+        let reminder = server
+            .mock("POST", "/topic")
+            .match_body(Matcher::Exact("beta: v1.0.0 -> v1.1.0\n".to_string()))
+            .with_status(200)
+            .create_async()
+            .await;
+        send_update_notification(
+            &http::client(),
+            &store,
+            &RunTimedArgs::parse_from([
+                "run-timed",
+                "--notification-backoff",
+                "3600",
+                "--remind-pending",
+            ]),
+            &ntfy,
+            &programs,
+        )
+        .await
+        .unwrap();
+        reminder.assert_async().await;
+        mock.assert_async().await;
+        assert!(
+            store
+                .get_notification_info("beta")
+                .await
+                .unwrap()
+                .unwrap()
+                .sent_on
+                .is_some()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_update_notification_failed() {
+        let store = notification_store().await;
+        let programs = store.get_all_programs().await.unwrap();
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/topic")
+            .with_status(403)
+            .create_async()
+            .await;
+        let ntfy = Ntfy::new(&server.url(), "topic", None, Duration::ZERO);
+        let run_timed_args =
+            RunTimedArgs::parse_from(["run-timed", "--notification-backoff", "3600"]);
+        assert!(
+            send_update_notification(&http::client(), &store, &run_timed_args, &ntfy, &programs,)
+                .await
+                .is_err()
+        );
+        // the notifications are sent again with the next update check
+        for name in ["alpha", "gamma"] {
+            assert!(
+                !store
+                    .get_notification_info(name)
+                    .await
+                    .unwrap()
+                    .unwrap()
+                    .sent
+            );
+            assert_eq!(None, store.get_notification_backoff(name).await.unwrap());
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! Notifications to systemd for services with `Type=notify`, see sd_notify(3).
+//!
+//! All functions do nothing when the update checker was not started by systemd (`NOTIFY_SOCKET` is not set).
+
+use std::{env, io, os::unix::net::UnixDatagram, process, time::Duration};
+
+/// Tells systemd that the startup is finished.
+pub fn ready() {
+    notify("READY=1");
+}
+
+/// Tells systemd that the service is still alive.
+pub fn watchdog() {
+    notify("WATCHDOG=1");
+}
+
+/// Tells systemd that the service is shutting down.
+pub fn stopping() {
+    notify("STOPPING=1");
+}
+
+/// Returns the interval in which the watchdog should be notified, half of `WatchdogSec`.
+///
+/// `None` if the watchdog is not enabled for this process.
+#[must_use]
+pub fn watchdog_interval() -> Option<Duration> {
+    // the watchdog applies to a different process, e.g. when the update checker was started by a script
+    if let Ok(pid) = env::var("WATCHDOG_PID")
+        && pid.parse::<u32>().ok() != Some(process::id())
+    {
+        return None;
+    }
+    let usec = env::var("WATCHDOG_USEC").ok()?.parse::<u64>().ok()?;
+    (usec > 0).then(|| Duration::from_micros(usec / 2))
+}
+
+/// Sends `state` to the socket in `NOTIFY_SOCKET`, errors are only logged.
+fn notify(state: &str) {
+    let Ok(socket_path) = env::var("NOTIFY_SOCKET") else {
+        return;
+    };
+    if let Err(e) = send(&socket_path, state) {
+        tracing::warn!("Unable to notify systemd ({state}): {e}");
+    }
+}
+
+/// Sends `state` as a single datagram to the unix socket at `socket_path`.
+///
+/// Paths starting with `@` refer to a socket in the abstract namespace.
+fn send(socket_path: &str, state: &str) -> io::Result<()> {
+    let socket = UnixDatagram::unbound()?;
+    #[cfg(target_os = "linux")]
+    if let Some(name) = socket_path.strip_prefix('@') {
+        use std::os::{linux::net::SocketAddrExt, unix::net::SocketAddr};
+        socket.send_to_addr(state.as_bytes(), &SocketAddr::from_abstract_name(name)?)?;
+        return Ok(());
+    }
+    socket.send_to(state.as_bytes(), socket_path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::net::UnixDatagram;
+
+    use super::send;
+
+    #[test]
+    fn test_send() {
+        let path = std::env::temp_dir().join(format!(
+            "simple_update_checker_notify_{}.sock",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let socket = UnixDatagram::bind(&path).unwrap();
+        for state in ["READY=1", "WATCHDOG=1", "STOPPING=1"] {
+            send(&path.to_string_lossy(), state).unwrap();
+            let mut buf = [0; 64];
+            let len = socket.recv(&mut buf).unwrap();
+            assert_eq!(state.as_bytes(), &buf[..len]);
+        }
+        let _ = std::fs::remove_file(&path);
+        // the socket no longer exists
+        assert!(send(&path.to_string_lossy(), "READY=1").is_err());
+    }
+}
@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use axum::{
+    Json, Router,
+    extract::State,
+    http::{HeaderMap, StatusCode, header},
+    routing::post,
+};
+use serde_json::{Value, json};
+use tokio::{net::TcpListener, sync::oneshot};
+
+use super::run_timed::Checker;
+use crate::UpdateCheckType;
+
+#[derive(Clone)]
+struct TriggerState {
+    checker: Arc<Checker>,
+    token: Option<String>,
+}
+
+/// Serves `POST /check` on `listener` until `shutdown` receives a value or is dropped.
+///
+/// If `token` is set, requests are only accepted when they send it as bearer token.
+pub async fn serve(
+    listener: TcpListener,
+    checker: Arc<Checker>,
+    token: Option<String>,
+    shutdown: oneshot::Receiver<()>,
+) -> Result<()> {
+    let app = Router::new()
+        .route("/check", post(check))
+        .with_state(TriggerState { checker, token });
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            let _ = shutdown.await;
+        })
+        .await?;
+    Ok(())
+}
+
+async fn check(State(state): State<TriggerState>, headers: HeaderMap) -> (StatusCode, Json<Value>) {
+    if !is_authorized(&headers, state.token.as_deref()) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "error": "missing or invalid bearer token" })),
+        );
+    }
+    tracing::info!("Update check was triggered");
+    let Some(result) = state.checker.try_check(UpdateCheckType::Triggered).await else {
+        return (
+            StatusCode::CONFLICT,
+            Json(json!({ "error": "an update check is already running" })),
+        );
+    };
+    let mut report = state.checker.status().to_json();
+    match result {
+        Ok(_) => (StatusCode::OK, Json(report)),
+        Err(e) => {
+            tracing::error!("Error while checking for updates: {e}");
+            report["error"] = json!(e.to_string());
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(report))
+        }
+    }
+}
+
+/// Checks if the `Authorization` header contains `token` as bearer token, always `true` when no token is required.
+fn is_authorized(headers: &HeaderMap, token: Option<&str>) -> bool {
+    let Some(token) = token else {
+        return true;
+    };
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|sent| sent == token)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{HeaderMap, HeaderValue, header};
+
+    use super::is_authorized;
+
+    #[test]
+    fn test_is_authorized() {
+        let mut headers = HeaderMap::new();
+        assert!(is_authorized(&headers, None));
+        assert!(!is_authorized(&headers, Some("secret")));
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer secret"),
+        );
+        assert!(is_authorized(&headers, None));
+        assert!(is_authorized(&headers, Some("secret")));
+        assert!(!is_authorized(&headers, Some("other")));
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("secret"));
+        assert!(!is_authorized(&headers, Some("secret")));
+    }
+}
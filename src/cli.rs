@@ -1,13 +1,161 @@
-use clap::{Parser, Subcommand};
+use std::{
+    collections::BTreeSet,
+    env,
+    ffi::{OsStr, OsString},
+    fs,
+    path::PathBuf,
+    sync::{Mutex, PoisonError},
+    time::Duration,
+};
 
-use crate::config::ConfigFile;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand, parser::ValueSource};
+use globset::{Glob, GlobMatcher};
+use sqlx::types::chrono::NaiveDateTime;
+
+use crate::{
+    ActiveHours, OutputTemplate, Program, ReleaseSelector, Secret, Shard, VersionConstraint,
+    VersionScheme,
+    config::{ConfigFile, SecretRef},
+};
+
+/// Prefix of the environment variables that set options.
+pub const ENV_PREFIX: &str = "SUC_";
+
+/// Environment variables that where used before [`ENV_PREFIX`] was introduced, together with their new name.
+///
+/// Options that where added later never had a variable without prefix.
+const LEGACY_ENV_VARS: [(&str, &str); 7] = [
+    ("SUC_DB_PATH", "DB_PATH"),
+    ("SUC_GITHUB_ACCESS_TOKEN", "GITHUB_ACCESS_TOKEN"),
+    ("SUC_NTFY_TOPIC", "NTFY_TOPIC"),
+    ("SUC_CHECK_INTERVAL", "CHECK_INTERVAL"),
+    ("SUC_SET_CURRENT_VERSION", "SET_CURRENT_VERSION"),
+    ("SUC_ALLOW_NOTIFICATION", "ALLOW_NOTIFICATION"),
+    ("SUC_CONFIG", "SIMPLE_UPDATE_CHECKER_CONFIG"),
+];
+
+/// Deprecated environment variables for which a warning was already logged.
+static WARNED_LEGACY_ENV_VARS: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// Returns the deprecated name of the environment variable `name`, that was used before [`ENV_PREFIX`] was introduced.
+fn legacy_env_var(name: &str) -> Option<&str> {
+    LEGACY_ENV_VARS
+        .iter()
+        .find(|(new, _)| *new == name)
+        .map(|(_, legacy)| *legacy)
+}
+
+/// Changes the environment variable of the options of `command` and its subcommands to the deprecated variable, when
+/// only the deprecated variable is set. The changed variables are added to `legacy_env_vars` together with their new
+/// name.
+fn use_legacy_env_vars(
+    command: clap::Command,
+    legacy_env_vars: &mut Vec<(String, String)>,
+) -> clap::Command {
+    let mut command = command.mut_args(|arg| {
+        let Some((legacy, new)) = arg
+            .get_env()
+            .and_then(OsStr::to_str)
+            .and_then(|new| Some((legacy_env_var(new)?.to_string(), new.to_string())))
+        else {
+            return arg;
+        };
+        if env::var_os(&new).is_some() || env::var_os(&legacy).is_none() {
+            return arg;
+        }
+        legacy_env_vars.push((legacy.clone(), new));
+        arg.env(legacy)
+    });
+    let subcommands = command
+        .get_subcommands()
+        .map(|subcommand| subcommand.get_name().to_string())
+        .collect::<Vec<_>>();
+    for subcommand in subcommands {
+        command = command.mut_subcommand(subcommand, |subcommand| {
+            use_legacy_env_vars(subcommand, legacy_env_vars)
+        });
+    }
+    command
+}
+
+/// Logs a warning for each deprecated environment variable in `legacy_env_vars`, see [`Cli::parse_env`].
+///
+/// The warning for a variable is only logged once, although the environment variables are parsed again by
+/// [`RunTimedArgs::from_env`].
+fn warn_legacy_env_vars(legacy_env_vars: &[(String, String)]) {
+    let mut warned = WARNED_LEGACY_ENV_VARS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    for (legacy, new) in legacy_env_vars {
+        if warned.insert(legacy.clone()) {
+            tracing::warn!(
+                "The environment variable {legacy} is deprecated and will be removed in the next release, use {new} instead"
+            );
+        }
+    }
+}
+
+/// Checks if the environment variable `name` sets the value of an option of `command` or of the selected subcommand.
+fn sets_value(command: &clap::Command, matches: &ArgMatches, name: &str) -> bool {
+    command.get_arguments().any(|arg| {
+        arg.get_env() == Some(OsStr::new(name))
+            && matches.value_source(arg.get_id().as_str()) == Some(ValueSource::EnvVariable)
+    }) || matches.subcommand().is_some_and(|(subcommand, matches)| {
+        command
+            .find_subcommand(subcommand)
+            .is_some_and(|subcommand| sets_value(subcommand, matches, name))
+    })
+}
+
+/// Identifiers of all providers, used to filter programs by provider.
+const PROVIDERS: [&str; 5] = ["github", "githubcommit", "homebrew", "fdroid", "command"];
+
+/// Parses a glob pattern like `work-*` that is matched against program names.
+fn parse_name_pattern(s: &str) -> anyhow::Result<GlobMatcher> {
+    Ok(Glob::new(s)?.compile_matcher())
+}
+
+/// Parses a short duration like `10m` into a duration.
+///
+/// Supported units are `s` (seconds), `m` (minutes), `h` (hours) and `d` (days).
+fn parse_max_age(s: &str) -> anyhow::Result<Duration> {
+    let s = s.trim();
+    let (value, unit) = s.split_at(s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len()));
+    let value: u64 = value.parse()?;
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => anyhow::bail!("Unknown unit '{unit}', supported units are s, m, h and d"),
+    };
+    Ok(Duration::from_secs(value * seconds))
+}
+
+/// Parses an age like `180d` into a duration.
+///
+/// Supported units are `h` (hours), `d` (days), `w` (weeks) and `y` (years of 365 days).
+fn parse_age(s: &str) -> anyhow::Result<Duration> {
+    let s = s.trim();
+    let (value, unit) = s.split_at(s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len()));
+    let value: u64 = value.parse()?;
+    let hours = match unit {
+        "h" => 1,
+        "d" => 24,
+        "w" => 7 * 24,
+        "y" => 365 * 24,
+        _ => anyhow::bail!("Unknown unit '{unit}', supported units are h, d, w and y"),
+    };
+    Ok(Duration::from_secs(value * hours * 60 * 60))
+}
 
 #[derive(Parser, Debug)]
 #[command(
     author = "LMH01",
     version,
     about,
-    long_about = "Simple program that can be used to automatically check for updates of programs. Optionally allows to send push notifications using ntfy.sh when an update is found."
+    long_about = "Simple program that can be used to automatically check for updates of programs. Optionally allows to send push notifications using ntfy.sh when an update is found.",
+    after_help = "Options can also be set with environment variables prefixed with SUC_, the variable of each option is shown in its help (e.g. SUC_DB_PATH for --db-path).\nThe previous variables DB_PATH, GITHUB_ACCESS_TOKEN, NTFY_TOPIC, CHECK_INTERVAL, SET_CURRENT_VERSION, ALLOW_NOTIFICATION and SIMPLE_UPDATE_CHECKER_CONFIG are deprecated and will be removed in the next release."
 )]
 pub struct Cli {
     #[command(subcommand)]
@@ -16,23 +164,157 @@ pub struct Cli {
     #[command(flatten)]
     pub db_args: DbArgs,
 
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Path of the config file that is used instead of '~/.config/simple_update_checker/config.toml'. Fails if the file does not exist or can not be parsed.",
+        env = "SUC_CONFIG"
+    )]
+    pub config: Option<PathBuf>,
+
     #[arg(
         short,
         long,
         help = "Set to increase rate limit of github api.\nSee https://github.com/settings/personal-access-tokens",
-        env
+        env = "SUC_GITHUB_ACCESS_TOKEN"
+    )]
+    pub github_access_token: Option<Secret>,
+
+    #[arg(
+        long,
+        value_name = "TOKEN",
+        value_delimiter = ',',
+        help = "Access tokens that are used in turn, when the rate limit of the github api is exceeded for the current token. Can be set multiple times.",
+        env = "SUC_GITHUB_ACCESS_TOKEN_FALLBACK"
+    )]
+    pub github_access_token_fallback: Vec<Secret>,
+
+    #[arg(
+        long,
+        help = "Maximum number of pages that are requested when a list of releases is fetched from the github api.",
+        default_value = "5",
+        env = "SUC_GITHUB_MAX_PAGES"
+    )]
+    pub github_max_pages: u32,
+
+    #[arg(
+        long,
+        help = "Update the stored repository of a program when the github repository was renamed or transferred.",
+        default_value_t = true,
+        action = clap::ArgAction::Set,
+        env = "SUC_AUTO_FOLLOW_RENAMES"
+    )]
+    pub auto_follow_renames: bool,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "Timeout of a single http request of 'check' and 'run-timed' in seconds. If not set, requests do not time out.",
+        env = "SUC_REQUEST_TIMEOUT"
+    )]
+    pub request_timeout: Option<u64>,
+
+    #[arg(
+        short = 'y',
+        long,
+        global = true,
+        help = "Do not ask for confirmation before destructive actions, like removing programs. No confirmation is asked when stdout is not a terminal."
+    )]
+    pub assume_yes: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Style in which the borders of tables are drawn.",
+        default_value = "ascii",
+        env = "SUC_TABLE_STYLE"
     )]
-    pub github_access_token: Option<String>,
+    pub table_style: TableStyle,
 }
 
 impl Cli {
+    /// Parses the cli from the command line arguments and the environment variables.
+    ///
+    /// Options whose `SUC_*` environment variable is not set are read from the deprecated variable without prefix,
+    /// a warning is logged for each deprecated variable that sets a value. Exits when the arguments are invalid.
+    #[must_use]
+    pub fn parse_env() -> Self {
+        let (cli, legacy_env_vars) =
+            Self::try_parse_env_from(env::args_os()).unwrap_or_else(|e| e.exit());
+        warn_legacy_env_vars(&legacy_env_vars);
+        cli
+    }
+
+    /// Parses the cli from `args` and the environment variables, see [`Cli::parse_env`].
+    ///
+    /// Returns the cli and the deprecated environment variables that set a value, together with their new name.
+    fn try_parse_env_from<I, T>(args: I) -> Result<(Self, Vec<(String, String)>), clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let mut legacy_env_vars = Vec::new();
+        let mut command = use_legacy_env_vars(Self::command(), &mut legacy_env_vars);
+        let matches = command.try_get_matches_from_mut(args)?;
+        legacy_env_vars.sort();
+        legacy_env_vars.dedup();
+        legacy_env_vars.retain(|(legacy, _)| sets_value(&command, &matches, legacy));
+        Ok((Self::from_arg_matches(&matches)?, legacy_env_vars))
+    }
 
     /// Applies the values set in the provided config file.
-    /// 
+    ///
     /// If a value is defined in the cli and in the config file, the value provided by the cli will take precedence.
-    pub fn apply_config_file(&mut self, config_file: ConfigFile) {
-        if self.github_access_token.is_none() && config_file.github_access_token.is_some() {
-            self.github_access_token = config_file.github_access_token;
+    /// If a profile is selected, the values of the profile section take precedence over the top level values of the config file.
+    /// The github access token is read from `github_access_token_file` only when no token is set otherwise.
+    /// Secrets referenced by the config file are resolved here, so that they are read only once at startup.
+    pub fn apply_config_file(&mut self, config_file: &ConfigFile) {
+        match &mut self.command {
+            Command::AddProgram(add_program_args) => {
+                add_program_args.default_provider = config_file.default_provider.clone();
+            }
+            Command::RunTimed(run_timed_args) => run_timed_args.apply_config_file(config_file),
+            _ => (),
+        }
+        if self.request_timeout.is_none() {
+            self.request_timeout = config_file.request_timeout;
+        }
+        let profile_github_access_token = self
+            .db_args
+            .profile
+            .as_ref()
+            .and_then(|profile| config_file.profiles.get(profile))
+            .and_then(|profile| profile.github_access_token.as_ref());
+        if self.github_access_token.is_none() {
+            self.github_access_token = profile_github_access_token
+                .or(config_file.github_access_token.as_ref())
+                .and_then(config_secret)
+                .or_else(|| {
+                    let path = config_file.github_access_token_file.as_ref()?;
+                    match fs::read_to_string(path) {
+                        Ok(token) => Some(Secret::from(token.trim().to_string())),
+                        Err(e) => {
+                            println!(
+                                "Warning: unable to read github access token from {}: {e}",
+                                path.display()
+                            );
+                            None
+                        }
+                    }
+                });
+        }
+    }
+}
+
+/// Resolves a value of the config file that can reference a secret, see [`SecretRef`].
+///
+/// Prints a warning and returns `None` if the secret can not be read.
+fn config_secret(secret: &SecretRef) -> Option<Secret> {
+    match secret.resolve() {
+        Ok(secret) => Some(secret),
+        Err(e) => {
+            println!("Warning: unable to resolve value of config file: {e}");
+            None
         }
     }
 }
@@ -43,23 +325,70 @@ pub enum Command {
         about = "Add a program to the database that should be checked for updates. Sets the latest version to the latest version currently available.",
         subcommand_value_name = "PROVIDER"
     )]
-    AddProgram(AddProgramArgs),
+    AddProgram(Box<AddProgramArgs>),
     #[command(
         about = "Remove a program from the database that should no longer be checked for updates.",
         subcommand_value_name = "PROVIDER"
     )]
     RemoveProgram(RemoveProgramArgs),
+    #[command(
+        about = "Copy a program together with its provider settings to a new name.",
+        long_about = "Copy a program together with its provider settings to a new name. The current version of the new program is set to its latest version."
+    )]
+    CloneProgram(CloneProgramArgs),
+    #[command(
+        about = "Add the programs defined in the config file to the database.",
+        long_about = "Add the programs defined in the [[programs]] sections of the config file to the database. Programs that are in the database but not in the config file are only reported, unless 'prune = true' is set in the config file. Then they are removed."
+    )]
+    SyncConfig,
     #[command(about = "Lists all programs that are checked for updates.")]
-    ListPrograms,
+    ListPrograms(ListProgramsArgs),
+    #[command(about = "Show all stored information about a single program.")]
+    ShowProgram(ShowProgramArgs),
+    #[command(about = "Open the release page of the latest version of a program in the browser.")]
+    Open(OpenArgs),
     #[command{
         about = "Check all programs once for updates.",
         long_about = "Check all programs once for updates. Does not send a push notification when updates are found."
     }]
     Check(CheckArgs),
+    #[command(
+        about = "Refresh the latest version of programs without recording any history.",
+        long_about = "Refresh the latest version of programs without recording any history. Unlike 'check' the current version is never changed, no notification state is changed and no history entries are added, e.g. to refresh the programs of a restored database."
+    )]
+    RefreshLatest(RefreshLatestArgs),
+    #[command(
+        about = "Print the latest version that a provider reports, without adding a program.",
+        long_about = "Print the latest version that a provider reports, without adding a program. The database is not used, e.g. to test a provider configuration before adding a program with it.",
+        subcommand_value_name = "PROVIDER"
+    )]
+    Resolve(ResolveArgs),
     #[command(about = "Update current_version of a program to the currently found latest_version.")]
     Update(UpdateArgs),
+    #[command(
+        about = "Check the database for inconsistencies, e.g. after it was edited manually.",
+        long_about = "Check the database for inconsistencies, e.g. after it was edited manually. Reports provider entries without a matching program, programs without provider entry and programs with an unknown provider."
+    )]
+    VerifyDb(VerifyDbArgs),
+    #[command(
+        about = "Store the github access token in the system keyring.",
+        long_about = "Store the github access token in the system keyring. The token is used when '--github-access-token' is not set. If the token is not provided as argument, it is read from stdin."
+    )]
+    SetToken(SetTokenArgs),
+    #[command(about = "Remove the github access token from the system keyring.")]
+    ClearToken,
+    #[command(
+        about = "Show the remaining github api rate limit.",
+        long_about = "Show the remaining github api rate limit. Exits with a non zero exit code when the remaining core rate limit is lower than the number of programs that use github as provider."
+    )]
+    RateLimit,
     #[command(about = "Show the history of performed updates.")]
     UpdateHistory(UpdateHistoryArgs),
+    #[command(
+        about = "Show the versions of a program that where found by update checks.",
+        long_about = "Show the versions of a program that where found by update checks, to see how often new versions are released. Unlike 'update-history' this includes versions that where not installed."
+    )]
+    VersionHistory(VersionHistoryArgs),
     #[command(about = "Show the history of performed updates checks.")]
     UpdateCheckHistory(UpdateCheckHistoryArgs),
     #[command{
@@ -67,15 +396,226 @@ pub enum Command {
         long_about = "Periodically check all programs for updates. Sends a push notification when updates are found and the ntfy.sh topic is configured."
     }]
     RunTimed(RunTimedArgs),
+    #[command(
+        about = "Check if a newer release of simple_update_checker exists.",
+        long_about = "Check if a newer release of simple_update_checker exists. The latest release on github is compared with the version of the running binary."
+    )]
+    SelfVersionCheck,
+    #[command(
+        about = "Validate the config file or show the settings that are used.",
+        subcommand_value_name = "COMMAND"
+    )]
+    Config(ConfigArgs),
 }
 
 #[derive(Parser, Debug, Clone)]
 pub struct AddProgramArgs {
     #[command(subcommand)]
-    pub provider: UpdateProviderAdd,
+    pub provider: Option<UpdateProviderAdd>,
+
+    #[command(flatten)]
+    pub default_provider_args: DefaultProviderArgs,
+
+    /// Provider that is used when no provider subcommand is given, set by `default_provider` in the config file.
+    #[arg(skip)]
+    pub default_provider: Option<String>,
 
     #[arg(short, long, help = "Display name for the program")]
     pub name: String,
+
+    #[arg(
+        long,
+        value_name = "PROGRAM",
+        help = "Add the program as additional provider of an existing program, e.g. the server of a suite of programs.\nThe existing program is reported as updated when any of its components has an update."
+    )]
+    pub component_of: Option<String>,
+
+    #[arg(
+        long,
+        help = "Only consider versions with this major version as updates.\nA note is shown when a newer major version is available."
+    )]
+    pub pin_major: Option<u32>,
+
+    #[arg(
+        long,
+        conflicts_with = "pin_major",
+        help = "Only consider versions that satisfy this constraint as latest version.\nEither a semantic version requirement (e.g. '^1' or '>=1.2, <2') or a glob pattern (e.g. 'v1.*-lts')."
+    )]
+    pub version_constraint: Option<VersionConstraint>,
+
+    #[arg(
+        long,
+        help = "Defines how versions are compared to decide if a found version is newer.\nWith 'none' every version that differs from the stored version is treated as update.",
+        default_value = "none"
+    )]
+    pub version_scheme: VersionScheme,
+
+    #[arg(
+        long,
+        help = "When the found version is older than the latest version (e.g. because the release was removed), set the latest version back to the found version.\nRequires a version scheme other than 'none'."
+    )]
+    pub allow_rollback: bool,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "Only check the program for updates when it was last checked at least this many seconds ago.\nWhen not set, the program is checked on every update check."
+    )]
+    pub check_interval: Option<u32>,
+}
+
+impl AddProgramArgs {
+    /// Returns the provider subcommand, or the default provider created from the default provider options when no subcommand is given.
+    pub fn update_provider(&self) -> anyhow::Result<UpdateProviderAdd> {
+        if let Some(provider) = &self.provider {
+            if let Some(arg) = self.default_provider_args.set_args().first() {
+                anyhow::bail!(
+                    "'--{arg}' can only be used without a provider subcommand, pass it after the subcommand instead."
+                );
+            }
+            return Ok(provider.clone());
+        }
+        let Some(default_provider) = &self.default_provider else {
+            anyhow::bail!(
+                "No provider given, use one of the provider subcommands or set 'default_provider' in the config file."
+            );
+        };
+        self.default_provider_args.update_provider(default_provider)
+    }
+}
+
+/// Options of the provider subcommands that can be used when the provider is omitted and the default provider is used.
+#[derive(clap::Args, Debug, Clone, Default)]
+#[command(next_help_heading = "Default provider options")]
+pub struct DefaultProviderArgs {
+    #[arg(short, long, help = "Github repository (github, githubcommit)")]
+    pub repository: Option<String>,
+
+    #[arg(
+        long,
+        help = "Glob pattern that at least one asset of a release has to match (github)"
+    )]
+    pub required_asset_pattern: Option<String>,
+
+    #[arg(
+        long,
+        help = "Name of the environment variable from which the github access token is read (github)"
+    )]
+    pub access_token_env: Option<String>,
+
+    #[arg(
+        long,
+        help = "Defines which release is used as latest version (github)"
+    )]
+    pub release_selector: Option<ReleaseSelector>,
+
+    #[arg(
+        short,
+        long,
+        help = "Branch of which the latest commit is used (githubcommit)"
+    )]
+    pub branch: Option<String>,
+
+    #[arg(short, long, help = "Name or id of the package (homebrew, fdroid)")]
+    pub package: Option<String>,
+
+    #[arg(long, help = "The package is a cask instead of a formula (homebrew)")]
+    pub cask: bool,
+
+    #[arg(
+        short,
+        long,
+        help = "Shell command that prints the latest version (command)"
+    )]
+    pub command: Option<String>,
+
+    #[arg(
+        long,
+        help = "Regex that is applied to the output of the command (command)"
+    )]
+    pub regex: Option<String>,
+}
+
+impl DefaultProviderArgs {
+    /// Returns the names of all options that are set.
+    fn set_args(&self) -> Vec<&'static str> {
+        [
+            ("repository", self.repository.is_some()),
+            (
+                "required-asset-pattern",
+                self.required_asset_pattern.is_some(),
+            ),
+            ("access-token-env", self.access_token_env.is_some()),
+            ("release-selector", self.release_selector.is_some()),
+            ("branch", self.branch.is_some()),
+            ("package", self.package.is_some()),
+            ("cask", self.cask),
+            ("command", self.command.is_some()),
+            ("regex", self.regex.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(arg, set)| set.then_some(arg))
+        .collect()
+    }
+
+    /// Creates the provider subcommand for `provider` from the options.
+    ///
+    /// Fails when an option that `provider` requires is missing or when an option is set that `provider` does not use.
+    fn update_provider(&self, provider: &str) -> anyhow::Result<UpdateProviderAdd> {
+        let allowed_args: &[&str] = match provider {
+            "github" => &[
+                "repository",
+                "required-asset-pattern",
+                "access-token-env",
+                "release-selector",
+            ],
+            "githubcommit" | "github-commit" => &["repository", "branch"],
+            "homebrew" => &["package", "cask"],
+            "fdroid" | "f-droid" => &["package"],
+            "command" => &["command", "regex"],
+            _ => anyhow::bail!(
+                "Unknown default provider '{provider}' in config file, possible values: {}",
+                PROVIDERS.join(", ")
+            ),
+        };
+        if let Some(arg) = self
+            .set_args()
+            .into_iter()
+            .find(|arg| !allowed_args.contains(arg))
+        {
+            anyhow::bail!("'--{arg}' can not be used with the default provider '{provider}'.");
+        }
+        let required = |value: &Option<String>, arg: &str| {
+            value.clone().ok_or_else(|| {
+                anyhow::anyhow!(
+                    "'--{arg}' is required when the default provider '{provider}' is used."
+                )
+            })
+        };
+        Ok(match provider {
+            "github" => UpdateProviderAdd::Github(AddGithubProgramArgs {
+                repository: required(&self.repository, "repository")?,
+                required_asset_pattern: self.required_asset_pattern.clone(),
+                access_token_env: self.access_token_env.clone(),
+                release_selector: self.release_selector.unwrap_or_default(),
+            }),
+            "homebrew" => UpdateProviderAdd::Homebrew(AddHomebrewProgramArgs {
+                package: required(&self.package, "package")?,
+                cask: self.cask,
+            }),
+            "fdroid" | "f-droid" => UpdateProviderAdd::FDroid(AddFDroidProgramArgs {
+                package: required(&self.package, "package")?,
+            }),
+            "command" => UpdateProviderAdd::Command(AddCommandProgramArgs {
+                command: required(&self.command, "command")?,
+                regex: required(&self.regex, "regex")?,
+            }),
+            _ => UpdateProviderAdd::GithubCommit(AddGithubCommitProgramArgs {
+                repository: required(&self.repository, "repository")?,
+                branch: self.branch.clone(),
+            }),
+        })
+    }
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -94,6 +634,27 @@ pub enum UpdateProviderAdd {
         about = "Use Github as provider for update information"
     }]
     Github(AddGithubProgramArgs),
+    #[command{
+        about = "Use the latest commit of a Github repository as version",
+        long_about = "Use the latest commit of a Github repository as version. The short commit hash is stored as version, every new commit is reported as update.",
+        alias = "githubcommit"
+    }]
+    GithubCommit(AddGithubCommitProgramArgs),
+    #[command{
+        about = "Use the stable version of a Homebrew formula or cask as version"
+    }]
+    Homebrew(AddHomebrewProgramArgs),
+    #[command{
+        about = "Use the version of an android app in the F-Droid repository as version",
+        long_about = "Use the version of an android app in the F-Droid repository as version. The version that is suggested by F-Droid is used.",
+        alias = "fdroid"
+    }]
+    FDroid(AddFDroidProgramArgs),
+    #[command{
+        about = "Use the output of a local command as version",
+        long_about = "Use the output of a local command as version. The command is run with 'sh -c' on every update check, only add commands you trust as they are run with the permissions of the update checker."
+    }]
+    Command(AddCommandProgramArgs),
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -104,86 +665,1096 @@ pub struct AddGithubProgramArgs {
         help = "Github repository where the program can be found and where the latest version is taken from"
     )]
     pub repository: String,
+
+    #[arg(
+        long,
+        help = "Glob pattern (e.g. '*linux-x86_64*') that at least one asset of a release has to match.\nReleases without a matching asset are not reported as update until the asset is uploaded."
+    )]
+    pub required_asset_pattern: Option<String>,
+
+    #[arg(
+        long,
+        help = "Name of the environment variable from which the github access token for this repository should be read.\nThe token is used instead of '--github-access-token'."
+    )]
+    pub access_token_env: Option<String>,
+
+    #[arg(
+        long,
+        help = "Defines which release is used as latest version.\n'latest' uses the release github marks as latest, 'newest' the newest release including pre-releases and 'newest-stable' the newest release that is not a pre-release.",
+        default_value = "latest"
+    )]
+    pub release_selector: ReleaseSelector,
 }
 
 #[derive(Parser, Debug, Clone)]
-pub struct CheckArgs {
-    #[arg{
+pub struct AddGithubCommitProgramArgs {
+    #[arg(
         short,
         long,
-        help = "When set, the newest found version will also be set as current version.",
-        env
-    }]
-    pub set_current_version: bool,
+        help = "Github repository of which the latest commit is used as version"
+    )]
+    pub repository: String,
 
-    #[arg{
+    #[arg(
         short,
         long,
-        help = "Normally notifications are not sent in run-timed mode for updates that where seen manually.\nSet this flag to not mark the update as seen and to make the notification get sent when run-timed mode is used the next time.",
-        env
-    }]
-    pub allow_notification: bool,
+        help = "Branch of which the latest commit is used.\nIf not set, the default branch of the repository is used."
+    )]
+    pub branch: Option<String>,
 }
 
 #[derive(Parser, Debug, Clone)]
-pub struct UpdateArgs {
+pub struct AddHomebrewProgramArgs {
+    #[arg(short, long, help = "Name of the Homebrew formula or cask")]
+    pub package: String,
+
+    #[arg(long, help = "The package is a cask instead of a formula.")]
+    pub cask: bool,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct AddCommandProgramArgs {
     #[arg(
         short,
         long,
-        help = "Name of the program for which the current_version should be set to latest_version."
+        help = "Shell command that prints the latest version (e.g. 'kubectl version --client')"
     )]
-    pub name: String,
-}
+    pub command: String,
 
-#[derive(Parser, Debug, Clone)]
-pub struct UpdateHistoryArgs {
     #[arg(
         short,
         long,
-        help = "How many entries should be shown at max.",
-        default_value = "20"
+        help = "Regex that is applied to the output of the command.\nThe first capture group is used as version, or the whole match if the regex has no capture group."
     )]
-    pub max_entries: u32,
+    pub regex: String,
 }
 
 #[derive(Parser, Debug, Clone)]
-pub struct UpdateCheckHistoryArgs {
+pub struct AddFDroidProgramArgs {
+    #[arg(short, long, help = "Package id of the app (e.g. org.fdroid.fdroid)")]
+    pub package: String,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct CloneProgramArgs {
+    #[arg(short, long, help = "Name of the program that should be copied")]
+    pub name: String,
+
+    #[arg(long, help = "Name of the new program")]
+    pub new_name: String,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct ListProgramsArgs {
     #[arg(
-        short,
         long,
-        help = "How many entries should be shown at max.",
-        default_value = "20"
+        help = "Only list programs that use this provider.",
+        value_parser = PROVIDERS
     )]
-    pub max_entries: u32,
-}
+    pub provider: Option<String>,
 
-#[derive(Parser, Debug, Clone)]
-pub struct RunTimedArgs {
-    #[arg{
-        short,
+    #[arg(
         long,
-        help = "Topic under which the update checks should be published.",
-        env
-    }]
-    pub ntfy_topic: String,
+        value_name = "PATTERN",
+        value_parser = parse_name_pattern,
+        help = "Only list programs whose name matches this glob pattern (e.g. 'work-*')."
+    )]
+    pub name_pattern: Option<GlobMatcher>,
+
+    #[arg(
+        long,
+        value_name = "AGE",
+        value_parser = parse_age,
+        help = "Show a note for programs whose latest version was published longer ago than this age (e.g. 180d), as they are possibly unmaintained.\nSupported units are h (hours), d (days), w (weeks) and y (years)."
+    )]
+    pub stale_after: Option<Duration>,
+
+    #[arg(
+        long,
+        help = "Format in which the entries are printed.",
+        default_value = "table"
+    )]
+    pub output: OutputFormat,
+
+    #[arg(
+        long,
+        value_name = "FIELDS",
+        value_delimiter = ',',
+        help = "Comma separated list of the columns that should be listed (e.g. 'name,latest_version,status'). If not set, name, versions and provider are listed."
+    )]
+    pub fields: Option<Vec<ProgramField>>,
+
+    #[arg(
+        long,
+        help = "Print a separate table for each provider. Has no effect when the output format is csv."
+    )]
+    pub group_by_provider: bool,
+
     #[arg(
-        env,
-        short,
         long,
-        help = "Interval in which the update check should be run. Time in seconds.",
-        default_value = "3600",
-        env
+        help = "Show versions without a leading 'v' (e.g. '1.2.3' instead of 'v1.2.3'), so that versions of different providers look the same. Only the output is changed, the stored versions are not modified."
     )]
-    pub check_interval: u32,
+    pub normalize_display: bool,
+
+    #[command(flatten)]
+    pub read_db_args: ReadDbArgs,
 }
 
 #[derive(Parser, Debug, Clone)]
-pub struct DbArgs {
-    #[arg{
-        short,
-        long,
-        help = "Path where 'programs.db' is located that contains the programs that should be checked for updates and their latest versions. If not set and config file not existing will default to 'programs.db'.",
-        env
-    }]
-    pub db_path: Option<String>,
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub command: ConfigCommand,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ConfigCommand {
+    #[command(
+        about = "Check the config file for problems.",
+        long_about = "Check the config file for problems: syntax and type errors, unknown keys and referenced files or environment variables that do not exist. Exits with a non zero exit code when a problem is found."
+    )]
+    Validate(ConfigValidateArgs),
+    #[command(
+        about = "Show the settings that result from cli, environment variables and config file.",
+        long_about = "Show the settings that result from cli, environment variables and config file. Secrets like the github access token are not printed."
+    )]
+    Show,
+    #[command(
+        about = "Write a config file that contains all supported keys.",
+        long_about = "Write a config file that contains all supported keys with their defaults and commented out examples. The file is written to the path set with --config or to the default location of the config file."
+    )]
+    Init(ConfigInitArgs),
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct ConfigInitArgs {
+    #[arg(long, help = "Overwrite the config file when it already exists.")]
+    pub force: bool,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct ConfigValidateArgs {
+    #[arg(long, help = "Also check if the ntfy server can be reached.")]
+    pub check_connectivity: bool,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct ShowProgramArgs {
+    #[arg(short, long, help = "Name of the program that should be shown")]
+    pub name: String,
+
+    #[command(flatten)]
+    pub read_db_args: ReadDbArgs,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct SetTokenArgs {
+    #[arg(help = "The github access token that should be stored")]
+    pub token: Option<String>,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct OpenArgs {
+    #[arg(
+        short,
+        long,
+        help = "Name of the program for which the release page should be opened"
+    )]
+    pub name: String,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct CheckArgs {
+    #[arg{
+        short,
+        long,
+        help = "When set, the newest found version will also be set as current version.",
+        env = "SUC_SET_CURRENT_VERSION"
+    }]
+    pub set_current_version: bool,
+
+    #[arg{
+        short,
+        long,
+        help = "Normally notifications are not sent in run-timed mode for updates that where seen manually.\nSet this flag to not mark the update as seen and to make the notification get sent when run-timed mode is used the next time.",
+        env = "SUC_ALLOW_NOTIFICATION"
+    }]
+    pub allow_notification: bool,
+
+    #[arg(
+        long,
+        help = "Only print a single line with the number of checked programs, available updates and errors."
+    )]
+    pub summary_only: bool,
+
+    #[arg(
+        long,
+        value_name = "TEMPLATE",
+        conflicts_with = "summary_only",
+        help = "Only print this template, e.g. '{pending}/{total} updates' for status bars. The placeholders {total} (checked programs), {pending} (available updates), {errors} (failed programs) and {timestamp} (time of the update check) are replaced."
+    )]
+    pub output_template: Option<OutputTemplate>,
+
+    #[arg(
+        long,
+        help = "Only check a part of all programs, in the format INDEX/COUNT (e.g. 1/4).\nPrograms are assigned to a shard by their name, so multiple runs with different shards check all programs."
+    )]
+    pub shard: Option<Shard>,
+
+    #[arg(
+        long,
+        help = "Only check programs that use this provider.",
+        value_parser = PROVIDERS
+    )]
+    pub provider: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "PATTERN",
+        value_parser = parse_name_pattern,
+        help = "Only check programs whose name matches this glob pattern (e.g. 'work-*')."
+    )]
+    pub name_pattern: Option<GlobMatcher>,
+
+    #[arg(
+        long,
+        value_name = "AGE",
+        value_parser = parse_age,
+        help = "Show a note for programs whose latest version was published longer ago than this age (e.g. 180d), as they are possibly unmaintained.\nSupported units are h (hours), d (days), w (weeks) and y (years)."
+    )]
+    pub stale_after: Option<Duration>,
+
+    #[arg(
+        long,
+        value_name = "AGE",
+        value_parser = parse_max_age,
+        help = "Skip programs that where checked for updates less than this age ago (e.g. 10m), even when --force is set.\nSupported units are s (seconds), m (minutes), h (hours) and d (days)."
+    )]
+    pub max_age: Option<Duration>,
+
+    #[arg(
+        long,
+        overrides_with = "no_concurrent",
+        help = "Check the programs concurrently, up to 8 at the same time. Notes that are shown while checking a program can then be printed out of order."
+    )]
+    pub concurrent: bool,
+
+    #[arg(
+        long,
+        overrides_with = "concurrent",
+        help = "Check the programs one after another, this is the default."
+    )]
+    pub no_concurrent: bool,
+
+    #[arg(
+        long,
+        help = "Check all programs, even those whose check interval has not yet passed."
+    )]
+    pub force: bool,
+
+    #[arg(
+        long,
+        help = "Only check programs for which the last update check failed, e.g. after network or configuration issues where fixed."
+    )]
+    pub failed_only: bool,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct ResolveArgs {
+    #[command(subcommand)]
+    pub provider: UpdateProviderAdd,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[group(required = true, multiple = false)]
+pub struct RefreshLatestArgs {
+    #[arg(long, help = "Refresh the latest version of all programs.")]
+    pub all: bool,
+
+    #[arg(
+        short,
+        long,
+        help = "Name of the program for which the latest version should be refreshed."
+    )]
+    pub name: Option<String>,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct UpdateArgs {
+    #[arg(
+        short,
+        long,
+        help = "Name of the program for which the current_version should be set to latest_version."
+    )]
+    pub name: String,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct VerifyDbArgs {
+    #[arg(
+        short,
+        long,
+        help = "Remove provider entries for which no matching program exists."
+    )]
+    pub fix: bool,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct UpdateHistoryArgs {
+    #[arg(
+        short,
+        long,
+        help = "How many entries should be shown at max.",
+        default_value = "20"
+    )]
+    pub max_entries: u32,
+
+    #[arg(
+        long,
+        help = "Order in which the entries are printed, 'name' groups the entries by program.",
+        default_value = "date-asc"
+    )]
+    pub sort: HistorySort,
+
+    #[arg(
+        long,
+        help = "Format in which the entries are printed.",
+        default_value = "table"
+    )]
+    pub output: OutputFormat,
+
+    #[command(flatten)]
+    pub read_db_args: ReadDbArgs,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct VersionHistoryArgs {
+    #[arg(
+        short,
+        long,
+        help = "Name of the program for which the found versions should be shown"
+    )]
+    pub name: String,
+
+    #[arg(
+        short,
+        long,
+        help = "How many entries should be shown at max.",
+        default_value = "20"
+    )]
+    pub max_entries: u32,
+
+    #[arg(
+        long,
+        help = "Format in which the entries are printed.",
+        default_value = "table"
+    )]
+    pub output: OutputFormat,
+
+    #[command(flatten)]
+    pub read_db_args: ReadDbArgs,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct UpdateCheckHistoryArgs {
+    #[arg(
+        short,
+        long,
+        help = "How many entries should be shown at max.",
+        default_value = "20"
+    )]
+    pub max_entries: u32,
+
+    #[arg(
+        long,
+        help = "Only show the update checks that where performed after the latest manual update check, e.g. to review what run-timed did since then."
+    )]
+    pub since_last_manual: bool,
+
+    #[arg(
+        long,
+        help = "Format in which the entries are printed.",
+        default_value = "table"
+    )]
+    pub output: OutputFormat,
+
+    #[command(flatten)]
+    pub read_db_args: ReadDbArgs,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct RunTimedArgs {
+    #[arg{
+        short,
+        long,
+        help = "Topic under which the update checks should be published. If not set, no notifications are sent and the update checker only keeps the database up to date.",
+        env = "SUC_NTFY_TOPIC"
+    }]
+    pub ntfy_topic: Option<String>,
+    #[arg(
+        long,
+        value_name = "EMAIL",
+        help = "Let ntfy forward the notifications to this email address, in addition to publishing them to the topic.",
+        requires = "ntfy_topic",
+        env = "SUC_NTFY_EMAIL"
+    )]
+    pub ntfy_email: Option<String>,
+    #[arg(
+        long,
+        help = "Server to which the notifications are sent. If not set, https://ntfy.sh is used.",
+        env = "SUC_NTFY_SERVER"
+    )]
+    pub ntfy_server: Option<String>,
+    #[arg(
+        short,
+        long,
+        help = "Interval in which the update check should be run. Time in seconds. If not set, 3600 is used.",
+        env = "SUC_CHECK_INTERVAL"
+    )]
+    pub check_interval: Option<u32>,
+    #[arg(
+        long,
+        help = "Randomly shift each check interval by up to this many percent (in both directions), to avoid many instances hitting the apis at the same time.",
+        default_value = "0",
+        value_parser = clap::value_parser!(u8).range(0..=100),
+        env = "SUC_INTERVAL_JITTER"
+    )]
+    pub interval_jitter: u8,
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "Add a random delay of up to this many seconds before each update check, including the first one, to avoid many instances that where started at the same time hitting the apis at the same time.",
+        default_value = "0",
+        env = "SUC_JITTER"
+    )]
+    pub jitter: u32,
+    #[arg(
+        long,
+        help = "Whether the first update check should be performed directly after starting. If set to false, the first check is performed after one check interval.",
+        default_value_t = true,
+        action = clap::ArgAction::Set,
+        env = "SUC_CHECK_ON_START"
+    )]
+    pub check_on_start: bool,
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "Wait this many seconds before the first update check.",
+        default_value = "0",
+        env = "SUC_INITIAL_DELAY"
+    )]
+    pub initial_delay: u32,
+    #[arg(
+        long,
+        help = "Only perform the first update check directly after starting, when the last update check is longer ago than the check interval. Otherwise the first check is performed when the check interval since the last update check has elapsed.",
+        env = "SUC_SKIP_INITIAL_CHECK"
+    )]
+    pub skip_initial_check: bool,
+    #[arg(
+        long,
+        value_name = "HH:MM-HH:MM",
+        help = "Only check for updates within this time window (local time), e.g. '07:00-23:00'. Update checks that would start outside of the window are delayed until the window opens. The window may cross midnight, e.g. '22:00-06:00'.",
+        env = "SUC_ACTIVE_HOURS"
+    )]
+    pub active_hours: Option<ActiveHours>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Write the result of every update check as json to this file. The file is replaced atomically and contains:\n'timestamp': time at which the update check was completed (RFC 3339)\n'duration_secs': duration of the update check in seconds\n'success': true when the update check was completed without errors\n'programs_checked': number of programs that where checked\n'updates_available': number of programs with an available update\n'errors': number of programs for which the update check failed\n'offline': true when the update check was skipped because the network was unreachable\n'pending_updates': list of programs with an available update, each with 'name', 'current_version' and 'latest_version'\n'last_error': error of the update check or null",
+        env = "SUC_STATUS_FILE"
+    )]
+    pub status_file: Option<PathBuf>,
+    #[arg(
+        short,
+        long,
+        help = "Send a notification for all programs where the current version is behind the latest version on every check, even when a notification for that version was already sent or the update was seen manually.",
+        env = "SUC_REMIND_PENDING"
+    )]
+    pub remind_pending: bool,
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "After a notification was sent for a program, suppress further notifications for that program for this many seconds, unless a version with a newer major, minor or patch version is found. The interval doubles for every notification that is sent shortly after the last interval ended.",
+        env = "SUC_NOTIFICATION_BACKOFF"
+    )]
+    pub notification_backoff: Option<u32>,
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "Wait at least this many seconds between two notifications, to not exceed the rate limit of the ntfy server. Notifications that are rejected because the rate limit is exceeded are sent again after the time requested by the server.",
+        default_value = "1",
+        env = "SUC_NOTIFICATION_THROTTLE"
+    )]
+    pub notification_throttle: u32,
+    #[arg(
+        long,
+        help = "When the network is unreachable, the update check is skipped and retried after this many seconds instead of after the check interval.",
+        default_value = "300",
+        env = "SUC_OFFLINE_RETRY_INTERVAL"
+    )]
+    pub offline_retry_interval: u32,
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "When update checks fail for all programs, e.g. during an outage of github, the check interval doubles with every failed update check up to this many seconds. It is reset once an update check succeeds for at least one program.",
+        default_value = "86400",
+        env = "SUC_MAX_BACKOFF"
+    )]
+    pub max_backoff: u32,
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "Stop an update check that takes longer than this many seconds. The remaining programs are not checked, the results of the programs that where already checked are stored and an error notification is sent.\nHas to be longer than the request timeout, if one is set.",
+        default_value = "600",
+        env = "SUC_CHECK_DEADLINE"
+    )]
+    pub check_deadline: u32,
+    #[arg(
+        long,
+        help = "Send an error notification when the network was unreachable for this many consecutive update checks.",
+        default_value = "3",
+        value_parser = clap::value_parser!(u32).range(1..),
+        env = "SUC_OFFLINE_NOTIFY_AFTER"
+    )]
+    pub offline_notify_after: u32,
+    #[cfg(feature = "health")]
+    #[arg(
+        long,
+        value_name = "ADDRESS",
+        help = "Start a http server on this address (e.g. 0.0.0.0:8080) that serves '/healthz' and '/status'.\n'/healthz' returns 200 when the last update check was completed within two check intervals and 503 otherwise, '/status' returns the result of the last update check as json.",
+        env = "SUC_HEALTH_LISTEN"
+    )]
+    pub health_listen: Option<std::net::SocketAddr>,
+    #[cfg(feature = "trigger")]
+    #[arg(
+        long,
+        value_name = "ADDRESS",
+        help = "Start a http server on this address (e.g. 127.0.0.1:8081) that starts an update check when 'POST /check' is requested and returns the result as json.",
+        env = "SUC_TRIGGER_ADDR"
+    )]
+    pub trigger_addr: Option<std::net::SocketAddr>,
+    #[cfg(feature = "trigger")]
+    #[arg(
+        long,
+        value_name = "TOKEN",
+        help = "Require requests to the trigger endpoint to send this token in the 'Authorization: Bearer <TOKEN>' header.",
+        env = "SUC_TRIGGER_TOKEN",
+        hide_env_values = true
+    )]
+    pub trigger_token: Option<String>,
+    #[arg(
+        long,
+        help = "Check the programs one after another instead of checking all programs at the same time.",
+        env = "SUC_NO_CONCURRENT"
+    )]
+    pub no_concurrent: bool,
+    #[arg(
+        long,
+        help = "Take over the lock on the database that prevents multiple instances from running at the same time, when the process holding it no longer exists."
+    )]
+    pub force: bool,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Write the PID of the process to this file at startup and remove it on shutdown, e.g. to send signals from scripts. Refuses to start when the file contains the PID of a process that is still running.",
+        env = "SUC_PID_FILE"
+    )]
+    pub pid_file: Option<PathBuf>,
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "On shutdown, wait this many seconds for a running update check to finish. The update check stops before the next program is checked and stores the results of the programs that where already checked.",
+        default_value = "30",
+        env = "SUC_SHUTDOWN_TIMEOUT"
+    )]
+    pub shutdown_timeout: u32,
+    #[arg(
+        long,
+        help = "Retry connecting to the database this many times when the connection fails on startup, e.g. when the volume containing the database is not yet mounted. The delay between the attempts doubles, starting at one second and limited to one minute.",
+        default_value = "0",
+        env = "SUC_DB_CONNECT_RETRIES"
+    )]
+    pub db_connect_retries: u32,
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "Stop retrying to connect to the database when the next retry would start later than this many seconds after the first attempt.",
+        default_value = "300",
+        env = "SUC_DB_CONNECT_TIMEOUT"
+    )]
+    pub db_connect_timeout: u32,
+}
+
+/// Interval in seconds in which the update check is run, when no interval is configured.
+pub const DEFAULT_CHECK_INTERVAL: u32 = 3600;
+
+impl RunTimedArgs {
+    /// Returns the arguments of `run-timed` that are set by environment variables, see [`Cli::parse_env`].
+    #[must_use]
+    pub fn from_env() -> Self {
+        let (cli, legacy_env_vars) =
+            Cli::try_parse_env_from(["simple_update_checker", "run-timed"])
+                .unwrap_or_else(|e| e.exit());
+        warn_legacy_env_vars(&legacy_env_vars);
+        match cli.command {
+            Command::RunTimed(run_timed_args) => run_timed_args,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Applies the values set in the provided config file, values that are already set take precedence.
+    ///
+    /// The values of the `[notifications.ntfy]` section take precedence over the top level `ntfy_*` values.
+    pub fn apply_config_file(&mut self, config_file: &ConfigFile) {
+        let ntfy = config_file.notifications.ntfy.as_ref();
+        if self.ntfy_topic.is_none() {
+            self.ntfy_topic = ntfy
+                .and_then(|ntfy| ntfy.topic.as_ref())
+                .and_then(config_secret)
+                .map(|topic| topic.expose().to_string())
+                .or_else(|| config_file.ntfy_topic.clone());
+        }
+        if self.ntfy_server.is_none() {
+            self.ntfy_server = ntfy
+                .and_then(|ntfy| ntfy.server.clone())
+                .or_else(|| config_file.ntfy_server.clone());
+        }
+        if self.ntfy_email.is_none() {
+            self.ntfy_email = ntfy
+                .and_then(|ntfy| ntfy.email.as_ref())
+                .and_then(config_secret)
+                .map(|email| email.expose().to_string());
+        }
+        if self.check_interval.is_none() {
+            self.check_interval = config_file.check_interval;
+        }
+    }
+
+    /// Returns the interval in seconds in which the update check should be run.
+    #[must_use]
+    pub fn check_interval(&self) -> u32 {
+        self.check_interval.unwrap_or(DEFAULT_CHECK_INTERVAL)
+    }
+
+    /// Returns the server to which notifications are sent.
+    #[must_use]
+    pub fn ntfy_server(&self) -> &str {
+        self.ntfy_server
+            .as_deref()
+            .unwrap_or(crate::notification::NTFY_SERVER)
+    }
+}
+
+/// Format in which lists are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable table.
+    Table,
+    /// Comma separated values with a header row, e.g. for spreadsheets.
+    Csv,
+}
+
+/// Style in which the borders of tables are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum TableStyle {
+    /// Borders drawn with `+`, `-` and `|`.
+    #[default]
+    Ascii,
+    /// Borders drawn with box drawing characters and rounded corners.
+    Rounded,
+    /// Markdown table, e.g. for issues or documentation.
+    Markdown,
+    /// Only a line below the header row, like the output of `psql`.
+    Psql,
+    /// No borders.
+    Blank,
+}
+
+/// Column of the program list that can be selected with `--fields`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ProgramField {
+    #[value(name = "name")]
+    Name,
+    #[value(name = "current_version")]
+    CurrentVersion,
+    #[value(name = "current_version_last_updated")]
+    CurrentVersionLastUpdated,
+    #[value(name = "latest_version")]
+    LatestVersion,
+    #[value(name = "latest_version_last_updated")]
+    LatestVersionLastUpdated,
+    #[value(name = "latest_version_published_at")]
+    LatestVersionPublishedAt,
+    #[value(name = "last_checked")]
+    LastChecked,
+    #[value(name = "provider")]
+    Provider,
+    /// Whether an update is available.
+    #[value(name = "status")]
+    Status,
+}
+
+impl ProgramField {
+    /// Columns that are listed when `--fields` is not set, the same columns as the table of [`Program`].
+    pub const DEFAULT: [Self; 6] = [
+        Self::Name,
+        Self::CurrentVersion,
+        Self::CurrentVersionLastUpdated,
+        Self::LatestVersion,
+        Self::LatestVersionLastUpdated,
+        Self::Provider,
+    ];
+
+    /// Returns the column name, the columns of the table of [`Program`] use the same names.
+    #[must_use]
+    pub fn header(self) -> &'static str {
+        match self {
+            Self::Name => "Name",
+            Self::CurrentVersion => "Current version (CV)",
+            Self::CurrentVersionLastUpdated => "CV last updated",
+            Self::LatestVersion => "Latest version (LV)",
+            Self::LatestVersionLastUpdated => "LV last updated",
+            Self::LatestVersionPublishedAt => "LV published at",
+            Self::LastChecked => "Last checked",
+            Self::Provider => "Provider",
+            Self::Status => "Status",
+        }
+    }
+
+    /// Returns the value of this column for `program`.
+    #[must_use]
+    pub fn value(self, program: &Program) -> String {
+        let format_optional = |datetime: Option<NaiveDateTime>, none: &str| {
+            datetime.map_or_else(
+                || none.to_string(),
+                |datetime| crate::format_datetime(&datetime),
+            )
+        };
+        match self {
+            Self::Name => program.name.clone(),
+            Self::CurrentVersion => program.current_version.clone(),
+            Self::CurrentVersionLastUpdated => {
+                crate::format_datetime(&program.current_version_last_updated)
+            }
+            Self::LatestVersion => program.latest_version.clone(),
+            Self::LatestVersionLastUpdated => {
+                crate::format_datetime(&program.latest_version_last_updated)
+            }
+            Self::LatestVersionPublishedAt => {
+                format_optional(program.latest_version_published_at, "unknown")
+            }
+            Self::LastChecked => format_optional(program.last_checked, "never"),
+            Self::Provider => program.provider.to_string(),
+            Self::Status => {
+                if program
+                    .version_scheme
+                    .is_newer(&program.latest_version, &program.current_version)
+                {
+                    "update available".to_string()
+                } else {
+                    "up to date".to_string()
+                }
+            }
+        }
+    }
+
+    /// Returns the value of this column for `program`, versions are shown without a leading `v` if
+    /// `normalize_versions` is set.
+    #[must_use]
+    pub fn display_value(self, program: &Program, normalize_versions: bool) -> String {
+        let value = self.value(program);
+        match self {
+            Self::CurrentVersion | Self::LatestVersion if normalize_versions => {
+                crate::version::normalize_for_display(&value).to_string()
+            }
+            _ => value,
+        }
+    }
+}
+
+/// Order in which the update history is printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum HistorySort {
+    /// Oldest entry first, newest entry at the bottom.
+    DateAsc,
+    /// Newest entry first.
+    DateDesc,
+    /// Sorted by program name, the entries of a program are sorted by date.
+    Name,
+}
+
+/// Arguments for commands that only read from the database.
+#[derive(Parser, Debug, Clone)]
+pub struct ReadDbArgs {
+    #[arg(
+        long,
+        help = "Open the database read-only and do not apply migrations. Fails if the database schema is outdated."
+    )]
+    pub no_migrate: bool,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct DbArgs {
+    #[arg{
+        short,
+        long,
+        help = "Path where 'programs.db' is located that contains the programs that should be checked for updates and their latest versions. If not set and config file not existing will default to 'programs.db'.",
+        env = "SUC_DB_PATH"
+    }]
+    pub db_path: Option<String>,
+
+    #[arg{
+        long,
+        help = "Name of the profile that should be used. Each profile uses its own database, located at '~/.config/simple_update_checker/<PROFILE>.db' unless 'db_path' is set in the '[profiles.<PROFILE>]' section of the config file.",
+        env = "SUC_PROFILE"
+    }]
+    pub profile: Option<String>,
+
+    #[arg(
+        long,
+        help = "Open the database read-only. 'check' then only reports the found updates without storing anything, commands that change the database fail."
+    )]
+    pub read_only: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, time::Duration};
+
+    use clap::Parser;
+    use tabled::Tabled;
+
+    use super::{
+        Cli, Command, ProgramField, UpdateProviderAdd, legacy_env_var, parse_age, parse_max_age,
+        parse_name_pattern,
+    };
+    use crate::{GithubProvider, Program, Provider, VersionScheme};
+
+    /// Parses `args` as arguments of `add-program` and sets `default_provider`.
+    fn add_program_args(args: &[&str], default_provider: Option<&str>) -> super::AddProgramArgs {
+        let cli = Cli::try_parse_from(
+            [
+                "simple_update_checker",
+                "add-program",
+                "--name",
+                "alpha_tui",
+            ]
+            .iter()
+            .chain(args),
+        )
+        .unwrap();
+        let Command::AddProgram(mut add_program_args) = cli.command else {
+            panic!("add-program was not parsed");
+        };
+        add_program_args.default_provider = default_provider.map(str::to_string);
+        *add_program_args
+    }
+
+    #[test]
+    fn test_legacy_env_var() {
+        assert_eq!(Some("DB_PATH"), legacy_env_var("SUC_DB_PATH"));
+        assert_eq!(
+            Some("SIMPLE_UPDATE_CHECKER_CONFIG"),
+            legacy_env_var("SUC_CONFIG")
+        );
+        assert_eq!(None, legacy_env_var("LOG_LEVEL"));
+        // options that where added after the prefix was introduced have no deprecated variable
+        assert_eq!(None, legacy_env_var("SUC_PROFILE"));
+        assert_eq!(None, legacy_env_var("SUC_DB_CONNECT_TIMEOUT"));
+    }
+
+    #[test]
+    fn test_parse_env() {
+        // SAFETY: no other test reads or writes these variables, the variables without prefix are only read by
+        // Cli::try_parse_env_from
+        unsafe {
+            env::set_var("CHECK_INTERVAL", "60");
+            env::set_var("DB_CONNECT_TIMEOUT", "60");
+            env::set_var("PROFILE", "work");
+        }
+        let run_timed_args = |args: &[&str]| {
+            let (cli, legacy_env_vars) =
+                Cli::try_parse_env_from(["suc", "run-timed"].iter().chain(args)).unwrap();
+            let Command::RunTimed(run_timed_args) = cli.command else {
+                panic!("run-timed was not parsed");
+            };
+            (run_timed_args, legacy_env_vars)
+        };
+        // the deprecated variable is used when the prefixed variable is not set
+        let (args, legacy_env_vars) = run_timed_args(&[]);
+        assert_eq!(Some(60), args.check_interval);
+        assert_eq!(
+            vec![(
+                "CHECK_INTERVAL".to_string(),
+                "SUC_CHECK_INTERVAL".to_string()
+            )],
+            legacy_env_vars
+        );
+        // options that never had a variable without prefix do not read it
+        assert_eq!(300, args.db_connect_timeout);
+        let (cli, _) = Cli::try_parse_env_from(["suc", "list-programs"]).unwrap();
+        assert_eq!(None, cli.db_args.profile);
+        // the cli takes precedence over all variables
+        let (args, legacy_env_vars) = run_timed_args(&["--check-interval", "10"]);
+        assert_eq!(Some(10), args.check_interval);
+        assert!(legacy_env_vars.is_empty());
+        // SAFETY: see above
+        unsafe {
+            env::remove_var("CHECK_INTERVAL");
+            env::remove_var("DB_CONNECT_TIMEOUT");
+            env::remove_var("PROFILE");
+        }
+    }
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(
+            Duration::from_secs(180 * 24 * 3600),
+            parse_age("180d").unwrap()
+        );
+        assert_eq!(Duration::from_secs(12 * 3600), parse_age("12h").unwrap());
+        assert_eq!(
+            Duration::from_secs(2 * 7 * 24 * 3600),
+            parse_age("2w").unwrap()
+        );
+        assert_eq!(
+            Duration::from_secs(365 * 24 * 3600),
+            parse_age("1y").unwrap()
+        );
+        assert!(parse_age("6m").is_err());
+        assert!(parse_age("d").is_err());
+    }
+
+    #[test]
+    fn test_parse_max_age() {
+        assert_eq!(Duration::from_secs(10 * 60), parse_max_age("10m").unwrap());
+        assert_eq!(Duration::from_secs(30), parse_max_age("30s").unwrap());
+        assert_eq!(Duration::from_secs(2 * 3600), parse_max_age("2h").unwrap());
+        assert_eq!(Duration::from_secs(24 * 3600), parse_max_age("1d").unwrap());
+        assert!(parse_max_age("10").is_err());
+        assert!(parse_max_age("m").is_err());
+    }
+
+    #[test]
+    fn test_update_provider() {
+        // explicit subcommands do not use the default provider
+        let args = add_program_args(&["homebrew", "--package", "wget"], Some("github"));
+        assert!(matches!(
+            args.update_provider().unwrap(),
+            UpdateProviderAdd::Homebrew(homebrew) if homebrew.package == "wget"
+        ));
+        let args = add_program_args(&["--repository", "LMH01/alpha_tui"], Some("github"));
+        assert!(matches!(
+            args.update_provider().unwrap(),
+            UpdateProviderAdd::Github(github) if github.repository == "LMH01/alpha_tui"
+        ));
+        let args = add_program_args(
+            &["-r", "LMH01/alpha_tui", "-b", "dev"],
+            Some("githubcommit"),
+        );
+        assert!(matches!(
+            args.update_provider().unwrap(),
+            UpdateProviderAdd::GithubCommit(commit) if commit.branch.as_deref() == Some("dev")
+        ));
+        // no default provider
+        let args = add_program_args(&["--repository", "LMH01/alpha_tui"], None);
+        assert!(args.update_provider().is_err());
+        // required option is missing
+        let args = add_program_args(&[], Some("github"));
+        assert!(
+            args.update_provider()
+                .unwrap_err()
+                .to_string()
+                .contains("'--repository' is required")
+        );
+        // option that the default provider does not use
+        let args = add_program_args(
+            &["--repository", "LMH01/alpha_tui", "--cask"],
+            Some("github"),
+        );
+        assert!(args.update_provider().is_err());
+        // options of the default provider together with a subcommand
+        let args = add_program_args(
+            &["--package", "wget", "homebrew", "--package", "wget"],
+            None,
+        );
+        assert!(args.update_provider().is_err());
+        let args = add_program_args(&["--package", "wget"], Some("unknown"));
+        assert!(args.update_provider().is_err());
+    }
+
+    #[test]
+    fn test_parse_name_pattern() {
+        let name_pattern = parse_name_pattern("work-*").unwrap();
+        assert!(name_pattern.is_match("work-vpn"));
+        assert!(!name_pattern.is_match("home-vpn"));
+        assert!(parse_name_pattern("work-[").is_err());
+    }
+
+    #[test]
+    fn test_parse_fields() {
+        let cli = Cli::try_parse_from([
+            "suc",
+            "list-programs",
+            "--fields",
+            "name,latest_version,status",
+        ])
+        .unwrap();
+        let Command::ListPrograms(list_programs_args) = cli.command else {
+            panic!("list-programs was not parsed");
+        };
+        assert_eq!(
+            Some(vec![
+                ProgramField::Name,
+                ProgramField::LatestVersion,
+                ProgramField::Status
+            ]),
+            list_programs_args.fields
+        );
+        let e = Cli::try_parse_from(["suc", "list-programs", "--fields", "name,latest"])
+            .unwrap_err()
+            .to_string();
+        assert!(e.contains("latest_version_last_updated"));
+    }
+
+    #[test]
+    fn test_program_field_value() {
+        let mut program = Program::builder()
+            .name("alpha_tui")
+            .current_version("v1.0.0")
+            .latest_version("v1.1.0")
+            .provider(Provider::Github(GithubProvider::new("LMH01/alpha_tui")))
+            .version_scheme(VersionScheme::Semver)
+            .build()
+            .unwrap();
+        // the default fields match the table of programs
+        assert_eq!(
+            Program::headers(),
+            ProgramField::DEFAULT.map(ProgramField::header)
+        );
+        assert_eq!(
+            program.fields(),
+            ProgramField::DEFAULT.map(|field| field.value(&program))
+        );
+        assert_eq!("update available", ProgramField::Status.value(&program));
+        assert_eq!("never", ProgramField::LastChecked.value(&program));
+        assert_eq!(
+            "v1.0.0",
+            ProgramField::CurrentVersion.display_value(&program, false)
+        );
+        assert_eq!(
+            "1.0.0",
+            ProgramField::CurrentVersion.display_value(&program, true)
+        );
+        program.current_version = "v1.1.0".to_string();
+        assert_eq!("up to date", ProgramField::Status.value(&program));
+        // the versions are compared with the version scheme of the program
+        program.version_scheme = VersionScheme::Calver;
+        program.current_version = "2024.09.01".to_string();
+        program.latest_version = "2024.9.1".to_string();
+        assert_eq!("up to date", ProgramField::Status.value(&program));
+        program.current_version = "2024.10.01".to_string();
+        assert_eq!("up to date", ProgramField::Status.value(&program));
+        program.current_version = "2024.08.01".to_string();
+        assert_eq!("update available", ProgramField::Status.value(&program));
+    }
 }
@@ -1,8 +1,19 @@
-use std::fs;
+use std::{
+    collections::{BTreeMap, HashMap},
+    env, fmt, fs, iter,
+    path::{Path, PathBuf},
+};
 
 use anyhow::Result;
+use clap::ValueEnum;
 use directories::BaseDirs;
 use serde::{Deserialize, Serialize};
+use toml::Spanned;
+
+use crate::{
+    ReleaseSelector, Secret, VersionScheme,
+    cli::{AddProgramArgs, DefaultProviderArgs},
+};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ConfigFile {
@@ -10,27 +21,151 @@ pub struct ConfigFile {
     #[serde(skip)]
     pub path: String,
     pub db_path: String,
-    pub github_access_token: Option<String>,
+    /// Can reference a secret, see [`SecretRef`].
+    pub github_access_token: Option<SecretRef>,
+    /// File from which the github access token is read, when `github_access_token` is not set.
+    pub github_access_token_file: Option<PathBuf>,
+    /// Timeout of a single http request in seconds.
+    pub request_timeout: Option<u64>,
+    /// Topic to which `run-timed` publishes notifications.
+    pub ntfy_topic: Option<String>,
+    /// Server to which `run-timed` sends notifications.
+    pub ntfy_server: Option<String>,
+    /// Interval in seconds in which `run-timed` checks for updates.
+    pub check_interval: Option<u32>,
+    /// Provider that `add-program` uses when no provider subcommand is given.
+    pub default_provider: Option<String>,
+    /// Profiles that can be selected with `--profile`, the key is the name of the profile.
+    #[serde(default)]
+    pub profiles: HashMap<String, ProfileConfig>,
+    /// Notification backends, their settings take precedence over the top level `ntfy_*` values.
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+    /// Programs that are added to the database by `sync-config` and when `run-timed` starts.
+    pub programs: Option<Vec<ProgramConfig>>,
+    /// Remove programs that are not defined in `programs` from the database when the programs are synced.
+    #[serde(default)]
+    pub prune: bool,
+}
+
+/// Program defined in a `[[programs]]` section of the config file.
+///
+/// `provider` selects the provider, the other keys are the options of the provider subcommands of `add-program`.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ProgramConfig {
+    pub name: String,
+    pub provider: String,
+    pub repository: Option<String>,
+    pub required_asset_pattern: Option<String>,
+    pub access_token_env: Option<String>,
+    pub release_selector: Option<String>,
+    pub branch: Option<String>,
+    pub package: Option<String>,
+    #[serde(default)]
+    pub cask: bool,
+    pub command: Option<String>,
+    pub regex: Option<String>,
+    pub version_scheme: Option<String>,
+    pub check_interval: Option<u32>,
+}
+
+impl ProgramConfig {
+    /// Returns the arguments of `add-program` that add this program.
+    ///
+    /// Fails when a value is invalid or when the options do not match the provider.
+    pub fn add_program_args(&self) -> Result<AddProgramArgs> {
+        let release_selector = self
+            .release_selector
+            .as_deref()
+            .map(|release_selector| ReleaseSelector::from_str(release_selector, false))
+            .transpose()
+            .map_err(|e| anyhow::anyhow!("invalid release_selector: {e}"))?;
+        let version_scheme = self
+            .version_scheme
+            .as_deref()
+            .map(|version_scheme| VersionScheme::from_str(version_scheme, false))
+            .transpose()
+            .map_err(|e| anyhow::anyhow!("invalid version_scheme: {e}"))?
+            .unwrap_or_default();
+        let add_program_args = AddProgramArgs {
+            provider: None,
+            default_provider_args: DefaultProviderArgs {
+                repository: self.repository.clone(),
+                required_asset_pattern: self.required_asset_pattern.clone(),
+                access_token_env: self.access_token_env.clone(),
+                release_selector,
+                branch: self.branch.clone(),
+                package: self.package.clone(),
+                cask: self.cask,
+                command: self.command.clone(),
+                regex: self.regex.clone(),
+            },
+            default_provider: Some(self.provider.clone()),
+            name: self.name.clone(),
+            component_of: None,
+            pin_major: None,
+            version_constraint: None,
+            version_scheme,
+            allow_rollback: false,
+            check_interval: self.check_interval,
+        };
+        // the provider options are checked, so that invalid programs are found when the config is validated
+        add_program_args.update_provider()?;
+        Ok(add_program_args)
+    }
+}
+
+/// Settings of the notification backends, defined in the `[notifications]` section of the config file.
+///
+/// Each backend has its own sub-table, unknown backends are rejected when the config file is parsed.
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct NotificationsConfig {
+    pub ntfy: Option<NtfyConfig>,
+}
+
+/// Settings of ntfy, defined in the `[notifications.ntfy]` section of the config file.
+///
+/// `topic` and `email` can reference a secret, see [`SecretRef`].
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct NtfyConfig {
+    pub topic: Option<SecretRef>,
+    pub server: Option<String>,
+    pub email: Option<SecretRef>,
+}
+
+/// Settings of a single profile, defined in a `[profiles.<NAME>]` section of the config file.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ProfileConfig {
+    pub db_path: Option<String>,
+    pub github_access_token: Option<SecretRef>,
 }
 
 impl ConfigFile {
-    /// Tries to load the config located at ~/.`config/simple_update_checker/config.toml`
+    /// Tries to load the config located at `path` or, when `path` is not set, at [`default_config_path`]
     ///
     /// ## Returns
     /// - `Ok(Config)` when the config exists and could be parsed.
-    /// - `Ok(None)` when the config does not exit.
-    /// - `Err(e)` when the config exists but could not be parsed.
-    pub fn try_parse() -> Result<Option<Self>> {
-        let base_dirs = match BaseDirs::new() {
-            Some(base_dirs) => base_dirs,
-            None => anyhow::bail!("Home directory path could not be determined"),
+    /// - `Ok(None)` when `path` is not set and the config does not exit.
+    /// - `Err(e)` when the config exists but could not be parsed or when `path` is set and the config does not exist.
+    pub fn try_parse(path: Option<&Path>) -> Result<Option<Self>> {
+        let config_file = match path {
+            Some(path) => {
+                if !path.exists() {
+                    anyhow::bail!("Config file {} does not exist", path.display());
+                }
+                path.to_path_buf()
+            }
+            None => {
+                let config_file = default_config_path();
+                if !config_file.exists() {
+                    return Ok(None);
+                }
+                config_file
+            }
         };
-        let config_file = base_dirs
-            .config_dir()
-            .join("simple_update_checker/config.toml");
-        if !config_file.exists() {
-            return Ok(None);
-        }
 
         let mut config = toml::from_str::<ConfigFile>(&fs::read_to_string(&config_file)?)?;
         config.path = config_file
@@ -42,3 +177,715 @@ impl ConfigFile {
         Ok(Some(config))
     }
 }
+
+/// Value of the config file that can reference a secret instead of containing it.
+///
+/// `env:<VAR_NAME>` references the value of the environment variable and `file:<PATH>` the content of the file
+/// without surrounding whitespace. All other values are used as they are.
+/// Literal values are not shown in debug output.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum SecretRef {
+    Literal(Secret),
+    Env(String),
+    File(PathBuf),
+}
+
+impl SecretRef {
+    /// Reads the referenced secret.
+    ///
+    /// The error names the reference that could not be resolved.
+    pub fn resolve(&self) -> Result<Secret> {
+        match self {
+            Self::Literal(secret) => Ok(secret.clone()),
+            Self::Env(var) => env::var(var)
+                .map(Secret::from)
+                .map_err(|e| anyhow::anyhow!("unable to resolve {self}: {e}")),
+            Self::File(path) => fs::read_to_string(path)
+                .map(|content| Secret::from(content.trim().to_string()))
+                .map_err(|e| anyhow::anyhow!("unable to resolve {self}: {e}")),
+        }
+    }
+}
+
+impl From<String> for SecretRef {
+    fn from(value: String) -> Self {
+        if let Some(var) = value.strip_prefix("env:") {
+            Self::Env(var.to_string())
+        } else if let Some(path) = value.strip_prefix("file:") {
+            Self::File(PathBuf::from(path))
+        } else {
+            Self::Literal(Secret::from(value))
+        }
+    }
+}
+
+impl From<&str> for SecretRef {
+    fn from(value: &str) -> Self {
+        Self::from(value.to_string())
+    }
+}
+
+impl From<SecretRef> for String {
+    fn from(value: SecretRef) -> Self {
+        match value {
+            SecretRef::Literal(secret) => secret.expose().to_string(),
+            SecretRef::Env(var) => format!("env:{var}"),
+            SecretRef::File(path) => format!("file:{}", path.display()),
+        }
+    }
+}
+
+impl fmt::Display for SecretRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Literal(_) => write!(f, "literal value (redacted)"),
+            Self::Env(var) => write!(f, "env:{var}"),
+            Self::File(path) => write!(f, "file:{}", path.display()),
+        }
+    }
+}
+
+impl fmt::Debug for SecretRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretRef({self})")
+    }
+}
+
+/// Returns the content of a new config file that stores the programs in the database at `db_path`.
+///
+/// All supported keys are listed, keys without a default value are commented out.
+#[must_use]
+pub fn template(db_path: &str) -> String {
+    format!(
+        r#"# Config file of simple_update_checker, see 'simple_update_checker config validate' to check it for problems.
+
+# Database that contains the programs that are checked for updates.
+db_path = {db_path}
+
+# Github access token that is used to increase the rate limit of the github api.
+# Set either the token or a file that contains it. The token can reference a secret
+# with "env:<VAR_NAME>" or "file:<PATH>".
+#github_access_token = "ghp_..."
+#github_access_token_file = "/run/secrets/github_access_token"
+
+# Timeout of a single http request in seconds, no timeout is used when not set.
+#request_timeout = 30
+
+# Interval in seconds in which 'run-timed' checks for updates.
+check_interval = {check_interval}
+
+# Provider that 'add-program' uses when no provider subcommand is given.
+#default_provider = "github"
+
+# Remove programs from the database that are not defined in [[programs]] when the programs are synced.
+#prune = false
+
+# Notifications that 'run-timed' sends when updates are found.
+# topic and email can reference a secret with "env:<VAR_NAME>" or "file:<PATH>".
+#[notifications.ntfy]
+#topic = "env:NTFY_TOPIC"
+#server = "{ntfy_server}"
+#email = "file:/run/secrets/ntfy_email"
+
+# Profiles can be selected with --profile, each profile uses its own database.
+#[profiles.work]
+#db_path = "/home/user/work.db"
+#github_access_token = "ghp_..."
+
+# Programs that are added to the database by 'sync-config' and when 'run-timed' starts.
+# provider is one of github, githubcommit, homebrew, fdroid and command, the other keys are the options of the
+# provider subcommands of 'add-program', e.g. repository, package, command and regex.
+#[[programs]]
+#name = "fzf"
+#provider = "github"
+#repository = "junegunn/fzf"
+"#,
+        db_path = toml::Value::String(db_path.to_string()),
+        check_interval = crate::cli::DEFAULT_CHECK_INTERVAL,
+        ntfy_server = crate::notification::NTFY_SERVER,
+    )
+}
+
+/// Keys that can be set at the top level of the config file.
+const KEYS: [&str; 12] = [
+    "db_path",
+    "github_access_token",
+    "github_access_token_file",
+    "request_timeout",
+    "ntfy_topic",
+    "ntfy_server",
+    "check_interval",
+    "default_provider",
+    "profiles",
+    "notifications",
+    "programs",
+    "prune",
+];
+
+/// Keys that can be set in a `[profiles.<NAME>]` section of the config file.
+const PROFILE_KEYS: [&str; 2] = ["db_path", "github_access_token"];
+
+/// Checks the content of a config file for problems.
+///
+/// Reports syntax and type errors, unknown keys and files or environment variables that are referenced by the config
+/// but do not exist. Returns a description of every problem that was found, with the line number where possible.
+pub fn validate(content: &str) -> Vec<String> {
+    #[derive(Deserialize)]
+    struct Profiles {
+        #[serde(default)]
+        profiles: BTreeMap<String, BTreeMap<Spanned<String>, toml::Value>>,
+    }
+
+    let line = |key: &Spanned<String>| content[..key.span().start].matches('\n').count() + 1;
+    let mut problems = Vec::new();
+    // unknown keys are ignored when the config is parsed, except in the [notifications] section
+    if let Ok(keys) = toml::from_str::<BTreeMap<Spanned<String>, toml::Value>>(content) {
+        for key in keys.keys() {
+            if !KEYS.contains(&key.get_ref().as_str()) {
+                problems.push(format!(
+                    "line {}: unknown key `{}`",
+                    line(key),
+                    key.get_ref()
+                ));
+            }
+        }
+    }
+    if let Ok(config) = toml::from_str::<Profiles>(content) {
+        for (name, keys) in &config.profiles {
+            for key in keys.keys() {
+                if !PROFILE_KEYS.contains(&key.get_ref().as_str()) {
+                    problems.push(format!(
+                        "line {}: unknown key `{}` in profile {name}",
+                        line(key),
+                        key.get_ref()
+                    ));
+                }
+            }
+        }
+    }
+    let config = match toml::from_str::<ConfigFile>(content) {
+        Ok(config) => config,
+        Err(e) => {
+            problems.push(e.to_string().trim_end().to_string());
+            return problems;
+        }
+    };
+    if let Some(path) = &config.github_access_token_file
+        && !path.exists()
+    {
+        problems.push(format!(
+            "github_access_token_file: {} does not exist",
+            path.display()
+        ));
+    }
+    let db_paths = config.profiles.iter().filter_map(|(name, profile)| {
+        let db_path = profile.db_path.as_ref()?;
+        Some((format!("profiles.{name}.db_path"), db_path))
+    });
+    for (key, db_path) in iter::once(("db_path".to_string(), &config.db_path)).chain(db_paths) {
+        if let Some(parent) = Path::new(db_path).parent()
+            && !parent.as_os_str().is_empty()
+            && !parent.exists()
+        {
+            problems.push(format!(
+                "{key}: directory {} does not exist",
+                parent.display()
+            ));
+        }
+    }
+    let programs = config.programs.as_deref().unwrap_or_default();
+    for (idx, program) in programs.iter().enumerate() {
+        if programs[..idx]
+            .iter()
+            .any(|other| other.name == program.name)
+        {
+            problems.push(format!(
+                "programs: {} is defined more than once",
+                program.name
+            ));
+        }
+        if let Err(e) = program.add_program_args() {
+            problems.push(format!("programs: {}: {e}", program.name));
+        }
+    }
+    let profile_tokens = config.profiles.iter().map(|(name, profile)| {
+        (
+            format!("profiles.{name}.github_access_token"),
+            profile.github_access_token.as_ref(),
+        )
+    });
+    let ntfy = config.notifications.ntfy.as_ref();
+    let secrets = [
+        (
+            "github_access_token".to_string(),
+            config.github_access_token.as_ref(),
+        ),
+        (
+            "notifications.ntfy.topic".to_string(),
+            ntfy.and_then(|ntfy| ntfy.topic.as_ref()),
+        ),
+        (
+            "notifications.ntfy.email".to_string(),
+            ntfy.and_then(|ntfy| ntfy.email.as_ref()),
+        ),
+    ];
+    for (key, secret) in secrets.into_iter().chain(profile_tokens) {
+        if let Some(secret) = secret
+            && let Err(e) = secret.resolve()
+        {
+            problems.push(format!("{key}: {e}"));
+        }
+    }
+    problems
+}
+
+/// Returns the path of the config file that is used when no config file is set with `--config`.
+///
+/// The config file is called `config.toml` and located in the directory returned by [`config_dir`].
+#[must_use]
+pub fn default_config_path() -> PathBuf {
+    config_dir().join("config.toml")
+}
+
+/// Returns the path to the default database of the profile named `profile`.
+///
+/// The database is called `<profile>.db` and located in the directory returned by [`config_dir`].
+#[must_use]
+pub fn default_profile_db_path(profile: &str) -> PathBuf {
+    config_dir().join(format!("{profile}.db"))
+}
+
+/// Returns the directory that contains the config file and the databases of profiles.
+///
+/// The directory is taken from the first of these sources that is available:
+/// 1. `$XDG_CONFIG_HOME/simple_update_checker`
+/// 2. the config directory of the platform, e.g. `~/.config/simple_update_checker`
+/// 3. the current working directory, e.g. in containers without home directory
+fn config_dir() -> PathBuf {
+    config_dir_from(
+        env::var_os("XDG_CONFIG_HOME").map(PathBuf::from),
+        BaseDirs::new().map(|base_dirs| base_dirs.config_dir().to_path_buf()),
+    )
+}
+
+/// Returns the directory that contains the config file, see [`config_dir`].
+fn config_dir_from(
+    xdg_config_home: Option<PathBuf>,
+    platform_config_dir: Option<PathBuf>,
+) -> PathBuf {
+    // relative paths in XDG_CONFIG_HOME are invalid and should be ignored
+    let xdg_config_home = xdg_config_home.filter(|path| path.is_absolute());
+    match xdg_config_home.or(platform_config_dir) {
+        Some(config_dir) => config_dir.join("simple_update_checker"),
+        None => PathBuf::from("."),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::PathBuf};
+
+    use super::{ConfigFile, SecretRef, config_dir_from, template, validate};
+    use crate::{ReleaseSelector, VersionScheme, cli::UpdateProviderAdd};
+
+    #[test]
+    fn test_parse_config_with_profiles() {
+        let config = r#"
+db_path = "/home/user/programs.db"
+
+[profiles.work]
+db_path = "/home/user/work.db"
+github_access_token = "work_token"
+
+[profiles.home]
+"#;
+        let config = toml::from_str::<ConfigFile>(config).unwrap();
+        let work = config.profiles.get("work").unwrap();
+        assert_eq!(Some("/home/user/work.db".to_string()), work.db_path);
+        assert_eq!(
+            Some(SecretRef::from("work_token")),
+            work.github_access_token
+        );
+        let home = config.profiles.get("home").unwrap();
+        assert_eq!(None, home.db_path);
+    }
+
+    #[test]
+    fn test_parse_config_with_run_timed_settings() {
+        let config = r#"
+db_path = "/home/user/programs.db"
+github_access_token_file = "/run/secrets/github_token"
+request_timeout = 30
+ntfy_topic = "updates"
+ntfy_server = "https://ntfy.example.com"
+check_interval = 7200
+"#;
+        let config = toml::from_str::<ConfigFile>(config).unwrap();
+        assert_eq!(
+            Some(PathBuf::from("/run/secrets/github_token")),
+            config.github_access_token_file
+        );
+        assert_eq!(Some(30), config.request_timeout);
+        assert_eq!(Some("updates".to_string()), config.ntfy_topic);
+        assert_eq!(
+            Some("https://ntfy.example.com".to_string()),
+            config.ntfy_server
+        );
+        assert_eq!(Some(7200), config.check_interval);
+    }
+
+    #[test]
+    fn test_parse_config_with_notifications() {
+        let config = r#"
+db_path = "/home/user/programs.db"
+
+[notifications.ntfy]
+topic = "env:NTFY_TOPIC"
+server = "https://ntfy.example.com"
+"#;
+        let config = toml::from_str::<ConfigFile>(config).unwrap();
+        let ntfy = config.notifications.ntfy.unwrap();
+        assert_eq!(Some(SecretRef::Env("NTFY_TOPIC".to_string())), ntfy.topic);
+        assert_eq!(Some("https://ntfy.example.com".to_string()), ntfy.server);
+        assert_eq!(None, ntfy.email);
+    }
+
+    #[test]
+    fn test_parse_config_with_unknown_notification_settings() {
+        let config = r#"
+db_path = "/home/user/programs.db"
+
+[notifications.ntfy]
+topik = "updates"
+"#;
+        let e = toml::from_str::<ConfigFile>(config)
+            .unwrap_err()
+            .to_string();
+        assert!(e.contains("unknown field `topik`"), "{e}");
+        let config = r#"
+db_path = "/home/user/programs.db"
+
+[notifications.telegram]
+token = "secret"
+"#;
+        let e = toml::from_str::<ConfigFile>(config)
+            .unwrap_err()
+            .to_string();
+        assert!(e.contains("unknown field `telegram`"), "{e}");
+    }
+
+    #[test]
+    fn test_secret_ref_literal() {
+        let secret = SecretRef::from("ghp_secret");
+        assert!(matches!(secret, SecretRef::Literal(_)));
+        assert_eq!("ghp_secret", secret.resolve().unwrap().expose());
+        // the value is not shown in debug output
+        assert!(!format!("{secret:?}").contains("ghp_secret"));
+        assert!(!format!("{:?}", secret.resolve().unwrap()).contains("ghp_secret"));
+        assert_eq!("ghp_secret", String::from(secret));
+    }
+
+    #[test]
+    fn test_secret_ref_env() {
+        let secret = SecretRef::from("env:PATH");
+        assert_eq!(SecretRef::Env("PATH".to_string()), secret);
+        // PATH is set in every environment the tests run in
+        assert_eq!(
+            env::var("PATH").unwrap(),
+            secret.resolve().unwrap().expose()
+        );
+        assert_eq!("env:PATH", String::from(secret));
+
+        let e = SecretRef::from("env:SIMPLE_UPDATE_CHECKER_UNSET_VARIABLE")
+            .resolve()
+            .unwrap_err()
+            .to_string();
+        assert!(
+            e.contains("env:SIMPLE_UPDATE_CHECKER_UNSET_VARIABLE"),
+            "{e}"
+        );
+    }
+
+    #[test]
+    fn test_secret_ref_file() {
+        let path = env::temp_dir().join(format!(
+            "simple_update_checker_secret_{}",
+            std::process::id()
+        ));
+        let secret = SecretRef::from(format!("file:{}", path.display()));
+        assert_eq!(SecretRef::File(path.clone()), secret);
+        fs::write(&path, "file_secret\n").unwrap();
+        assert_eq!("file_secret", secret.resolve().unwrap().expose());
+        fs::remove_file(&path).unwrap();
+
+        let e = secret.resolve().unwrap_err().to_string();
+        assert!(e.contains(&format!("file:{}", path.display())), "{e}");
+    }
+
+    #[test]
+    fn test_parse_secret_refs() {
+        let config = r#"
+db_path = "/home/user/programs.db"
+github_access_token = "file:/run/secrets/github_token"
+
+[profiles.work]
+github_access_token = "env:WORK_GITHUB_TOKEN"
+"#;
+        let config = toml::from_str::<ConfigFile>(config).unwrap();
+        assert_eq!(
+            Some(SecretRef::File(PathBuf::from("/run/secrets/github_token"))),
+            config.github_access_token
+        );
+        assert_eq!(
+            Some(SecretRef::Env("WORK_GITHUB_TOKEN".to_string())),
+            config.profiles.get("work").unwrap().github_access_token
+        );
+    }
+
+    #[test]
+    fn test_try_parse_path() {
+        let path = env::temp_dir().join(format!(
+            "simple_update_checker_config_{}.toml",
+            std::process::id()
+        ));
+        fs::write(&path, r#"db_path = "/home/user/programs.db""#).unwrap();
+        let config = ConfigFile::try_parse(Some(&path)).unwrap().unwrap();
+        assert_eq!("/home/user/programs.db", config.db_path);
+        assert_eq!(path.to_str().unwrap(), config.path);
+        fs::write(&path, "db_path = ").unwrap();
+        assert!(ConfigFile::try_parse(Some(&path)).is_err());
+        fs::remove_file(&path).unwrap();
+        // an explicitly specified config file has to exist
+        let e = ConfigFile::try_parse(Some(&path)).unwrap_err();
+        assert!(e.to_string().contains("does not exist"));
+    }
+
+    #[test]
+    fn test_template() {
+        let content = template("/home/user/\"programs\".db");
+        let path = env::temp_dir().join(format!(
+            "simple_update_checker_template_{}.toml",
+            std::process::id()
+        ));
+        fs::write(&path, &content).unwrap();
+        let config = ConfigFile::try_parse(Some(&path)).unwrap().unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!("/home/user/\"programs\".db", config.db_path);
+        assert_eq!(
+            Some(crate::cli::DEFAULT_CHECK_INTERVAL),
+            config.check_interval
+        );
+        // the commented out examples have to be valid as well
+        let uncommented = content
+            .lines()
+            .map(|line| match line.strip_prefix('#') {
+                Some(example) if !example.starts_with(' ') => example,
+                _ => line,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let config = toml::from_str::<ConfigFile>(&uncommented).unwrap();
+        assert!(config.notifications.ntfy.is_some());
+        assert!(config.profiles.contains_key("work"));
+        assert!(
+            !validate(&uncommented)
+                .iter()
+                .any(|problem| problem.contains("unknown"))
+        );
+        let db_path = env::temp_dir().join("programs.db");
+        assert!(validate(&template(db_path.to_str().unwrap())).is_empty());
+    }
+
+    #[test]
+    fn test_validate() {
+        let config = r#"
+db_path = "programs.db"
+request_timeout = 30
+
+[profiles.work]
+db_path = "work.db"
+"#;
+        assert!(validate(config).is_empty());
+    }
+
+    #[test]
+    fn test_parse_programs() {
+        let config = r#"
+db_path = "programs.db"
+prune = true
+
+[[programs]]
+name = "fzf"
+provider = "github"
+repository = "junegunn/fzf"
+release_selector = "newest-stable"
+version_scheme = "semver"
+
+[[programs]]
+name = "wget"
+provider = "homebrew"
+package = "wget"
+check_interval = 86400
+"#;
+        let config = toml::from_str::<ConfigFile>(config).unwrap();
+        assert!(config.prune);
+        let programs = config.programs.unwrap();
+        let add_program_args = programs[0].add_program_args().unwrap();
+        assert_eq!("fzf", add_program_args.name);
+        assert_eq!(VersionScheme::Semver, add_program_args.version_scheme);
+        assert_eq!(
+            Some(ReleaseSelector::NewestStable),
+            add_program_args.default_provider_args.release_selector
+        );
+        assert!(matches!(
+            add_program_args.update_provider().unwrap(),
+            UpdateProviderAdd::Github(_)
+        ));
+        let add_program_args = programs[1].add_program_args().unwrap();
+        assert_eq!(Some(86400), add_program_args.check_interval);
+        assert!(matches!(
+            add_program_args.update_provider().unwrap(),
+            UpdateProviderAdd::Homebrew(_)
+        ));
+    }
+
+    #[test]
+    fn test_validate_programs() {
+        let config = r#"
+db_path = "programs.db"
+
+[[programs]]
+name = "fzf"
+provider = "github"
+
+[[programs]]
+name = "fzf"
+provider = "homebrew"
+package = "fzf"
+version_scheme = "semantic"
+"#;
+        let problems = validate(config);
+        assert_eq!(3, problems.len(), "{problems:?}");
+        assert!(problems[0].contains("'--repository' is required"));
+        assert_eq!("programs: fzf is defined more than once", problems[1]);
+        assert!(problems[2].contains("invalid version_scheme"));
+        let config = r#"
+db_path = "programs.db"
+
+[[programs]]
+name = "fzf"
+provider = "github"
+repo = "junegunn/fzf"
+"#;
+        let problems = validate(config);
+        assert_eq!(1, problems.len());
+        assert!(problems[0].contains("unknown field `repo`"));
+    }
+
+    #[test]
+    fn test_validate_unknown_keys() {
+        let config = r#"
+db_path = "programs.db"
+ntfy_topik = "updates"
+
+[profiles.work]
+db_pth = "work.db"
+"#;
+        assert_eq!(
+            vec![
+                "line 3: unknown key `ntfy_topik`".to_string(),
+                "line 6: unknown key `db_pth` in profile work".to_string()
+            ],
+            validate(config)
+        );
+        let config = r#"
+db_path = "programs.db"
+
+[notifications.ntfy]
+topik = "updates"
+"#;
+        let problems = validate(config);
+        assert_eq!(1, problems.len());
+        assert!(problems[0].contains("line 5"), "{}", problems[0]);
+        assert!(problems[0].contains("unknown field `topik`"));
+    }
+
+    #[test]
+    fn test_validate_type_error() {
+        let config = r#"
+db_path = "programs.db"
+request_timeout = "ten"
+"#;
+        let problems = validate(config);
+        assert_eq!(1, problems.len());
+        assert!(problems[0].contains("line 3"), "{}", problems[0]);
+        let problems = validate("request_timeout = 30");
+        assert!(problems[0].contains("missing field `db_path`"));
+    }
+
+    #[test]
+    fn test_validate_referenced_files() {
+        let config = r#"
+db_path = "/simple_update_checker_missing/programs.db"
+github_access_token_file = "/simple_update_checker_missing/token"
+
+[notifications.ntfy]
+topic = "env:SIMPLE_UPDATE_CHECKER_UNSET_VARIABLE"
+"#;
+        let problems = validate(config);
+        assert_eq!(3, problems.len(), "{problems:?}");
+        assert!(problems[0].starts_with("github_access_token_file"));
+        assert!(problems[1].starts_with("db_path"));
+        assert!(problems[2].starts_with("notifications.ntfy.topic"));
+
+        let config = r#"
+db_path = "programs.db"
+github_access_token = "file:/simple_update_checker_missing/token"
+
+[profiles.work]
+github_access_token = "env:SIMPLE_UPDATE_CHECKER_UNSET_VARIABLE"
+"#;
+        assert_eq!(
+            vec![
+                "github_access_token: unable to resolve file:/simple_update_checker_missing/token: No such file or directory (os error 2)".to_string(),
+                "profiles.work.github_access_token: unable to resolve env:SIMPLE_UPDATE_CHECKER_UNSET_VARIABLE: environment variable not found".to_string(),
+            ],
+            validate(config)
+        );
+    }
+
+    #[test]
+    fn test_config_dir_from() {
+        let xdg = PathBuf::from("/xdg");
+        let platform = PathBuf::from("/home/user/.config");
+        assert_eq!(
+            PathBuf::from("/xdg/simple_update_checker"),
+            config_dir_from(Some(xdg.clone()), Some(platform.clone()))
+        );
+        assert_eq!(
+            PathBuf::from("/home/user/.config/simple_update_checker"),
+            config_dir_from(None, Some(platform.clone()))
+        );
+        // relative paths and empty values are ignored
+        assert_eq!(
+            PathBuf::from("/home/user/.config/simple_update_checker"),
+            config_dir_from(Some(PathBuf::from("config")), Some(platform))
+        );
+        assert_eq!(
+            PathBuf::from("/xdg/simple_update_checker"),
+            config_dir_from(Some(xdg), None)
+        );
+        assert_eq!(
+            PathBuf::from("."),
+            config_dir_from(Some(PathBuf::new()), None)
+        );
+    }
+
+    #[test]
+    fn test_parse_config_without_profiles() {
+        let config = r#"db_path = "/home/user/programs.db""#;
+        let config = toml::from_str::<ConfigFile>(config).unwrap();
+        assert!(config.profiles.is_empty());
+    }
+}
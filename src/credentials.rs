@@ -0,0 +1,48 @@
+use anyhow::Result;
+use keyring::Entry;
+
+/// Service name under which the github access token is stored in the system keyring.
+const KEYRING_SERVICE: &str = "simple_update_checker";
+/// User name under which the github access token is stored in the system keyring.
+const KEYRING_USER: &str = "github_access_token";
+
+/// Runs `f` with the keyring entry of the github access token.
+///
+/// Some keyring backends block on their own async runtime, so the entry is accessed on a blocking thread.
+async fn with_entry<T: Send + 'static>(
+    f: impl FnOnce(Entry) -> keyring::Result<T> + Send + 'static,
+) -> Result<T> {
+    let res =
+        tokio::task::spawn_blocking(move || f(Entry::new(KEYRING_SERVICE, KEYRING_USER)?)).await?;
+    Ok(res?)
+}
+
+/// Stores the github access token in the system keyring.
+pub async fn store_github_access_token(token: &str) -> Result<()> {
+    let token = token.to_string();
+    with_entry(move |entry| entry.set_password(&token)).await
+}
+
+/// Loads the github access token from the system keyring.
+///
+/// Returns `None` if no token is stored.
+pub async fn load_github_access_token() -> Result<Option<String>> {
+    with_entry(|entry| match entry.get_password() {
+        Ok(token) => Ok(Some(token)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e),
+    })
+    .await
+}
+
+/// Removes the github access token from the system keyring.
+///
+/// Returns `false` if no token was stored.
+pub async fn clear_github_access_token() -> Result<bool> {
+    with_entry(|entry| match entry.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(e),
+    })
+    .await
+}
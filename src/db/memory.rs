@@ -0,0 +1,325 @@
+//! In-memory [`ProgramStore`] and providers that do not use the network, used to test the update check and the
+//! notification handling without a sqlite database.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Mutex,
+};
+
+use anyhow::Result;
+use sqlx::types::chrono::NaiveDateTime;
+
+use crate::{
+    Identifier, NotificationBackoff, NotificationInfo, Program, Provider, ProviderError,
+    UpdateCheckHistoryEntry, VersionHistoryEntry,
+    db::{ProgramChanges, ProgramStore},
+    provider::{AuthContext, LatestVersionFuture, UpdateProvider},
+    update_check::LatestVersion,
+};
+
+/// Stores the programs in memory, behaves like [`Db`](super::Db).
+#[derive(Default)]
+pub struct MemoryStore {
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    /// Programs by their name.
+    programs: BTreeMap<String, StoredProgram>,
+    /// Products by the names of their components.
+    products: HashMap<String, String>,
+    update_checks: Vec<UpdateCheckHistoryEntry>,
+    version_history: Vec<VersionHistoryEntry>,
+}
+
+struct StoredProgram {
+    program: Program,
+    notification_sent: bool,
+    notification_sent_on: Option<NaiveDateTime>,
+    backoff: Option<NotificationBackoff>,
+    last_error: Option<String>,
+}
+
+impl MemoryStore {
+    /// Adds `component` to the product named `product`.
+    pub fn add_component(&self, product: &str, component: &str) {
+        self.state
+            .lock()
+            .unwrap()
+            .products
+            .insert(component.to_string(), product.to_string());
+    }
+
+    /// Returns the error of the last update check of the program named `name`.
+    pub fn last_error(&self, name: &str) -> Option<String> {
+        self.state.lock().unwrap().programs[name].last_error.clone()
+    }
+
+    /// Returns all entries of the update check history, the oldest entry first.
+    pub fn update_checks(&self) -> Vec<UpdateCheckHistoryEntry> {
+        self.state.lock().unwrap().update_checks.clone()
+    }
+
+    /// Returns the versions that where found for the program named `name`.
+    pub fn version_history(&self, name: &str) -> Vec<String> {
+        self.state
+            .lock()
+            .unwrap()
+            .version_history
+            .iter()
+            .filter(|entry| entry.name == name)
+            .map(|entry| entry.version.clone())
+            .collect()
+    }
+
+    /// Calls `f` with the stored program named `name`, `None` if it does not exist.
+    fn with_program<T>(&self, name: &str, f: impl FnOnce(&mut StoredProgram) -> T) -> Option<T> {
+        self.state.lock().unwrap().programs.get_mut(name).map(f)
+    }
+
+    fn programs(&self, filter: impl Fn(&StoredProgram) -> bool) -> Vec<Program> {
+        self.state
+            .lock()
+            .unwrap()
+            .programs
+            .values()
+            .filter(|stored| filter(stored))
+            .map(|stored| stored.program.clone())
+            .collect()
+    }
+}
+
+impl ProgramStore for MemoryStore {
+    async fn get_program(&self, name: &str) -> Result<Option<Program>> {
+        Ok(self.with_program(name, |stored| stored.program.clone()))
+    }
+
+    async fn get_all_programs(&self) -> Result<Vec<Program>> {
+        Ok(self.programs(|_| true))
+    }
+
+    async fn get_programs_by_provider(&self, provider: &str) -> Result<Vec<Program>> {
+        Ok(self.programs(|stored| stored.program.provider.identifier() == provider))
+    }
+
+    async fn get_failed_programs(&self, provider: Option<&str>) -> Result<Vec<Program>> {
+        Ok(self.programs(|stored| {
+            stored.last_error.is_some()
+                && provider.is_none_or(|provider| stored.program.provider.identifier() == provider)
+        }))
+    }
+
+    async fn insert_program(&self, program: &Program) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if state.programs.contains_key(&program.name) {
+            anyhow::bail!("Program named {} already exists", program.name);
+        }
+        state.programs.insert(
+            program.name.clone(),
+            StoredProgram {
+                program: program.clone(),
+                notification_sent: false,
+                notification_sent_on: None,
+                backoff: None,
+                last_error: None,
+            },
+        );
+        Ok(())
+    }
+
+    async fn remove_program(&self, name: &str) -> Result<()> {
+        if self.state.lock().unwrap().programs.remove(name).is_none() {
+            anyhow::bail!("Program named {name} does not exist");
+        }
+        Ok(())
+    }
+
+    async fn update_provider(&self, name: &str, provider: &Provider) -> Result<()> {
+        self.with_program(name, |stored| stored.program.provider = provider.clone());
+        Ok(())
+    }
+
+    async fn apply_program_changes(&self, name: &str, changes: &ProgramChanges) -> Result<()> {
+        self.with_program(name, |stored| {
+            let program = &mut stored.program;
+            if let Some(last_checked) = changes.last_checked {
+                program.last_checked = Some(last_checked);
+            }
+            if let Some(older_upstream_version) = &changes.older_upstream_version {
+                program
+                    .older_upstream_version
+                    .clone_from(older_upstream_version);
+            }
+            if let Some(latest_version_published_at) = changes.latest_version_published_at {
+                program.latest_version_published_at = latest_version_published_at;
+            }
+            if let Some((latest_version, latest_version_last_updated)) = &changes.latest_version {
+                program.latest_version.clone_from(latest_version);
+                program.latest_version_last_updated = *latest_version_last_updated;
+            }
+            if let Some((current_version, current_version_last_updated)) = &changes.current_version
+            {
+                program.current_version.clone_from(current_version);
+                program.current_version_last_updated = *current_version_last_updated;
+            }
+            if let Some(notification_sent) = changes.notification_sent {
+                stored.notification_sent = notification_sent;
+            }
+            if changes.clear_notification_sent_on {
+                stored.notification_sent_on = None;
+            }
+            if changes.clear_last_error {
+                stored.last_error = None;
+            }
+        });
+        if let Some(found_version) = &changes.found_version {
+            self.state
+                .lock()
+                .unwrap()
+                .version_history
+                .push(VersionHistoryEntry {
+                    date: found_version.date,
+                    name: found_version.name.clone(),
+                    version: found_version.version.clone(),
+                });
+        }
+        Ok(())
+    }
+
+    async fn set_last_error(&self, name: &str, last_error: Option<&str>) -> Result<()> {
+        self.with_program(name, |stored| {
+            stored.last_error = last_error.map(str::to_string);
+        });
+        Ok(())
+    }
+
+    async fn get_products(&self) -> Result<HashMap<String, String>> {
+        Ok(self.state.lock().unwrap().products.clone())
+    }
+
+    async fn get_notification_info(&self, program_name: &str) -> Result<Option<NotificationInfo>> {
+        Ok(self.with_program(program_name, |stored| NotificationInfo {
+            sent: stored.notification_sent,
+            sent_on: stored.notification_sent_on,
+        }))
+    }
+
+    async fn set_notification_sent(
+        &self,
+        program_name: &str,
+        notification_sent: bool,
+    ) -> Result<()> {
+        self.with_program(program_name, |stored| {
+            stored.notification_sent = notification_sent;
+        });
+        Ok(())
+    }
+
+    async fn set_notification_sent_on(
+        &self,
+        program_name: &str,
+        notification_sent_on: Option<NaiveDateTime>,
+    ) -> Result<()> {
+        self.with_program(program_name, |stored| {
+            stored.notification_sent_on = notification_sent_on;
+        });
+        Ok(())
+    }
+
+    async fn get_notification_backoff(
+        &self,
+        program_name: &str,
+    ) -> Result<Option<NotificationBackoff>> {
+        Ok(self
+            .with_program(program_name, |stored| stored.backoff.clone())
+            .flatten())
+    }
+
+    async fn set_notification_backoff(
+        &self,
+        program_name: &str,
+        backoff: &NotificationBackoff,
+    ) -> Result<()> {
+        self.with_program(program_name, |stored| {
+            stored.backoff = Some(backoff.clone());
+        });
+        Ok(())
+    }
+
+    async fn insert_update_check_history(
+        &self,
+        update_check: &UpdateCheckHistoryEntry,
+    ) -> Result<()> {
+        self.state
+            .lock()
+            .unwrap()
+            .update_checks
+            .push(update_check.clone());
+        Ok(())
+    }
+
+    async fn get_latest_update_check_from_history(
+        &self,
+    ) -> Result<Option<UpdateCheckHistoryEntry>> {
+        Ok(self.state.lock().unwrap().update_checks.last().cloned())
+    }
+}
+
+/// Provider that always reports the version it was created with.
+#[derive(Debug)]
+pub struct StaticProvider(pub String);
+
+impl UpdateProvider for StaticProvider {
+    fn identifier(&self) -> String {
+        "static".to_string()
+    }
+
+    fn check_for_latest_version<'a>(
+        &'a self,
+        _client: &'a reqwest::Client,
+        _auth: &'a AuthContext,
+    ) -> LatestVersionFuture<'a> {
+        Box::pin(async move {
+            Ok(LatestVersion {
+                version: self.0.clone(),
+                renamed_repository: None,
+                published_at: None,
+            })
+        })
+    }
+
+    fn serialize_config(&self) -> String {
+        self.0.clone()
+    }
+
+    fn web_url(&self, _version: &str) -> Option<String> {
+        None
+    }
+}
+
+/// Provider for which the update check always fails, as if the program was not found.
+#[derive(Debug)]
+pub struct FailingProvider;
+
+impl UpdateProvider for FailingProvider {
+    fn identifier(&self) -> String {
+        "failing".to_string()
+    }
+
+    fn check_for_latest_version<'a>(
+        &'a self,
+        _client: &'a reqwest::Client,
+        _auth: &'a AuthContext,
+    ) -> LatestVersionFuture<'a> {
+        Box::pin(async { Err(ProviderError::NotFound) })
+    }
+
+    fn serialize_config(&self) -> String {
+        String::new()
+    }
+
+    fn web_url(&self, _version: &str) -> Option<String> {
+        None
+    }
+}
@@ -1,12 +1,25 @@
 use anyhow::Result;
 use sqlx::{SqlitePool, sqlite::SqliteConnectOptions};
 
+use crate::provider::{ProviderRegistry, UpdateProvider};
+
+#[cfg(test)]
+pub(crate) mod memory;
 mod program;
+mod store;
 mod update_check_history;
 mod update_history;
+mod verify;
+mod version_history;
+
+pub use program::ProgramChanges;
+pub use store::ProgramStore;
+pub use verify::DbInconsistency;
 
 pub struct Db {
     pub pool: SqlitePool,
+    /// Providers that are not built-in, programs of these providers store their settings in `provider_metadata`.
+    providers: ProviderRegistry,
 }
 
 impl Db {
@@ -22,18 +35,108 @@ impl Db {
         if let Err(e) = sqlx::migrate!().run(&pool).await {
             return Err(anyhow::anyhow!("Unable to apply migrations: {e}"));
         }
-        Ok(Self { pool })
+        Ok(Self::from_pool(pool))
+    }
+
+    /// Opens the database read-only, without applying migrations.
+    ///
+    /// Returns an error if the database does not exist or if the schema is outdated.
+    pub async fn connect_read_only(path: &str) -> Result<Self> {
+        let options = SqliteConnectOptions::new().filename(path).read_only(true);
+        let pool = SqlitePool::connect_with(options).await?;
+        let outdated = || {
+            anyhow::anyhow!(
+                "Database schema is outdated, run the command without --no-migrate or --read-only to update the schema"
+            )
+        };
+        let sql = r"SELECT version FROM _sqlx_migrations WHERE success = 1";
+        let applied = sqlx::query_as::<_, (i64,)>(sql)
+            .fetch_all(&pool)
+            .await
+            .map_err(|_| outdated())?
+            .into_iter()
+            .map(|(version,)| version)
+            .collect::<Vec<i64>>();
+        if sqlx::migrate!()
+            .iter()
+            .any(|migration| !applied.contains(&migration.version))
+        {
+            return Err(outdated());
+        }
+        Ok(Self::from_pool(pool))
+    }
+
+    fn from_pool(pool: SqlitePool) -> Self {
+        Self {
+            pool,
+            providers: ProviderRegistry::default(),
+        }
+    }
+
+    /// Registers a provider that is not built-in, so that programs using it can be read from the database.
+    ///
+    /// `factory` creates the provider from the settings that where returned by
+    /// [`UpdateProvider::serialize_config`] when the program was stored.
+    /// Fails if a provider with the identifier `identifier` is already registered.
+    pub fn register_provider<F>(&mut self, identifier: &str, factory: F) -> Result<()>
+    where
+        F: Fn(&str) -> Result<Box<dyn UpdateProvider>> + Send + Sync + 'static,
+    {
+        self.providers.register(identifier, factory)
     }
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
 
-    use sqlx::SqlitePool;
+    use sqlx::{SqlitePool, sqlite::SqliteConnectOptions};
 
     use super::Db;
 
     pub fn db(pool: SqlitePool) -> Db {
-        Db { pool }
+        Db::from_pool(pool)
+    }
+
+    /// Returns the path of a database file in the temp directory, that does not exist yet.
+    pub fn temp_db_path(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!(
+            "simple_update_checker_{name}_{}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        path.to_string_lossy().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_db_connect_read_only() {
+        let path = temp_db_path("read_only");
+        Db::connect(&path).await.unwrap().pool.close().await;
+        let db = Db::connect_read_only(&path).await.unwrap();
+        assert!(db.get_all_programs().await.unwrap().is_empty());
+        let sql = r"INSERT INTO update_history (date, name, old_version, updated_to) VALUES ('2025-03-12 13:45:00', 'alpha_tui', '1.0.0', '1.1.0')";
+        assert!(sqlx::query(sql).execute(&db.pool).await.is_err());
+        db.pool.close().await;
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_db_connect_read_only_outdated() {
+        let path = temp_db_path("read_only_outdated");
+        let options = SqliteConnectOptions::new()
+            .filename(&path)
+            .create_if_missing(true);
+        SqlitePool::connect_with(options)
+            .await
+            .unwrap()
+            .close()
+            .await;
+        let res = Db::connect_read_only(&path).await;
+        assert!(
+            res.err()
+                .unwrap()
+                .to_string()
+                .contains("without --no-migrate")
+        );
+        std::fs::remove_file(&path).unwrap();
     }
 }
@@ -0,0 +1,188 @@
+use anyhow::Result;
+use sqlx::types::chrono::NaiveDateTime;
+
+use crate::{VersionHistoryEntry, db::Db};
+
+/// Changes to a program that are the result of an update check, stored at once with [`Db::apply_program_changes`].
+#[derive(Debug, Default)]
+pub struct ProgramChanges {
+    /// Time at which the program was checked for updates.
+    pub last_checked: Option<NaiveDateTime>,
+    /// New value of `older_upstream_version`, `Some(None)` clears it.
+    pub older_upstream_version: Option<Option<String>>,
+    /// New value of `latest_version_published_at`.
+    pub latest_version_published_at: Option<Option<NaiveDateTime>>,
+    /// New latest version and the time at which it was found.
+    pub latest_version: Option<(String, NaiveDateTime)>,
+    /// New current version and the time at which it was set.
+    pub current_version: Option<(String, NaiveDateTime)>,
+    /// New value of `notification_sent`.
+    pub notification_sent: Option<bool>,
+    /// Set when `notification_sent_on` should be cleared.
+    pub clear_notification_sent_on: bool,
+    /// Set when the error of the last update check should be cleared.
+    pub clear_last_error: bool,
+    /// Version that is added to the version history.
+    pub found_version: Option<VersionHistoryEntry>,
+}
+
+impl Db {
+    /// Stores all changes to the program named `name` in a single transaction.
+    pub async fn apply_program_changes(&self, name: &str, changes: &ProgramChanges) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        if let Some(last_checked) = changes.last_checked {
+            let sql = r"UPDATE programs SET last_checked = ? WHERE name = ?";
+            sqlx::query(sql)
+                .bind(last_checked)
+                .bind(name)
+                .execute(&mut *tx)
+                .await?;
+        }
+        if let Some(older_upstream_version) = &changes.older_upstream_version {
+            let sql = r"UPDATE programs SET older_upstream_version = ? WHERE name = ?";
+            sqlx::query(sql)
+                .bind(older_upstream_version)
+                .bind(name)
+                .execute(&mut *tx)
+                .await?;
+        }
+        if let Some(latest_version_published_at) = changes.latest_version_published_at {
+            let sql = r"UPDATE programs SET latest_version_published_at = ? WHERE name = ?";
+            sqlx::query(sql)
+                .bind(latest_version_published_at)
+                .bind(name)
+                .execute(&mut *tx)
+                .await?;
+        }
+        if let Some((latest_version, latest_version_last_updated)) = &changes.latest_version {
+            let sql = r"UPDATE programs SET latest_version = ?, latest_version_last_updated = ? WHERE name = ?";
+            sqlx::query(sql)
+                .bind(latest_version)
+                .bind(latest_version_last_updated)
+                .bind(name)
+                .execute(&mut *tx)
+                .await?;
+        }
+        if let Some((current_version, current_version_last_updated)) = &changes.current_version {
+            let sql = r"UPDATE programs SET current_version = ?, current_version_last_updated = ? WHERE name = ?";
+            sqlx::query(sql)
+                .bind(current_version)
+                .bind(current_version_last_updated)
+                .bind(name)
+                .execute(&mut *tx)
+                .await?;
+        }
+        if let Some(notification_sent) = changes.notification_sent {
+            let sql = r"UPDATE programs SET notification_sent = ? WHERE name = ?";
+            sqlx::query(sql)
+                .bind(notification_sent)
+                .bind(name)
+                .execute(&mut *tx)
+                .await?;
+        }
+        if changes.clear_notification_sent_on {
+            let sql = r"UPDATE programs SET notification_sent_on = NULL WHERE name = ?";
+            sqlx::query(sql).bind(name).execute(&mut *tx).await?;
+        }
+        if changes.clear_last_error {
+            let sql = r"UPDATE programs SET last_error = NULL WHERE name = ?";
+            sqlx::query(sql).bind(name).execute(&mut *tx).await?;
+        }
+        if let Some(found_version) = &changes.found_version {
+            let sql = r"INSERT INTO version_history (date, name, version) VALUES (?, ?, ?)";
+            sqlx::query(sql)
+                .bind(found_version.date)
+                .bind(&found_version.name)
+                .bind(&found_version.version)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::{
+        SqlitePool,
+        types::chrono::{NaiveDate, NaiveDateTime, NaiveTime},
+    };
+
+    use crate::{GithubProvider, Program, Provider, VersionHistoryEntry, db};
+
+    use super::ProgramChanges;
+
+    #[sqlx::test]
+    fn test_apply_program_changes(pool: SqlitePool) {
+        let db = db::tests::db(pool);
+        let date = NaiveDateTime::new(
+            NaiveDate::from_ymd_opt(2025, 3, 12).unwrap(),
+            NaiveTime::from_hms_opt(13, 45, 0).unwrap(),
+        );
+        db.insert_program(
+            &Program::builder()
+                .name("alpha_tui")
+                .current_version("v1.0.0")
+                .current_version_last_updated(date)
+                .latest_version("v1.0.0")
+                .latest_version_last_updated(date)
+                .provider(Provider::Github(GithubProvider::new("LMH01/alpha_tui")))
+                .older_upstream_version(Some("v0.9.0"))
+                .build()
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+        db.set_notification_sent("alpha_tui", true).await.unwrap();
+        db.set_notification_sent_on("alpha_tui", Some(date))
+            .await
+            .unwrap();
+        db.set_last_error("alpha_tui", Some("Not found"))
+            .await
+            .unwrap();
+
+        db.apply_program_changes(
+            "alpha_tui",
+            &ProgramChanges {
+                last_checked: Some(date),
+                older_upstream_version: Some(None),
+                latest_version_published_at: Some(Some(date)),
+                latest_version: Some(("v1.1.0".to_string(), date)),
+                current_version: None,
+                notification_sent: Some(false),
+                clear_notification_sent_on: true,
+                clear_last_error: true,
+                found_version: Some(VersionHistoryEntry {
+                    date,
+                    name: "alpha_tui".to_string(),
+                    version: "v1.1.0".to_string(),
+                }),
+            },
+        )
+        .await
+        .unwrap();
+
+        let program = db.get_program("alpha_tui").await.unwrap().unwrap();
+        assert_eq!("v1.0.0", program.current_version);
+        assert_eq!("v1.1.0", program.latest_version);
+        assert_eq!(Some(date), program.latest_version_published_at);
+        assert_eq!(Some(date), program.last_checked);
+        assert_eq!(None, program.older_upstream_version);
+        let notification_info = db
+            .get_notification_info("alpha_tui")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!notification_info.sent);
+        assert_eq!(None, notification_info.sent_on);
+        assert_eq!(None, db.get_last_error("alpha_tui").await.unwrap());
+        assert_eq!(
+            1,
+            db.get_version_history("alpha_tui", None)
+                .await
+                .unwrap()
+                .len()
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use crate::db::Db;
+
+impl Db {
+    /// Adds the program named `component` as additional provider of the program named `name`.
+    ///
+    /// The program named `name` is then treated as product that is updated when any of its components is updated.
+    pub async fn add_component(&self, name: &str, component: &str) -> Result<()> {
+        let sql = r"INSERT INTO program_providers ('name', 'component') VALUES (?, ?)";
+        sqlx::query(sql)
+            .bind(name)
+            .bind(component)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Returns the names of the components of the program named `name`, sorted by name.
+    pub async fn get_components(&self, name: &str) -> Result<Vec<String>> {
+        let sql = r"SELECT component FROM program_providers WHERE name = ? ORDER BY component";
+        let rows = sqlx::query_as::<_, (String,)>(sql)
+            .bind(name)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.into_iter().map(|(component,)| component).collect())
+    }
+
+    /// Returns the name of the program of which the program named `component` is a component.
+    pub async fn get_product(&self, component: &str) -> Result<Option<String>> {
+        let sql = r"SELECT name FROM program_providers WHERE component = ?";
+        let row = sqlx::query_as::<_, (String,)>(sql)
+            .bind(component)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(row.map(|(name,)| name))
+    }
+
+    /// Returns the product of every program that is a component, the key is the name of the component.
+    pub async fn get_products(&self) -> Result<HashMap<String, String>> {
+        let sql = r"SELECT name, component FROM program_providers";
+        let rows = sqlx::query_as::<_, (String, String)>(sql)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(name, component)| (component, name))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::{
+        SqlitePool,
+        types::chrono::{NaiveDate, NaiveDateTime, NaiveTime},
+    };
+
+    use crate::{GithubProvider, Program, Provider, db};
+
+    fn program(name: &str) -> Program {
+        let date = NaiveDateTime::new(
+            NaiveDate::from_ymd_opt(2025, 3, 12).unwrap(),
+            NaiveTime::from_hms_opt(13, 45, 0).unwrap(),
+        );
+        Program::builder()
+            .name(name)
+            .current_version("v1.0.0")
+            .current_version_last_updated(date)
+            .latest_version("v1.0.0")
+            .latest_version_last_updated(date)
+            .provider(Provider::Github(GithubProvider::new(&format!(
+                "LMH01/{name}"
+            ))))
+            .build()
+            .unwrap()
+    }
+
+    #[sqlx::test]
+    fn test_components(pool: SqlitePool) {
+        let db = db::tests::db(pool);
+        for name in ["suite", "suite-cli", "suite-server", "alpha_tui"] {
+            db.insert_program(&program(name)).await.unwrap();
+        }
+        db.add_component("suite", "suite-server").await.unwrap();
+        db.add_component("suite", "suite-cli").await.unwrap();
+        assert_eq!(
+            vec!["suite-cli".to_string(), "suite-server".to_string()],
+            db.get_components("suite").await.unwrap()
+        );
+        assert!(db.get_components("alpha_tui").await.unwrap().is_empty());
+        assert_eq!(
+            Some("suite".to_string()),
+            db.get_product("suite-cli").await.unwrap()
+        );
+        assert_eq!(None, db.get_product("suite").await.unwrap());
+        let products = db.get_products().await.unwrap();
+        assert_eq!(2, products.len());
+        assert_eq!(Some(&"suite".to_string()), products.get("suite-server"));
+        // a program can only be a component of a single program
+        assert!(db.add_component("alpha_tui", "suite-cli").await.is_err());
+        // removing a program removes it from its product
+        db.remove_program("suite-cli").await.unwrap();
+        assert_eq!(
+            vec!["suite-server".to_string()],
+            db.get_components("suite").await.unwrap()
+        );
+        // removing a product keeps its components as single programs
+        db.remove_program("suite").await.unwrap();
+        assert!(db.get_products().await.unwrap().is_empty());
+        assert!(db.get_program("suite-server").await.unwrap().is_some());
+    }
+}
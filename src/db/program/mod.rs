@@ -1,18 +1,28 @@
+use std::str::FromStr;
+
 use anyhow::Result;
-use sqlx::types::chrono::NaiveDateTime;
+use sqlx::types::chrono::{NaiveDateTime, Utc};
 
-use crate::{Identifier, Program, Provider};
+use crate::{
+    CommandProvider, FDroidProvider, GithubCommitProvider, GithubProvider, HomebrewKind,
+    HomebrewProvider, Identifier, Program, Provider, ReleaseSelector, VersionConstraint,
+    VersionScheme,
+};
 
 use super::Db;
 
+mod changes;
+mod component;
 mod notification;
 mod version;
 
+pub use changes::ProgramChanges;
+
 impl Db {
     /// Add a program to the database.
     pub async fn insert_program(&self, program: &Program) -> Result<()> {
         // insert into programs table
-        let sql = r"INSERT INTO programs ('name','current_version', 'current_version_last_updated', 'latest_version', 'latest_version_last_updated' , 'provider') VALUES (?, ?, ?, ?, ?, ?)";
+        let sql = r"INSERT INTO programs ('name','current_version', 'current_version_last_updated', 'latest_version', 'latest_version_last_updated' , 'provider', 'pin_major', 'version_scheme', 'allow_rollback', 'older_upstream_version', 'version_constraint', 'latest_version_published_at', 'check_interval_secs', 'last_checked') VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
         let _ = sqlx::query(sql)
             .bind(&program.name)
             .bind(&program.current_version)
@@ -20,15 +30,71 @@ impl Db {
             .bind(&program.latest_version)
             .bind(program.latest_version_last_updated)
             .bind(program.provider.identifier())
+            .bind(program.pin_major)
+            .bind(program.version_scheme.identifier())
+            .bind(program.allow_rollback)
+            .bind(&program.older_upstream_version)
+            .bind(program.version_constraint.as_ref().map(ToString::to_string))
+            .bind(program.latest_version_published_at)
+            .bind(program.check_interval_secs)
+            .bind(program.last_checked)
             .fetch_all(&self.pool)
             .await?;
         // insert into provider specific table
         match &program.provider {
-            Provider::Github(repository) => {
-                let sql = r"INSERT INTO github_programs ('name', 'repository') VALUES (?, ?)";
+            Provider::Github(github) => {
+                let sql = r"INSERT INTO github_programs ('name', 'repository', 'required_asset_pattern', 'access_token_env', 'release_selector') VALUES (?, ?, ?, ?, ?)";
+                let _ = sqlx::query(sql)
+                    .bind(&program.name)
+                    .bind(&github.repository)
+                    .bind(&github.required_asset_pattern)
+                    .bind(&github.access_token_env)
+                    .bind(github.release_selector.identifier())
+                    .fetch_all(&self.pool)
+                    .await?;
+            }
+            Provider::GithubCommit(github_commit) => {
+                let sql = r"INSERT INTO githubcommit_programs ('name', 'repository', 'branch') VALUES (?, ?, ?)";
+                let _ = sqlx::query(sql)
+                    .bind(&program.name)
+                    .bind(&github_commit.repository)
+                    .bind(&github_commit.branch)
+                    .fetch_all(&self.pool)
+                    .await?;
+            }
+            Provider::Homebrew(homebrew) => {
+                let sql =
+                    r"INSERT INTO homebrew_programs ('name', 'package', 'kind') VALUES (?, ?, ?)";
+                let _ = sqlx::query(sql)
+                    .bind(&program.name)
+                    .bind(&homebrew.package)
+                    .bind(homebrew.kind.identifier())
+                    .fetch_all(&self.pool)
+                    .await?;
+            }
+            Provider::FDroid(fdroid) => {
+                let sql = r"INSERT INTO fdroid_programs ('name', 'package') VALUES (?, ?)";
                 let _ = sqlx::query(sql)
                     .bind(&program.name)
-                    .bind(repository)
+                    .bind(&fdroid.package)
+                    .fetch_all(&self.pool)
+                    .await?;
+            }
+            Provider::Command(command) => {
+                let sql =
+                    r"INSERT INTO command_programs ('name', 'command', 'regex') VALUES (?, ?, ?)";
+                let _ = sqlx::query(sql)
+                    .bind(&program.name)
+                    .bind(&command.command)
+                    .bind(&command.regex)
+                    .fetch_all(&self.pool)
+                    .await?;
+            }
+            Provider::Custom(custom) => {
+                let sql = r"UPDATE programs SET provider_metadata = ? WHERE name = ?";
+                let _ = sqlx::query(sql)
+                    .bind(custom.provider().serialize_config())
+                    .bind(&program.name)
                     .fetch_all(&self.pool)
                     .await?;
             }
@@ -47,7 +113,28 @@ impl Db {
                 let sql = r"DELETE FROM github_programs WHERE name = ?";
                 sqlx::query(sql).bind(name).execute(&self.pool).await?;
             }
+            Provider::GithubCommit(_) => {
+                let sql = r"DELETE FROM githubcommit_programs WHERE name = ?";
+                sqlx::query(sql).bind(name).execute(&self.pool).await?;
+            }
+            Provider::Homebrew(_) => {
+                let sql = r"DELETE FROM homebrew_programs WHERE name = ?";
+                sqlx::query(sql).bind(name).execute(&self.pool).await?;
+            }
+            Provider::FDroid(_) => {
+                let sql = r"DELETE FROM fdroid_programs WHERE name = ?";
+                sqlx::query(sql).bind(name).execute(&self.pool).await?;
+            }
+            Provider::Command(_) => {
+                let sql = r"DELETE FROM command_programs WHERE name = ?";
+                sqlx::query(sql).bind(name).execute(&self.pool).await?;
+            }
+            // the settings of custom providers are stored in the programs table
+            Provider::Custom(_) => (),
         }
+        // the components of a removed program are kept as single programs
+        let sql = r"DELETE FROM program_providers WHERE name = ?1 OR component = ?1";
+        sqlx::query(sql).bind(name).execute(&self.pool).await?;
         // Delete from main programs table
         let sql = r"DELETE FROM programs WHERE name = ?";
         sqlx::query(sql).bind(name).execute(&self.pool).await?;
@@ -55,17 +142,110 @@ impl Db {
         Ok(())
     }
 
+    /// Copies the program named `name` together with its provider specific settings to a new program named `new_name`.
+    ///
+    /// The current version of the new program is set to the latest version and the notification state is reset.
+    pub async fn clone_program(&self, name: &str, new_name: &str) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        let sql = r"SELECT provider FROM programs WHERE name = ?";
+        if sqlx::query_as::<_, (String,)>(sql)
+            .bind(new_name)
+            .fetch_optional(&mut *tx)
+            .await?
+            .is_some()
+        {
+            anyhow::bail!("Program named {new_name} already exists");
+        }
+        let Some((provider,)) = sqlx::query_as::<_, (String,)>(sql)
+            .bind(name)
+            .fetch_optional(&mut *tx)
+            .await?
+        else {
+            anyhow::bail!("Program named {name} does not exist");
+        };
+
+        let sql = r"INSERT INTO programs (name, current_version, current_version_last_updated, latest_version, latest_version_last_updated, provider, pin_major, version_scheme, allow_rollback, version_constraint, latest_version_published_at, check_interval_secs, provider_metadata) SELECT ?, latest_version, ?, latest_version, latest_version_last_updated, provider, pin_major, version_scheme, allow_rollback, version_constraint, latest_version_published_at, check_interval_secs, provider_metadata FROM programs WHERE name = ?";
+        sqlx::query(sql)
+            .bind(new_name)
+            .bind(Utc::now().naive_utc())
+            .bind(name)
+            .execute(&mut *tx)
+            .await?;
+        match provider.as_str() {
+            "github" => {
+                let sql = r"INSERT INTO github_programs (name, repository, required_asset_pattern, access_token_env, release_selector) SELECT ?, repository, required_asset_pattern, access_token_env, release_selector FROM github_programs WHERE name = ?";
+                sqlx::query(sql)
+                    .bind(new_name)
+                    .bind(name)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            "githubcommit" => {
+                let sql = r"INSERT INTO githubcommit_programs (name, repository, branch) SELECT ?, repository, branch FROM githubcommit_programs WHERE name = ?";
+                sqlx::query(sql)
+                    .bind(new_name)
+                    .bind(name)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            "homebrew" => {
+                let sql = r"INSERT INTO homebrew_programs (name, package, kind) SELECT ?, package, kind FROM homebrew_programs WHERE name = ?";
+                sqlx::query(sql)
+                    .bind(new_name)
+                    .bind(name)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            "fdroid" => {
+                let sql = r"INSERT INTO fdroid_programs (name, package) SELECT ?, package FROM fdroid_programs WHERE name = ?";
+                sqlx::query(sql)
+                    .bind(new_name)
+                    .bind(name)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            "command" => {
+                let sql = r"INSERT INTO command_programs (name, command, regex) SELECT ?, command, regex FROM command_programs WHERE name = ?";
+                sqlx::query(sql)
+                    .bind(new_name)
+                    .bind(name)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            // the settings of custom providers are copied together with the program
+            provider if self.providers.contains(provider) => {}
+            _ => anyhow::bail!("Program {name} uses unknown provider {provider}"),
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
     /// Retrieve program form database. If name of program is no found, returns 'None'.
     pub async fn get_program(&self, name: &str) -> Result<Option<Program>> {
         // Retrieve the basic program details
-        let sql = r"SELECT name, current_version, current_version_last_updated, latest_version, latest_version_last_updated, provider FROM programs WHERE name = ?";
-        let row =
-            sqlx::query_as::<_, (String, String, NaiveDateTime, String, NaiveDateTime, String)>(
-                sql,
-            )
-            .bind(name)
-            .fetch_optional(&self.pool)
-            .await?;
+        let sql = r"SELECT name, current_version, current_version_last_updated, latest_version, latest_version_last_updated, provider, pin_major, version_scheme, allow_rollback, older_upstream_version, version_constraint, latest_version_published_at, check_interval_secs, last_checked FROM programs WHERE name = ?";
+        let row = sqlx::query_as::<
+            _,
+            (
+                String,
+                String,
+                NaiveDateTime,
+                String,
+                NaiveDateTime,
+                String,
+                Option<u32>,
+                String,
+                bool,
+                Option<String>,
+                Option<String>,
+                Option<NaiveDateTime>,
+                Option<u32>,
+                Option<NaiveDateTime>,
+            ),
+        >(sql)
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await?;
 
         let Some((
             name,
@@ -74,28 +254,21 @@ impl Db {
             latest_version,
             latest_version_last_updated,
             provider,
+            pin_major,
+            version_scheme,
+            allow_rollback,
+            older_upstream_version,
+            version_constraint,
+            latest_version_published_at,
+            check_interval_secs,
+            last_checked,
         )) = row
         else {
             return Ok(None);
         };
 
         // Determine the provider type and fetch additional data if needed
-        let provider = match provider.as_str() {
-            "github" => {
-                let sql = r"SELECT repository FROM github_programs WHERE name = ?";
-                match sqlx::query_as::<_, (String,)>(sql)
-                    .bind(&name)
-                    .fetch_optional(&self.pool)
-                    .await?
-                {
-                    Some((repository,)) => Provider::Github(repository),
-                    _ => {
-                        anyhow::bail!("Github repository entry missing for program: {}", name);
-                    }
-                }
-            }
-            _ => anyhow::bail!("Unknown provider type: {}", provider),
-        };
+        let provider = self.get_provider(&name, &provider).await?;
 
         Ok(Some(Program {
             name,
@@ -104,17 +277,64 @@ impl Db {
             latest_version,
             latest_version_last_updated,
             provider,
+            pin_major,
+            version_scheme: VersionScheme::from_str(&version_scheme)?,
+            allow_rollback,
+            older_upstream_version,
+            version_constraint: version_constraint
+                .map(|constraint| VersionConstraint::from_str(&constraint))
+                .transpose()?,
+            latest_version_published_at,
+            check_interval_secs,
+            last_checked,
         }))
     }
 
     /// Retrieve all programs from the database.
     pub async fn get_all_programs(&self) -> Result<Vec<Program>> {
-        // Retrieve all programs
-        let sql = r"SELECT name, current_version, current_version_last_updated, latest_version, latest_version_last_updated, provider FROM programs";
+        self.get_programs(None, false).await
+    }
+
+    /// Retrieve all programs that use the provider with the identifier `provider` from the database.
+    pub async fn get_programs_by_provider(&self, provider: &str) -> Result<Vec<Program>> {
+        self.get_programs(Some(provider), false).await
+    }
+
+    /// Retrieve all programs for which the last update check failed, optionally only those that use the provider with
+    /// the identifier `provider`.
+    pub async fn get_failed_programs(&self, provider: Option<&str>) -> Result<Vec<Program>> {
+        self.get_programs(provider, true).await
+    }
+
+    /// Retrieve all programs from the database, if `provider` is set only programs using that provider are returned.
+    /// If `failed_only` is set, only programs for which the last update check failed are returned.
+    async fn get_programs(
+        &self,
+        provider: Option<&str>,
+        failed_only: bool,
+    ) -> Result<Vec<Program>> {
+        let sql = r"SELECT name, current_version, current_version_last_updated, latest_version, latest_version_last_updated, provider, pin_major, version_scheme, allow_rollback, older_upstream_version, version_constraint, latest_version_published_at, check_interval_secs, last_checked FROM programs WHERE (?1 IS NULL OR provider = ?1) AND (NOT ?2 OR last_error IS NOT NULL)";
         let rows = sqlx::query_as::<
             _,
-            (String, String, NaiveDateTime, String, NaiveDateTime, String),
+            (
+                String,
+                String,
+                NaiveDateTime,
+                String,
+                NaiveDateTime,
+                String,
+                Option<u32>,
+                String,
+                bool,
+                Option<String>,
+                Option<String>,
+                Option<NaiveDateTime>,
+                Option<u32>,
+                Option<NaiveDateTime>,
+            ),
         >(sql)
+        .bind(provider)
+        .bind(failed_only)
         .fetch_all(&self.pool)
         .await?;
 
@@ -126,24 +346,17 @@ impl Db {
             latest_version,
             latest_version_last_updated,
             provider,
+            pin_major,
+            version_scheme,
+            allow_rollback,
+            older_upstream_version,
+            version_constraint,
+            latest_version_published_at,
+            check_interval_secs,
+            last_checked,
         ) in rows
         {
-            let provider = match provider.as_str() {
-                "github" => {
-                    let sql = r"SELECT repository FROM github_programs WHERE name = ?";
-                    match sqlx::query_as::<_, (String,)>(sql)
-                        .bind(&name)
-                        .fetch_optional(&self.pool)
-                        .await?
-                    {
-                        Some((repository,)) => Provider::Github(repository),
-                        _ => {
-                            anyhow::bail!("Github repository entry missing for program: {}", name);
-                        }
-                    }
-                }
-                _ => anyhow::bail!("Unknown provider type: {}", provider),
-            };
+            let provider = self.get_provider(&name, &provider).await?;
 
             programs.push(Program {
                 name,
@@ -152,11 +365,213 @@ impl Db {
                 latest_version,
                 latest_version_last_updated,
                 provider,
+                pin_major,
+                version_scheme: VersionScheme::from_str(&version_scheme)?,
+                allow_rollback,
+                older_upstream_version,
+                version_constraint: version_constraint
+                    .map(|constraint| VersionConstraint::from_str(&constraint))
+                    .transpose()?,
+                latest_version_published_at,
+                check_interval_secs,
+                last_checked,
             });
         }
 
         Ok(programs)
     }
+
+    /// Retrieve the error of the last update check of the program named `name`, `None` if it succeeded.
+    pub async fn get_last_error(&self, name: &str) -> Result<Option<String>> {
+        let sql = r"SELECT last_error FROM programs WHERE name = ?";
+        Ok(sqlx::query_scalar::<_, Option<String>>(sql)
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await?
+            .flatten())
+    }
+
+    /// Stores the error of the last update check of the program named `name`, `None` clears it.
+    pub async fn set_last_error(&self, name: &str, last_error: Option<&str>) -> Result<()> {
+        let sql = r"UPDATE programs SET last_error = ? WHERE name = ?";
+        sqlx::query(sql)
+            .bind(last_error)
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Updates the provider specific settings of the program named `name`, e.g. after the repository was renamed.
+    pub async fn update_provider(&self, name: &str, provider: &Provider) -> Result<()> {
+        match provider {
+            Provider::Github(github) => {
+                let sql = r"UPDATE github_programs SET repository = ?, required_asset_pattern = ?, access_token_env = ?, release_selector = ? WHERE name = ?";
+                sqlx::query(sql)
+                    .bind(&github.repository)
+                    .bind(&github.required_asset_pattern)
+                    .bind(&github.access_token_env)
+                    .bind(github.release_selector.identifier())
+                    .bind(name)
+                    .execute(&self.pool)
+                    .await?;
+            }
+            Provider::GithubCommit(github_commit) => {
+                let sql =
+                    r"UPDATE githubcommit_programs SET repository = ?, branch = ? WHERE name = ?";
+                sqlx::query(sql)
+                    .bind(&github_commit.repository)
+                    .bind(&github_commit.branch)
+                    .bind(name)
+                    .execute(&self.pool)
+                    .await?;
+            }
+            Provider::Homebrew(homebrew) => {
+                let sql = r"UPDATE homebrew_programs SET package = ?, kind = ? WHERE name = ?";
+                sqlx::query(sql)
+                    .bind(&homebrew.package)
+                    .bind(homebrew.kind.identifier())
+                    .bind(name)
+                    .execute(&self.pool)
+                    .await?;
+            }
+            Provider::FDroid(fdroid) => {
+                let sql = r"UPDATE fdroid_programs SET package = ? WHERE name = ?";
+                sqlx::query(sql)
+                    .bind(&fdroid.package)
+                    .bind(name)
+                    .execute(&self.pool)
+                    .await?;
+            }
+            Provider::Command(command) => {
+                let sql = r"UPDATE command_programs SET command = ?, regex = ? WHERE name = ?";
+                sqlx::query(sql)
+                    .bind(&command.command)
+                    .bind(&command.regex)
+                    .bind(name)
+                    .execute(&self.pool)
+                    .await?;
+            }
+            Provider::Custom(custom) => {
+                let sql = r"UPDATE programs SET provider_metadata = ? WHERE name = ?";
+                sqlx::query(sql)
+                    .bind(custom.provider().serialize_config())
+                    .bind(name)
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Retrieve the provider of the program named `name` from the provider specific table.
+    ///
+    /// `provider` is the identifier of the provider that is stored in the programs table.
+    /// Providers that are not built-in are created by the registered factory from the `provider_metadata` column.
+    async fn get_provider(&self, name: &str, provider: &str) -> Result<Provider> {
+        match provider {
+            "github" => {
+                let sql = r"SELECT repository, required_asset_pattern, access_token_env, release_selector FROM github_programs WHERE name = ?";
+                match sqlx::query_as::<_, (String, Option<String>, Option<String>, String)>(sql)
+                    .bind(name)
+                    .fetch_optional(&self.pool)
+                    .await?
+                {
+                    Some((
+                        repository,
+                        required_asset_pattern,
+                        access_token_env,
+                        release_selector,
+                    )) => Ok(Provider::Github(GithubProvider {
+                        repository,
+                        required_asset_pattern,
+                        access_token_env,
+                        release_selector: ReleaseSelector::from_str(&release_selector)?,
+                    })),
+                    _ => {
+                        anyhow::bail!("Github repository entry missing for program: {}", name);
+                    }
+                }
+            }
+            "githubcommit" => {
+                let sql = r"SELECT repository, branch FROM githubcommit_programs WHERE name = ?";
+                match sqlx::query_as::<_, (String, Option<String>)>(sql)
+                    .bind(name)
+                    .fetch_optional(&self.pool)
+                    .await?
+                {
+                    Some((repository, branch)) => {
+                        Ok(Provider::GithubCommit(GithubCommitProvider {
+                            repository,
+                            branch,
+                        }))
+                    }
+                    _ => {
+                        anyhow::bail!("Github repository entry missing for program: {}", name);
+                    }
+                }
+            }
+            "homebrew" => {
+                let sql = r"SELECT package, kind FROM homebrew_programs WHERE name = ?";
+                match sqlx::query_as::<_, (String, String)>(sql)
+                    .bind(name)
+                    .fetch_optional(&self.pool)
+                    .await?
+                {
+                    Some((package, kind)) => Ok(Provider::Homebrew(HomebrewProvider {
+                        package,
+                        kind: HomebrewKind::from_str(&kind)?,
+                    })),
+                    _ => {
+                        anyhow::bail!("Homebrew package entry missing for program: {}", name);
+                    }
+                }
+            }
+            "fdroid" => {
+                let sql = r"SELECT package FROM fdroid_programs WHERE name = ?";
+                match sqlx::query_as::<_, (String,)>(sql)
+                    .bind(name)
+                    .fetch_optional(&self.pool)
+                    .await?
+                {
+                    Some((package,)) => Ok(Provider::FDroid(FDroidProvider { package })),
+                    _ => {
+                        anyhow::bail!("F-Droid package entry missing for program: {}", name);
+                    }
+                }
+            }
+            "command" => {
+                let sql = r"SELECT command, regex FROM command_programs WHERE name = ?";
+                match sqlx::query_as::<_, (String, String)>(sql)
+                    .bind(name)
+                    .fetch_optional(&self.pool)
+                    .await?
+                {
+                    Some((command, regex)) => {
+                        Ok(Provider::Command(CommandProvider { command, regex }))
+                    }
+                    _ => {
+                        anyhow::bail!("Command entry missing for program: {}", name);
+                    }
+                }
+            }
+            _ => {
+                let sql = r"SELECT provider_metadata FROM programs WHERE name = ?";
+                let (config,) = sqlx::query_as::<_, (Option<String>,)>(sql)
+                    .bind(name)
+                    .fetch_one(&self.pool)
+                    .await?;
+                match self
+                    .providers
+                    .create(provider, config.as_deref().unwrap_or_default())
+                {
+                    Some(provider) => provider,
+                    None => anyhow::bail!("Unknown provider type: {}", provider),
+                }
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -166,39 +581,49 @@ mod tests {
         types::chrono::{NaiveDate, NaiveDateTime, NaiveTime},
     };
 
-    use crate::{Program, Provider, db::tests};
+    use crate::{
+        CommandProvider, FDroidProvider, GithubCommitProvider, GithubProvider, HomebrewKind,
+        HomebrewProvider, Program, Provider, ReleaseSelector, VersionScheme,
+        db::{memory::StaticProvider, tests},
+        http,
+        provider::{AuthContext, CustomProvider},
+    };
 
     #[sqlx::test]
     fn test_db_programs(pool: SqlitePool) {
         let db = tests::db(pool);
-        let program = Program {
-            name: "simple_update_checker".to_string(),
-            current_version: "0.1.0".to_string(),
-            current_version_last_updated: NaiveDateTime::new(
+        let program = Program::builder()
+            .name("simple_update_checker")
+            .current_version("0.1.0")
+            .current_version_last_updated(NaiveDateTime::new(
                 NaiveDate::parse_from_str("10.03.2025", "%d.%m.%Y").unwrap(),
                 NaiveTime::parse_from_str("10:50:00", "%H:%M:%S").unwrap(),
-            ),
-            latest_version: "0.1.0".to_string(),
-            latest_version_last_updated: NaiveDateTime::new(
+            ))
+            .latest_version("0.1.0")
+            .latest_version_last_updated(NaiveDateTime::new(
                 NaiveDate::parse_from_str("12.03.2025", "%d.%m.%Y").unwrap(),
                 NaiveTime::parse_from_str("13:45:00", "%H:%M:%S").unwrap(),
-            ),
-            provider: Provider::Github("LMH01/simple_update_checker".to_string()),
-        };
-        let program2 = Program {
-            name: "test_program".to_string(),
-            current_version: "0.1.0".to_string(),
-            current_version_last_updated: NaiveDateTime::new(
+            ))
+            .provider(Provider::Github(GithubProvider::new(
+                "LMH01/simple_update_checker",
+            )))
+            .build()
+            .unwrap();
+        let program2 = Program::builder()
+            .name("test_program")
+            .current_version("0.1.0")
+            .current_version_last_updated(NaiveDateTime::new(
                 NaiveDate::parse_from_str("10.03.2025", "%d.%m.%Y").unwrap(),
                 NaiveTime::parse_from_str("10:50:00", "%H:%M:%S").unwrap(),
-            ),
-            latest_version_last_updated: NaiveDateTime::new(
+            ))
+            .latest_version_last_updated(NaiveDateTime::new(
                 NaiveDate::parse_from_str("12.03.2025", "%d.%m.%Y").unwrap(),
                 NaiveTime::parse_from_str("13:45:00", "%H:%M:%S").unwrap(),
-            ),
-            latest_version: "0.1.0".to_string(),
-            provider: Provider::Github("LMH01/test_program".to_string()),
-        };
+            ))
+            .latest_version("0.1.0")
+            .provider(Provider::Github(GithubProvider::new("LMH01/test_program")))
+            .build()
+            .unwrap();
         db.insert_program(&program).await.unwrap();
         let res = db.get_program(&program.name).await.unwrap();
         assert_eq!(Some(program), res);
@@ -209,20 +634,23 @@ mod tests {
     #[sqlx::test]
     fn test_db_remove_program(pool: SqlitePool) {
         let db = tests::db(pool);
-        let program = Program {
-            name: "simple_update_checker".to_string(),
-            current_version: "0.1.0".to_string(),
-            current_version_last_updated: NaiveDateTime::new(
+        let program = Program::builder()
+            .name("simple_update_checker")
+            .current_version("0.1.0")
+            .current_version_last_updated(NaiveDateTime::new(
                 NaiveDate::parse_from_str("10.03.2025", "%d.%m.%Y").unwrap(),
                 NaiveTime::parse_from_str("10:50:00", "%H:%M:%S").unwrap(),
-            ),
-            latest_version: "0.1.0".to_string(),
-            latest_version_last_updated: NaiveDateTime::new(
+            ))
+            .latest_version("0.1.0")
+            .latest_version_last_updated(NaiveDateTime::new(
                 NaiveDate::parse_from_str("12.03.2025", "%d.%m.%Y").unwrap(),
                 NaiveTime::parse_from_str("13:45:00", "%H:%M:%S").unwrap(),
-            ),
-            provider: Provider::Github("LMH01/simple_update_checker".to_string()),
-        };
+            ))
+            .provider(Provider::Github(GithubProvider::new(
+                "LMH01/simple_update_checker",
+            )))
+            .build()
+            .unwrap();
         db.insert_program(&program).await.unwrap();
         db.remove_program(&program.name).await.unwrap();
         let res = db.get_program(&program.name).await.unwrap();
@@ -232,34 +660,38 @@ mod tests {
     #[sqlx::test]
     fn test_db_get_all_programs(pool: SqlitePool) {
         let db = tests::db(pool);
-        let program = Program {
-            name: "simple_update_checker".to_string(),
-            current_version: "0.1.0".to_string(),
-            current_version_last_updated: NaiveDateTime::new(
+        let program = Program::builder()
+            .name("simple_update_checker")
+            .current_version("0.1.0")
+            .current_version_last_updated(NaiveDateTime::new(
                 NaiveDate::parse_from_str("10.03.2025", "%d.%m.%Y").unwrap(),
                 NaiveTime::parse_from_str("10:50:00", "%H:%M:%S").unwrap(),
-            ),
-            latest_version: "0.1.0".to_string(),
-            latest_version_last_updated: NaiveDateTime::new(
+            ))
+            .latest_version("0.1.0")
+            .latest_version_last_updated(NaiveDateTime::new(
                 NaiveDate::parse_from_str("12.03.2025", "%d.%m.%Y").unwrap(),
                 NaiveTime::parse_from_str("13:45:00", "%H:%M:%S").unwrap(),
-            ),
-            provider: Provider::Github("LMH01/simple_update_checker".to_string()),
-        };
-        let program2 = Program {
-            name: "test_program".to_string(),
-            current_version: "0.1.0".to_string(),
-            current_version_last_updated: NaiveDateTime::new(
+            ))
+            .provider(Provider::Github(GithubProvider::new(
+                "LMH01/simple_update_checker",
+            )))
+            .build()
+            .unwrap();
+        let program2 = Program::builder()
+            .name("test_program")
+            .current_version("0.1.0")
+            .current_version_last_updated(NaiveDateTime::new(
                 NaiveDate::parse_from_str("10.03.2025", "%d.%m.%Y").unwrap(),
                 NaiveTime::parse_from_str("10:50:00", "%H:%M:%S").unwrap(),
-            ),
-            latest_version: "0.1.0".to_string(),
-            latest_version_last_updated: NaiveDateTime::new(
+            ))
+            .latest_version("0.1.0")
+            .latest_version_last_updated(NaiveDateTime::new(
                 NaiveDate::parse_from_str("12.03.2025", "%d.%m.%Y").unwrap(),
                 NaiveTime::parse_from_str("13:45:00", "%H:%M:%S").unwrap(),
-            ),
-            provider: Provider::Github("LMH01/test_program".to_string()),
-        };
+            ))
+            .provider(Provider::Github(GithubProvider::new("LMH01/test_program")))
+            .build()
+            .unwrap();
         db.insert_program(&program).await.unwrap();
         db.insert_program(&program2).await.unwrap();
         let mut should = vec![program, program2];
@@ -267,5 +699,354 @@ mod tests {
         let mut res = db.get_all_programs().await.unwrap();
         res.sort_by(|a, b| a.name.cmp(&b.name));
         assert_eq!(should, res);
+        let mut res = db.get_programs_by_provider("github").await.unwrap();
+        res.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(should, res);
+        let res = db.get_programs_by_provider("unknown").await.unwrap();
+        assert!(res.is_empty());
+    }
+
+    #[sqlx::test]
+    fn test_db_program_settings(pool: SqlitePool) {
+        let db = tests::db(pool);
+        let program = Program::builder()
+            .name("simple_update_checker")
+            .current_version("0.1.0")
+            .current_version_last_updated(NaiveDateTime::new(
+                NaiveDate::parse_from_str("10.03.2025", "%d.%m.%Y").unwrap(),
+                NaiveTime::parse_from_str("10:50:00", "%H:%M:%S").unwrap(),
+            ))
+            .latest_version("0.1.0")
+            .latest_version_last_updated(NaiveDateTime::new(
+                NaiveDate::parse_from_str("12.03.2025", "%d.%m.%Y").unwrap(),
+                NaiveTime::parse_from_str("13:45:00", "%H:%M:%S").unwrap(),
+            ))
+            .provider(Provider::Github(GithubProvider {
+                repository: "LMH01/simple_update_checker".to_string(),
+                required_asset_pattern: Some("*linux-x86_64*".to_string()),
+                access_token_env: Some("WORK_GITHUB_TOKEN".to_string()),
+                release_selector: ReleaseSelector::Newest,
+            }))
+            .pin_major(Some(3))
+            .version_scheme(VersionScheme::Calver)
+            .version_constraint(Some("^3".parse().unwrap()))
+            .build()
+            .unwrap();
+        db.insert_program(&program).await.unwrap();
+        let res = db.get_program(&program.name).await.unwrap();
+        assert_eq!(Some(program), res);
+    }
+
+    #[sqlx::test]
+    fn test_db_github_commit_program(pool: SqlitePool) {
+        let db = tests::db(pool);
+        let program = Program::builder()
+            .name("alpha_tui_main")
+            .current_version("1a2b3c4")
+            .current_version_last_updated(NaiveDateTime::new(
+                NaiveDate::parse_from_str("10.03.2025", "%d.%m.%Y").unwrap(),
+                NaiveTime::parse_from_str("10:50:00", "%H:%M:%S").unwrap(),
+            ))
+            .latest_version("5d6e7f8")
+            .latest_version_last_updated(NaiveDateTime::new(
+                NaiveDate::parse_from_str("12.03.2025", "%d.%m.%Y").unwrap(),
+                NaiveTime::parse_from_str("13:45:00", "%H:%M:%S").unwrap(),
+            ))
+            .provider(Provider::GithubCommit(GithubCommitProvider {
+                repository: "LMH01/alpha_tui".to_string(),
+                branch: Some("dev".to_string()),
+            }))
+            .build()
+            .unwrap();
+        db.insert_program(&program).await.unwrap();
+        assert_eq!(
+            Some(program.clone()),
+            db.get_program(&program.name).await.unwrap()
+        );
+        assert_eq!(
+            vec![program.clone()],
+            db.get_programs_by_provider("githubcommit").await.unwrap()
+        );
+
+        db.clone_program(&program.name, "alpha_tui_main_2")
+            .await
+            .unwrap();
+        let res = db.get_program("alpha_tui_main_2").await.unwrap().unwrap();
+        assert_eq!(program.provider, res.provider);
+
+        db.remove_program(&program.name).await.unwrap();
+        assert_eq!(None, db.get_program(&program.name).await.unwrap());
+        assert!(db.find_inconsistencies().await.unwrap().is_empty());
+    }
+
+    #[sqlx::test]
+    fn test_db_homebrew_program(pool: SqlitePool) {
+        let db = tests::db(pool);
+        let program = Program::builder()
+            .name("firefox")
+            .current_version("131.0.2")
+            .current_version_last_updated(NaiveDateTime::new(
+                NaiveDate::parse_from_str("10.03.2025", "%d.%m.%Y").unwrap(),
+                NaiveTime::parse_from_str("10:50:00", "%H:%M:%S").unwrap(),
+            ))
+            .latest_version("131.0.3")
+            .latest_version_last_updated(NaiveDateTime::new(
+                NaiveDate::parse_from_str("12.03.2025", "%d.%m.%Y").unwrap(),
+                NaiveTime::parse_from_str("13:45:00", "%H:%M:%S").unwrap(),
+            ))
+            .provider(Provider::Homebrew(HomebrewProvider {
+                package: "firefox".to_string(),
+                kind: HomebrewKind::Cask,
+            }))
+            .build()
+            .unwrap();
+        db.insert_program(&program).await.unwrap();
+        assert_eq!(
+            vec![program.clone()],
+            db.get_programs_by_provider("homebrew").await.unwrap()
+        );
+
+        db.clone_program(&program.name, "firefox_2").await.unwrap();
+        let res = db.get_program("firefox_2").await.unwrap().unwrap();
+        assert_eq!(program.provider, res.provider);
+
+        db.remove_program(&program.name).await.unwrap();
+        assert_eq!(None, db.get_program(&program.name).await.unwrap());
+        assert!(db.find_inconsistencies().await.unwrap().is_empty());
+    }
+
+    #[sqlx::test]
+    fn test_db_fdroid_program(pool: SqlitePool) {
+        let db = tests::db(pool);
+        let program = Program::builder()
+            .name("fdroid")
+            .current_version("1.20.0")
+            .current_version_last_updated(NaiveDateTime::new(
+                NaiveDate::parse_from_str("10.03.2025", "%d.%m.%Y").unwrap(),
+                NaiveTime::parse_from_str("10:50:00", "%H:%M:%S").unwrap(),
+            ))
+            .latest_version("1.20.0")
+            .latest_version_last_updated(NaiveDateTime::new(
+                NaiveDate::parse_from_str("12.03.2025", "%d.%m.%Y").unwrap(),
+                NaiveTime::parse_from_str("13:45:00", "%H:%M:%S").unwrap(),
+            ))
+            .provider(Provider::FDroid(FDroidProvider {
+                package: "org.fdroid.fdroid".to_string(),
+            }))
+            .build()
+            .unwrap();
+        db.insert_program(&program).await.unwrap();
+        assert_eq!(
+            vec![program.clone()],
+            db.get_programs_by_provider("fdroid").await.unwrap()
+        );
+
+        db.clone_program(&program.name, "fdroid_2").await.unwrap();
+        let res = db.get_program("fdroid_2").await.unwrap().unwrap();
+        assert_eq!(program.provider, res.provider);
+
+        db.remove_program(&program.name).await.unwrap();
+        assert_eq!(None, db.get_program(&program.name).await.unwrap());
+        assert!(db.find_inconsistencies().await.unwrap().is_empty());
+    }
+
+    #[sqlx::test]
+    fn test_db_command_program(pool: SqlitePool) {
+        let db = tests::db(pool);
+        let program = Program::builder()
+            .name("kubectl")
+            .current_version("1.31.2")
+            .current_version_last_updated(NaiveDateTime::new(
+                NaiveDate::parse_from_str("10.03.2025", "%d.%m.%Y").unwrap(),
+                NaiveTime::parse_from_str("10:50:00", "%H:%M:%S").unwrap(),
+            ))
+            .latest_version("1.31.2")
+            .latest_version_last_updated(NaiveDateTime::new(
+                NaiveDate::parse_from_str("12.03.2025", "%d.%m.%Y").unwrap(),
+                NaiveTime::parse_from_str("13:45:00", "%H:%M:%S").unwrap(),
+            ))
+            .provider(Provider::Command(CommandProvider {
+                command: "kubectl version --client".to_string(),
+                regex: r"v(\d+\.\d+\.\d+)".to_string(),
+            }))
+            .build()
+            .unwrap();
+        db.insert_program(&program).await.unwrap();
+        assert_eq!(
+            vec![program.clone()],
+            db.get_programs_by_provider("command").await.unwrap()
+        );
+
+        db.clone_program(&program.name, "kubectl_2").await.unwrap();
+        let res = db.get_program("kubectl_2").await.unwrap().unwrap();
+        assert_eq!(program.provider, res.provider);
+
+        db.remove_program(&program.name).await.unwrap();
+        assert_eq!(None, db.get_program(&program.name).await.unwrap());
+        assert!(db.find_inconsistencies().await.unwrap().is_empty());
+    }
+
+    #[sqlx::test]
+    fn test_db_custom_program(pool: SqlitePool) {
+        let mut db = tests::db(pool.clone());
+        db.register_provider("static", |config| {
+            Ok(Box::new(StaticProvider(config.to_string())))
+        })
+        .unwrap();
+        assert!(
+            db.register_provider("static", |config| {
+                Ok(Box::new(StaticProvider(config.to_string())))
+            })
+            .is_err()
+        );
+        assert!(
+            db.register_provider("github", |config| {
+                Ok(Box::new(StaticProvider(config.to_string())))
+            })
+            .is_err()
+        );
+        let mut program = Program::new(
+            "internal_tool",
+            "1.2.0",
+            Provider::Custom(CustomProvider::new(StaticProvider("1.2.0".to_string()))),
+        );
+        db.insert_program(&program).await.unwrap();
+        assert_eq!(
+            vec![program.clone()],
+            db.get_programs_by_provider("static").await.unwrap()
+        );
+        assert!(db.find_inconsistencies().await.unwrap().is_empty());
+
+        program.provider =
+            Provider::Custom(CustomProvider::new(StaticProvider("1.3.0".to_string())));
+        db.update_provider(&program.name, &program.provider)
+            .await
+            .unwrap();
+        let res = db.get_program(&program.name).await.unwrap().unwrap();
+        assert_eq!(program.provider, res.provider);
+        assert_eq!(
+            "1.3.0",
+            res.provider
+                .check_for_latest_version(&http::client(), &AuthContext::default())
+                .await
+                .unwrap()
+                .version
+        );
+
+        db.clone_program(&program.name, "internal_tool_2")
+            .await
+            .unwrap();
+        let res = db.get_program("internal_tool_2").await.unwrap().unwrap();
+        assert_eq!(program.provider, res.provider);
+
+        // without the registered provider the program can not be read
+        let unregistered = tests::db(pool);
+        assert!(
+            unregistered
+                .get_program(&program.name)
+                .await
+                .unwrap_err()
+                .to_string()
+                .contains("Unknown provider type: static")
+        );
+
+        db.remove_program(&program.name).await.unwrap();
+        assert_eq!(None, db.get_program(&program.name).await.unwrap());
+    }
+
+    #[sqlx::test]
+    fn test_db_clone_program(pool: SqlitePool) {
+        let db = tests::db(pool);
+        let program = Program::builder()
+            .name("alpha_tui")
+            .current_version("v1.0.0")
+            .current_version_last_updated(NaiveDateTime::new(
+                NaiveDate::parse_from_str("10.03.2025", "%d.%m.%Y").unwrap(),
+                NaiveTime::parse_from_str("10:50:00", "%H:%M:%S").unwrap(),
+            ))
+            .latest_version("v1.1.0")
+            .latest_version_last_updated(NaiveDateTime::new(
+                NaiveDate::parse_from_str("12.03.2025", "%d.%m.%Y").unwrap(),
+                NaiveTime::parse_from_str("13:45:00", "%H:%M:%S").unwrap(),
+            ))
+            .provider(Provider::Github(GithubProvider {
+                repository: "LMH01/alpha_tui".to_string(),
+                required_asset_pattern: Some("*linux*".to_string()),
+                access_token_env: None,
+                release_selector: ReleaseSelector::Latest,
+            }))
+            .pin_major(Some(1))
+            .version_scheme(VersionScheme::Semver)
+            .allow_rollback(true)
+            .build()
+            .unwrap();
+        db.insert_program(&program).await.unwrap();
+        db.set_notification_sent(&program.name, true).await.unwrap();
+        db.clone_program("alpha_tui", "alpha_tui_nightly")
+            .await
+            .unwrap();
+
+        let res = db.get_program("alpha_tui_nightly").await.unwrap().unwrap();
+        assert_eq!("v1.1.0", res.current_version);
+        assert_eq!(
+            Program {
+                name: "alpha_tui_nightly".to_string(),
+                current_version: program.latest_version.clone(),
+                current_version_last_updated: res.current_version_last_updated,
+                ..program.clone()
+            },
+            res
+        );
+        let notification_info = db
+            .get_notification_info("alpha_tui_nightly")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!notification_info.sent);
+        // the source program is unchanged
+        assert_eq!(Some(program), db.get_program("alpha_tui").await.unwrap());
+
+        assert!(
+            db.clone_program("alpha_tui", "alpha_tui_nightly")
+                .await
+                .is_err()
+        );
+        assert!(db.clone_program("not_existing", "new").await.is_err());
+    }
+
+    #[sqlx::test]
+    fn test_db_update_provider(pool: SqlitePool) {
+        let db = tests::db(pool);
+        let mut program = Program::builder()
+            .name("alpha_tui")
+            .current_version("v1.0.0")
+            .current_version_last_updated(NaiveDateTime::new(
+                NaiveDate::parse_from_str("10.03.2025", "%d.%m.%Y").unwrap(),
+                NaiveTime::parse_from_str("10:50:00", "%H:%M:%S").unwrap(),
+            ))
+            .latest_version("v1.0.0")
+            .latest_version_last_updated(NaiveDateTime::new(
+                NaiveDate::parse_from_str("12.03.2025", "%d.%m.%Y").unwrap(),
+                NaiveTime::parse_from_str("13:45:00", "%H:%M:%S").unwrap(),
+            ))
+            .provider(Provider::Github(GithubProvider {
+                repository: "LMH01/alpha_tui".to_string(),
+                required_asset_pattern: Some("*linux*".to_string()),
+                access_token_env: None,
+                release_selector: ReleaseSelector::Latest,
+            }))
+            .build()
+            .unwrap();
+        db.insert_program(&program).await.unwrap();
+        program.provider = Provider::Github(GithubProvider {
+            repository: "alpha-tui/alpha_tui".to_string(),
+            required_asset_pattern: Some("*linux*".to_string()),
+            access_token_env: None,
+            release_selector: ReleaseSelector::Latest,
+        });
+        db.update_provider("alpha_tui", &program.provider)
+            .await
+            .unwrap();
+        assert_eq!(Some(program), db.get_program("alpha_tui").await.unwrap());
     }
 }
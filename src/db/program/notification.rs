@@ -1,7 +1,7 @@
 use anyhow::Result;
 use sqlx::types::chrono::NaiveDateTime;
 
-use crate::{NotificationInfo, db::Db};
+use crate::{NotificationBackoff, NotificationInfo, db::Db};
 
 impl Db {
     pub async fn set_notification_sent(
@@ -48,6 +48,43 @@ impl Db {
         }
         Ok(None)
     }
+
+    pub async fn get_notification_backoff(
+        &self,
+        program_name: &str,
+    ) -> Result<Option<NotificationBackoff>> {
+        let sql = r"SELECT notification_backoff_until, notification_backoff_secs, notified_version FROM programs WHERE name = ?";
+        if let Some((until, secs, notified_version)) =
+            sqlx::query_as::<_, (Option<NaiveDateTime>, Option<u32>, Option<String>)>(sql)
+                .bind(program_name)
+                .fetch_optional(&self.pool)
+                .await?
+        {
+            return Ok(Some(NotificationBackoff {
+                until,
+                secs,
+                notified_version,
+            }));
+        }
+        Ok(None)
+    }
+
+    pub async fn set_notification_backoff(
+        &self,
+        program_name: &str,
+        backoff: &NotificationBackoff,
+    ) -> Result<()> {
+        let sql = r"UPDATE programs SET notification_backoff_until = ?, notification_backoff_secs = ?, notified_version = ? WHERE name = ?";
+        sqlx::query(sql)
+            .bind(backoff.until)
+            .bind(backoff.secs)
+            .bind(&backoff.notified_version)
+            .bind(program_name)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -57,39 +94,43 @@ mod tests {
         types::chrono::{NaiveDate, NaiveDateTime, NaiveTime},
     };
 
-    use crate::{Program, Provider, db::tests};
+    use crate::{GithubProvider, NotificationBackoff, Program, Provider, db::tests};
 
     #[sqlx::test]
     fn test_db_set_notification_sent(pool: SqlitePool) {
         let db = tests::db(pool);
-        let program = Program {
-            name: "simple_update_checker".to_string(),
-            current_version: "0.1.0".to_string(),
-            current_version_last_updated: NaiveDateTime::new(
+        let program = Program::builder()
+            .name("simple_update_checker")
+            .current_version("0.1.0")
+            .current_version_last_updated(NaiveDateTime::new(
                 NaiveDate::parse_from_str("10.03.2025", "%d.%m.%Y").unwrap(),
                 NaiveTime::parse_from_str("10:50:00", "%H:%M:%S").unwrap(),
-            ),
-            latest_version: "0.1.0".to_string(),
-            latest_version_last_updated: NaiveDateTime::new(
+            ))
+            .latest_version("0.1.0")
+            .latest_version_last_updated(NaiveDateTime::new(
                 NaiveDate::parse_from_str("12.03.2025", "%d.%m.%Y").unwrap(),
                 NaiveTime::parse_from_str("13:45:00", "%H:%M:%S").unwrap(),
-            ),
-            provider: Provider::Github("LMH01/simple_update_checker".to_string()),
-        };
-        let program2 = Program {
-            name: "test_program".to_string(),
-            current_version: "0.1.0".to_string(),
-            current_version_last_updated: NaiveDateTime::new(
+            ))
+            .provider(Provider::Github(GithubProvider::new(
+                "LMH01/simple_update_checker",
+            )))
+            .build()
+            .unwrap();
+        let program2 = Program::builder()
+            .name("test_program")
+            .current_version("0.1.0")
+            .current_version_last_updated(NaiveDateTime::new(
                 NaiveDate::parse_from_str("10.03.2025", "%d.%m.%Y").unwrap(),
                 NaiveTime::parse_from_str("10:50:00", "%H:%M:%S").unwrap(),
-            ),
-            latest_version_last_updated: NaiveDateTime::new(
+            ))
+            .latest_version_last_updated(NaiveDateTime::new(
                 NaiveDate::parse_from_str("12.03.2025", "%d.%m.%Y").unwrap(),
                 NaiveTime::parse_from_str("13:45:00", "%H:%M:%S").unwrap(),
-            ),
-            latest_version: "0.1.0".to_string(),
-            provider: Provider::Github("LMH01/test_program".to_string()),
-        };
+            ))
+            .latest_version("0.1.0")
+            .provider(Provider::Github(GithubProvider::new("LMH01/test_program")))
+            .build()
+            .unwrap();
         db.insert_program(&program).await.unwrap();
         db.insert_program(&program2).await.unwrap();
         db.set_notification_sent("simple_update_checker", true)
@@ -101,47 +142,51 @@ mod tests {
             .unwrap()
             .unwrap()
             .sent;
-        assert_eq!(true, res);
+        assert!(res);
         let res = db
             .get_notification_info("test_program")
             .await
             .unwrap()
             .unwrap()
             .sent;
-        assert_eq!(false, res);
+        assert!(!res);
     }
 
     #[sqlx::test]
     fn test_db_set_notification_sent_on(pool: SqlitePool) {
         let db = tests::db(pool);
-        let program = Program {
-            name: "simple_update_checker".to_string(),
-            current_version: "0.1.0".to_string(),
-            current_version_last_updated: NaiveDateTime::new(
+        let program = Program::builder()
+            .name("simple_update_checker")
+            .current_version("0.1.0")
+            .current_version_last_updated(NaiveDateTime::new(
                 NaiveDate::parse_from_str("10.03.2025", "%d.%m.%Y").unwrap(),
                 NaiveTime::parse_from_str("10:50:00", "%H:%M:%S").unwrap(),
-            ),
-            latest_version: "0.1.0".to_string(),
-            latest_version_last_updated: NaiveDateTime::new(
+            ))
+            .latest_version("0.1.0")
+            .latest_version_last_updated(NaiveDateTime::new(
                 NaiveDate::parse_from_str("12.03.2025", "%d.%m.%Y").unwrap(),
                 NaiveTime::parse_from_str("13:45:00", "%H:%M:%S").unwrap(),
-            ),
-            provider: Provider::Github("LMH01/simple_update_checker".to_string()),
-        };
-        let program2 = Program {
-            name: "test_program".to_string(),
-            current_version: "0.1.0".to_string(),
-            current_version_last_updated: NaiveDateTime::new(
+            ))
+            .provider(Provider::Github(GithubProvider::new(
+                "LMH01/simple_update_checker",
+            )))
+            .build()
+            .unwrap();
+        let program2 = Program::builder()
+            .name("test_program")
+            .current_version("0.1.0")
+            .current_version_last_updated(NaiveDateTime::new(
                 NaiveDate::parse_from_str("10.03.2025", "%d.%m.%Y").unwrap(),
                 NaiveTime::parse_from_str("10:50:00", "%H:%M:%S").unwrap(),
-            ),
-            latest_version_last_updated: NaiveDateTime::new(
+            ))
+            .latest_version_last_updated(NaiveDateTime::new(
                 NaiveDate::parse_from_str("12.03.2025", "%d.%m.%Y").unwrap(),
                 NaiveTime::parse_from_str("13:45:00", "%H:%M:%S").unwrap(),
-            ),
-            latest_version: "0.1.0".to_string(),
-            provider: Provider::Github("LMH01/test_program".to_string()),
-        };
+            ))
+            .latest_version("0.1.0")
+            .provider(Provider::Github(GithubProvider::new("LMH01/test_program")))
+            .build()
+            .unwrap();
         db.insert_program(&program).await.unwrap();
         db.insert_program(&program2).await.unwrap();
 
@@ -150,7 +195,7 @@ mod tests {
             NaiveTime::parse_from_str("10:50:00", "%H:%M:%S").unwrap(),
         );
 
-        db.set_notification_sent_on("simple_update_checker", Some(test_date_time.clone()))
+        db.set_notification_sent_on("simple_update_checker", Some(test_date_time))
             .await
             .unwrap();
         let res = db
@@ -182,6 +227,52 @@ mod tests {
         assert_eq!(None, res);
     }
 
+    #[sqlx::test]
+    fn test_db_set_notification_backoff(pool: SqlitePool) {
+        let db = tests::db(pool);
+        let program = Program::builder()
+            .name("simple_update_checker")
+            .current_version("0.1.0")
+            .current_version_last_updated(NaiveDateTime::new(
+                NaiveDate::parse_from_str("10.03.2025", "%d.%m.%Y").unwrap(),
+                NaiveTime::parse_from_str("10:50:00", "%H:%M:%S").unwrap(),
+            ))
+            .latest_version("0.2.0")
+            .latest_version_last_updated(NaiveDateTime::new(
+                NaiveDate::parse_from_str("12.03.2025", "%d.%m.%Y").unwrap(),
+                NaiveTime::parse_from_str("13:45:00", "%H:%M:%S").unwrap(),
+            ))
+            .provider(Provider::Github(GithubProvider::new(
+                "LMH01/simple_update_checker",
+            )))
+            .build()
+            .unwrap();
+        db.insert_program(&program).await.unwrap();
+        let res = db
+            .get_notification_backoff("simple_update_checker")
+            .await
+            .unwrap();
+        assert_eq!(Some(NotificationBackoff::default()), res);
+
+        let backoff = NotificationBackoff {
+            until: Some(NaiveDateTime::new(
+                NaiveDate::parse_from_str("13.03.2025", "%d.%m.%Y").unwrap(),
+                NaiveTime::parse_from_str("13:45:00", "%H:%M:%S").unwrap(),
+            )),
+            secs: Some(3600),
+            notified_version: Some("0.2.0".to_string()),
+        };
+        db.set_notification_backoff("simple_update_checker", &backoff)
+            .await
+            .unwrap();
+        let res = db
+            .get_notification_backoff("simple_update_checker")
+            .await
+            .unwrap();
+        assert_eq!(Some(backoff), res);
+        assert!(db.get_notification_backoff("name").await.unwrap().is_none());
+    }
+
     #[sqlx::test]
     fn test_db_get_notification_sent_program_not_existing(pool: SqlitePool) {
         let db = tests::db(pool);
@@ -37,6 +37,39 @@ impl Db {
 
         Ok(())
     }
+
+    /// Stores the time at which `latest_version` was published.
+    pub async fn set_latest_version_published_at(
+        &self,
+        name: &str,
+        latest_version_published_at: Option<NaiveDateTime>,
+    ) -> Result<()> {
+        let sql = r"UPDATE programs SET latest_version_published_at = ? WHERE name = ?";
+        sqlx::query(sql)
+            .bind(latest_version_published_at)
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Stores the version reported by the provider when it is older than `latest_version`.
+    /// `None` clears the stored version.
+    pub async fn set_older_upstream_version(
+        &self,
+        name: &str,
+        older_upstream_version: Option<&str>,
+    ) -> Result<()> {
+        let sql = r"UPDATE programs SET older_upstream_version = ? WHERE name = ?";
+        sqlx::query(sql)
+            .bind(older_upstream_version)
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -46,37 +79,36 @@ mod tests {
         types::chrono::{NaiveDate, NaiveDateTime, NaiveTime},
     };
 
-    use crate::{Program, Provider, db::tests};
+    use crate::{GithubProvider, Program, Provider, VersionScheme, db::tests};
 
     #[sqlx::test]
     fn test_db_update_latest_version(pool: SqlitePool) {
         let db = tests::db(pool);
-        let mut program = Program {
-            name: "simple_update_checker".to_string(),
-            current_version: "0.1.0".to_string(),
-            current_version_last_updated: NaiveDateTime::new(
+        let mut program = Program::builder()
+            .name("simple_update_checker")
+            .current_version("0.1.0")
+            .current_version_last_updated(NaiveDateTime::new(
                 NaiveDate::parse_from_str("10.03.2025", "%d.%m.%Y").unwrap(),
                 NaiveTime::parse_from_str("10:50:00", "%H:%M:%S").unwrap(),
-            ),
-            latest_version: "0.1.0".to_string(),
-            latest_version_last_updated: NaiveDateTime::new(
+            ))
+            .latest_version("0.1.0")
+            .latest_version_last_updated(NaiveDateTime::new(
                 NaiveDate::parse_from_str("12.03.2025", "%d.%m.%Y").unwrap(),
                 NaiveTime::parse_from_str("13:45:00", "%H:%M:%S").unwrap(),
-            ),
-            provider: Provider::Github("LMH01/simple_update_checker".to_string()),
-        };
+            ))
+            .provider(Provider::Github(GithubProvider::new(
+                "LMH01/simple_update_checker",
+            )))
+            .build()
+            .unwrap();
         let new_latest_version_last_updated = NaiveDateTime::new(
             NaiveDate::parse_from_str("01.01.2025", "%d.%m.%Y").unwrap(),
             NaiveTime::parse_from_str("00:00:00", "%H:%M:%S").unwrap(),
         );
         db.insert_program(&program).await.unwrap();
-        db.update_latest_version(
-            &program.name,
-            "0.2.0",
-            new_latest_version_last_updated.clone(),
-        )
-        .await
-        .unwrap();
+        db.update_latest_version(&program.name, "0.2.0", new_latest_version_last_updated)
+            .await
+            .unwrap();
         let res = db.get_program(&program.name).await.unwrap().unwrap();
         program.latest_version = "0.2.0".to_string();
         program.latest_version_last_updated = new_latest_version_last_updated;
@@ -86,20 +118,23 @@ mod tests {
     #[sqlx::test]
     fn test_db_update_current_version(pool: SqlitePool) {
         let db = tests::db(pool);
-        let mut program = Program {
-            name: "simple_update_checker".to_string(),
-            current_version: "0.1.0".to_string(),
-            current_version_last_updated: NaiveDateTime::new(
+        let mut program = Program::builder()
+            .name("simple_update_checker")
+            .current_version("0.1.0")
+            .current_version_last_updated(NaiveDateTime::new(
                 NaiveDate::parse_from_str("10.03.2025", "%d.%m.%Y").unwrap(),
                 NaiveTime::parse_from_str("10:50:00", "%H:%M:%S").unwrap(),
-            ),
-            latest_version: "0.1.0".to_string(),
-            latest_version_last_updated: NaiveDateTime::new(
+            ))
+            .latest_version("0.1.0")
+            .latest_version_last_updated(NaiveDateTime::new(
                 NaiveDate::parse_from_str("12.03.2025", "%d.%m.%Y").unwrap(),
                 NaiveTime::parse_from_str("13:45:00", "%H:%M:%S").unwrap(),
-            ),
-            provider: Provider::Github("LMH01/simple_update_checker".to_string()),
-        };
+            ))
+            .provider(Provider::Github(GithubProvider::new(
+                "LMH01/simple_update_checker",
+            )))
+            .build()
+            .unwrap();
         let new_current_version_last_updated = NaiveDateTime::new(
             NaiveDate::parse_from_str("01.01.2025", "%d.%m.%Y").unwrap(),
             NaiveTime::parse_from_str("00:00:00", "%H:%M:%S").unwrap(),
@@ -113,4 +148,41 @@ mod tests {
         program.current_version_last_updated = new_current_version_last_updated;
         assert_eq!(program, res);
     }
+
+    #[sqlx::test]
+    fn test_db_set_older_upstream_version(pool: SqlitePool) {
+        let db = tests::db(pool);
+        let mut program = Program::builder()
+            .name("simple_update_checker")
+            .current_version("0.1.0")
+            .current_version_last_updated(NaiveDateTime::new(
+                NaiveDate::parse_from_str("10.03.2025", "%d.%m.%Y").unwrap(),
+                NaiveTime::parse_from_str("10:50:00", "%H:%M:%S").unwrap(),
+            ))
+            .latest_version("0.2.0")
+            .latest_version_last_updated(NaiveDateTime::new(
+                NaiveDate::parse_from_str("12.03.2025", "%d.%m.%Y").unwrap(),
+                NaiveTime::parse_from_str("13:45:00", "%H:%M:%S").unwrap(),
+            ))
+            .provider(Provider::Github(GithubProvider::new(
+                "LMH01/simple_update_checker",
+            )))
+            .version_scheme(VersionScheme::Semver)
+            .allow_rollback(true)
+            .build()
+            .unwrap();
+        db.insert_program(&program).await.unwrap();
+        db.set_older_upstream_version(&program.name, Some("0.1.5"))
+            .await
+            .unwrap();
+        let res = db.get_program(&program.name).await.unwrap().unwrap();
+        program.older_upstream_version = Some("0.1.5".to_string());
+        assert_eq!(program, res);
+        db.set_older_upstream_version(&program.name, None)
+            .await
+            .unwrap();
+        let res = db.get_program(&program.name).await.unwrap().unwrap();
+        program.older_upstream_version = None;
+        assert_eq!(program, res);
+    }
 }
@@ -0,0 +1,202 @@
+//! The parts of the database that are used by the update check and the timed mode.
+//!
+//! The update check and the notification handling of `run-timed` only access the database through [`ProgramStore`],
+//! so that their logic can be tested without a sqlite database.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use sqlx::types::chrono::NaiveDateTime;
+
+use crate::{
+    NotificationBackoff, NotificationInfo, Program, Provider, UpdateCheckHistoryEntry,
+    db::{Db, ProgramChanges},
+};
+
+/// Storage of the programs together with their update check and notification state.
+///
+/// The methods behave like the methods of [`Db`] with the same name.
+pub trait ProgramStore: Send + Sync {
+    /// Retrieve the program named `name`, `None` if it does not exist.
+    fn get_program(&self, name: &str) -> impl Future<Output = Result<Option<Program>>> + Send;
+
+    /// Retrieve all programs.
+    fn get_all_programs(&self) -> impl Future<Output = Result<Vec<Program>>> + Send;
+
+    /// Retrieve all programs that use the provider with the identifier `provider`.
+    fn get_programs_by_provider(
+        &self,
+        provider: &str,
+    ) -> impl Future<Output = Result<Vec<Program>>> + Send;
+
+    /// Retrieve all programs for which the last update check failed, optionally only those that use the provider with
+    /// the identifier `provider`.
+    fn get_failed_programs(
+        &self,
+        provider: Option<&str>,
+    ) -> impl Future<Output = Result<Vec<Program>>> + Send;
+
+    /// Stores a new program.
+    fn insert_program(&self, program: &Program) -> impl Future<Output = Result<()>> + Send;
+
+    /// Removes the program named `name`, fails if it does not exist.
+    fn remove_program(&self, name: &str) -> impl Future<Output = Result<()>> + Send;
+
+    /// Updates the provider specific settings of the program named `name`.
+    fn update_provider(
+        &self,
+        name: &str,
+        provider: &Provider,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Stores all changes to the program named `name` at once.
+    fn apply_program_changes(
+        &self,
+        name: &str,
+        changes: &ProgramChanges,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Stores the error of the last update check of the program named `name`, `None` clears it.
+    fn set_last_error(
+        &self,
+        name: &str,
+        last_error: Option<&str>,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Returns the product of every program that is a component, the key is the name of the component.
+    fn get_products(&self) -> impl Future<Output = Result<HashMap<String, String>>> + Send;
+
+    /// Retrieve the notification state of the program named `program_name`.
+    fn get_notification_info(
+        &self,
+        program_name: &str,
+    ) -> impl Future<Output = Result<Option<NotificationInfo>>> + Send;
+
+    /// Sets whether a notification was sent for the latest version of the program named `program_name`.
+    fn set_notification_sent(
+        &self,
+        program_name: &str,
+        notification_sent: bool,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Sets the time at which the notification for the program named `program_name` was sent.
+    fn set_notification_sent_on(
+        &self,
+        program_name: &str,
+        notification_sent_on: Option<NaiveDateTime>,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Retrieve the notification backoff of the program named `program_name`.
+    fn get_notification_backoff(
+        &self,
+        program_name: &str,
+    ) -> impl Future<Output = Result<Option<NotificationBackoff>>> + Send;
+
+    /// Stores the notification backoff of the program named `program_name`.
+    fn set_notification_backoff(
+        &self,
+        program_name: &str,
+        backoff: &NotificationBackoff,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Adds an entry to the update check history.
+    fn insert_update_check_history(
+        &self,
+        update_check: &UpdateCheckHistoryEntry,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Retrieve the latest entry of the update check history.
+    fn get_latest_update_check_from_history(
+        &self,
+    ) -> impl Future<Output = Result<Option<UpdateCheckHistoryEntry>>> + Send;
+}
+
+impl ProgramStore for Db {
+    async fn get_program(&self, name: &str) -> Result<Option<Program>> {
+        Db::get_program(self, name).await
+    }
+
+    async fn get_all_programs(&self) -> Result<Vec<Program>> {
+        Db::get_all_programs(self).await
+    }
+
+    async fn get_programs_by_provider(&self, provider: &str) -> Result<Vec<Program>> {
+        Db::get_programs_by_provider(self, provider).await
+    }
+
+    async fn get_failed_programs(&self, provider: Option<&str>) -> Result<Vec<Program>> {
+        Db::get_failed_programs(self, provider).await
+    }
+
+    async fn insert_program(&self, program: &Program) -> Result<()> {
+        Db::insert_program(self, program).await
+    }
+
+    async fn remove_program(&self, name: &str) -> Result<()> {
+        Db::remove_program(self, name).await
+    }
+
+    async fn update_provider(&self, name: &str, provider: &Provider) -> Result<()> {
+        Db::update_provider(self, name, provider).await
+    }
+
+    async fn apply_program_changes(&self, name: &str, changes: &ProgramChanges) -> Result<()> {
+        Db::apply_program_changes(self, name, changes).await
+    }
+
+    async fn set_last_error(&self, name: &str, last_error: Option<&str>) -> Result<()> {
+        Db::set_last_error(self, name, last_error).await
+    }
+
+    async fn get_products(&self) -> Result<HashMap<String, String>> {
+        Db::get_products(self).await
+    }
+
+    async fn get_notification_info(&self, program_name: &str) -> Result<Option<NotificationInfo>> {
+        Db::get_notification_info(self, program_name).await
+    }
+
+    async fn set_notification_sent(
+        &self,
+        program_name: &str,
+        notification_sent: bool,
+    ) -> Result<()> {
+        Db::set_notification_sent(self, program_name, notification_sent).await
+    }
+
+    async fn set_notification_sent_on(
+        &self,
+        program_name: &str,
+        notification_sent_on: Option<NaiveDateTime>,
+    ) -> Result<()> {
+        Db::set_notification_sent_on(self, program_name, notification_sent_on).await
+    }
+
+    async fn get_notification_backoff(
+        &self,
+        program_name: &str,
+    ) -> Result<Option<NotificationBackoff>> {
+        Db::get_notification_backoff(self, program_name).await
+    }
+
+    async fn set_notification_backoff(
+        &self,
+        program_name: &str,
+        backoff: &NotificationBackoff,
+    ) -> Result<()> {
+        Db::set_notification_backoff(self, program_name, backoff).await
+    }
+
+    async fn insert_update_check_history(
+        &self,
+        update_check: &UpdateCheckHistoryEntry,
+    ) -> Result<()> {
+        Db::insert_update_check_history(self, update_check).await
+    }
+
+    async fn get_latest_update_check_from_history(
+        &self,
+    ) -> Result<Option<UpdateCheckHistoryEntry>> {
+        Db::get_latest_update_check_from_history(self).await
+    }
+}
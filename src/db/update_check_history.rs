@@ -3,7 +3,7 @@ use std::str::FromStr;
 use anyhow::Result;
 use sqlx::types::chrono::NaiveDateTime;
 
-use crate::{Identifier, UpdateCheckHistoryEntry, UpdateCheckType};
+use crate::{Identifier, Shard, UpdateCheckHistoryEntry, UpdateCheckType};
 
 use super::Db;
 
@@ -12,12 +12,16 @@ impl Db {
         &self,
         update_check: &UpdateCheckHistoryEntry,
     ) -> Result<()> {
-        let sql = r"INSERT INTO update_check_history (date, type, updates_available, programs) VALUES (?, ?, ?, ?)";
+        let sql = r"INSERT INTO update_check_history (date, type, updates_available, programs, duration_ms, errors, shard, truncated) VALUES (?, ?, ?, ?, ?, ?, ?, ?)";
         sqlx::query(sql)
             .bind(update_check.date)
             .bind(update_check.r#type.identifier())
             .bind(update_check.updates_available)
             .bind(&update_check.programs)
+            .bind(update_check.duration_ms)
+            .bind(update_check.errors)
+            .bind(update_check.shard.map(|shard| shard.to_string()))
+            .bind(update_check.truncated)
             .execute(&self.pool)
             .await?;
 
@@ -27,11 +31,31 @@ impl Db {
     pub async fn get_latest_update_check_from_history(
         &self,
     ) -> Result<Option<UpdateCheckHistoryEntry>> {
-        let sql = r"SELECT date, type, updates_available, programs FROM update_check_history ORDER BY date DESC LIMIT 1";
-        if let Some((date, r#type, updates_available, programs)) =
-            sqlx::query_as::<_, (NaiveDateTime, String, u32, String)>(sql)
-                .fetch_optional(&self.pool)
-                .await?
+        let sql = r"SELECT date, type, updates_available, programs, duration_ms, errors, shard, truncated FROM update_check_history ORDER BY date DESC LIMIT 1";
+        if let Some((
+            date,
+            r#type,
+            updates_available,
+            programs,
+            duration_ms,
+            errors,
+            shard,
+            truncated,
+        )) = sqlx::query_as::<
+            _,
+            (
+                NaiveDateTime,
+                String,
+                u32,
+                String,
+                Option<u32>,
+                Option<u32>,
+                Option<String>,
+                bool,
+            ),
+        >(sql)
+        .fetch_optional(&self.pool)
+        .await?
         {
             return Ok(Some(UpdateCheckHistoryEntry {
                 date,
@@ -39,32 +63,66 @@ impl Db {
                     .expect("database should contain only valid entries"),
                 updates_available,
                 programs,
+                duration_ms,
+                errors,
+                shard: shard.map(|shard| {
+                    Shard::from_str(&shard).expect("database should contain only valid entries")
+                }),
+                truncated,
             }));
         }
         Ok(None)
     }
 
+    /// Returns the latest update checks, newest first.
+    ///
+    /// If `since_last_manual` is set, only the update checks performed after the latest manual update check are
+    /// returned.
     pub async fn get_all_update_checks(
         &self,
         max_entries: Option<u32>,
+        since_last_manual: bool,
     ) -> Result<Vec<UpdateCheckHistoryEntry>> {
-        let sql = r"SELECT date, type, updates_available, programs FROM update_check_history ORDER BY date DESC LIMIT ?";
-        let update_checks = sqlx::query_as::<_, (NaiveDateTime, String, u32, String)>(sql)
-            .bind(max_entries.unwrap_or(100))
-            .fetch_all(&self.pool)
-            .await?
-            .into_iter()
-            .map(
-                |(date, r#type, updates_available, programs)| UpdateCheckHistoryEntry {
+        let sql = r"SELECT date, type, updates_available, programs, duration_ms, errors, shard, truncated FROM update_check_history AS update_check WHERE NOT ? OR NOT EXISTS (SELECT 1 FROM update_check_history WHERE type = ? AND date >= update_check.date) ORDER BY date DESC LIMIT ?";
+        let update_checks = sqlx::query_as::<
+            _,
+            (
+                NaiveDateTime,
+                String,
+                u32,
+                String,
+                Option<u32>,
+                Option<u32>,
+                Option<String>,
+                bool,
+            ),
+        >(sql)
+        .bind(since_last_manual)
+        .bind(UpdateCheckType::Manual.identifier())
+        .bind(max_entries.unwrap_or(100))
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(
+            |(date, r#type, updates_available, programs, duration_ms, errors, shard, truncated)| {
+                UpdateCheckHistoryEntry {
                     date,
                     r#type: UpdateCheckType::from_str(&r#type).expect(
                         "Database should contain string that can be parsed to UpdateCheckType",
                     ),
                     updates_available,
                     programs,
-                },
-            )
-            .collect();
+                    duration_ms,
+                    errors,
+                    shard: shard.map(|shard| {
+                        Shard::from_str(&shard)
+                            .expect("Database should contain string that can be parsed to Shard")
+                    }),
+                    truncated,
+                }
+            },
+        )
+        .collect();
         Ok(update_checks)
     }
 }
@@ -76,7 +134,7 @@ mod tests {
         types::chrono::{NaiveDate, NaiveDateTime, NaiveTime},
     };
 
-    use crate::{UpdateCheckHistoryEntry, UpdateCheckType, db::tests};
+    use crate::{Shard, UpdateCheckHistoryEntry, UpdateCheckType, db::tests};
 
     #[sqlx::test]
     fn test_db_update_check(pool: SqlitePool) {
@@ -89,6 +147,10 @@ mod tests {
             r#type: UpdateCheckType::Manual,
             updates_available: 0,
             programs: "".to_string(),
+            duration_ms: Some(1200),
+            errors: Some(0),
+            shard: None,
+            truncated: false,
         };
         let update_check1 = UpdateCheckHistoryEntry {
             date: NaiveDateTime::new(
@@ -98,6 +160,10 @@ mod tests {
             r#type: UpdateCheckType::Manual,
             updates_available: 2,
             programs: "alpha_tui, simple_update_checker".to_string(),
+            duration_ms: None,
+            errors: None,
+            shard: Some(Shard { index: 2, count: 4 }),
+            truncated: false,
         };
         db.insert_update_check_history(&update_check).await.unwrap();
         db.insert_update_check_history(&update_check1)
@@ -125,6 +191,10 @@ mod tests {
             r#type: UpdateCheckType::Manual,
             updates_available: 0,
             programs: "".to_string(),
+            duration_ms: Some(1200),
+            errors: Some(0),
+            shard: None,
+            truncated: false,
         };
         let entry2 = UpdateCheckHistoryEntry {
             date: NaiveDateTime::new(
@@ -134,6 +204,10 @@ mod tests {
             r#type: UpdateCheckType::Manual,
             updates_available: 0,
             programs: "".to_string(),
+            duration_ms: Some(1200),
+            errors: Some(0),
+            shard: None,
+            truncated: false,
         };
         let entry3 = UpdateCheckHistoryEntry {
             date: NaiveDateTime::new(
@@ -143,12 +217,16 @@ mod tests {
             r#type: UpdateCheckType::Manual,
             updates_available: 0,
             programs: "".to_string(),
+            duration_ms: Some(1200),
+            errors: Some(0),
+            shard: None,
+            truncated: false,
         };
         db.insert_update_check_history(&entry).await.unwrap();
         db.insert_update_check_history(&entry2).await.unwrap();
         db.insert_update_check_history(&entry3).await.unwrap();
 
-        let mut res = db.get_all_update_checks(None).await.unwrap();
+        let mut res = db.get_all_update_checks(None, false).await.unwrap();
         res.reverse();
 
         assert_eq!(vec![entry, entry2, entry3], res);
@@ -165,6 +243,10 @@ mod tests {
             r#type: UpdateCheckType::Manual,
             updates_available: 0,
             programs: "".to_string(),
+            duration_ms: Some(1200),
+            errors: Some(0),
+            shard: None,
+            truncated: false,
         };
         let entry2 = UpdateCheckHistoryEntry {
             date: NaiveDateTime::new(
@@ -174,6 +256,10 @@ mod tests {
             r#type: UpdateCheckType::Manual,
             updates_available: 0,
             programs: "".to_string(),
+            duration_ms: Some(1200),
+            errors: Some(0),
+            shard: None,
+            truncated: false,
         };
         let entry3 = UpdateCheckHistoryEntry {
             date: NaiveDateTime::new(
@@ -183,14 +269,64 @@ mod tests {
             r#type: UpdateCheckType::Manual,
             updates_available: 0,
             programs: "".to_string(),
+            duration_ms: Some(1200),
+            errors: Some(0),
+            shard: None,
+            truncated: false,
         };
         db.insert_update_check_history(&entry).await.unwrap();
         db.insert_update_check_history(&entry2).await.unwrap();
         db.insert_update_check_history(&entry3).await.unwrap();
 
-        let mut res = db.get_all_update_checks(Some(2)).await.unwrap();
+        let mut res = db.get_all_update_checks(Some(2), false).await.unwrap();
         res.reverse();
 
         assert_eq!(vec![entry2, entry3], res);
     }
+
+    #[sqlx::test]
+    fn test_db_get_all_update_checks_since_last_manual(pool: SqlitePool) {
+        let db = tests::db(pool);
+        let entry = |date: &str, r#type: UpdateCheckType| UpdateCheckHistoryEntry {
+            date: NaiveDateTime::parse_from_str(date, "%d.%m.%Y %H:%M:%S").unwrap(),
+            r#type,
+            updates_available: 0,
+            programs: "".to_string(),
+            duration_ms: Some(1200),
+            errors: Some(0),
+            shard: None,
+            truncated: false,
+        };
+        let entries = [
+            entry("12.03.2025 13:45:00", UpdateCheckType::Timed),
+            entry("13.03.2025 13:45:00", UpdateCheckType::Manual),
+            entry("14.03.2025 13:45:00", UpdateCheckType::Timed),
+            entry("15.03.2025 13:45:00", UpdateCheckType::Triggered),
+        ];
+        for entry in &entries {
+            db.insert_update_check_history(entry).await.unwrap();
+        }
+
+        let mut res = db.get_all_update_checks(None, true).await.unwrap();
+        res.reverse();
+        assert_eq!(&entries[2..], res.as_slice());
+        assert_eq!(
+            2,
+            db.get_all_update_checks(Some(2), false)
+                .await
+                .unwrap()
+                .len()
+        );
+
+        // without newer update checks, no update check is returned
+        db.insert_update_check_history(&entry("16.03.2025 13:45:00", UpdateCheckType::Manual))
+            .await
+            .unwrap();
+        assert!(
+            db.get_all_update_checks(None, true)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
 }
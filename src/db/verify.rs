@@ -0,0 +1,151 @@
+use anyhow::Result;
+
+use super::Db;
+
+/// An inconsistency that was found in the database.
+#[derive(Debug, PartialEq)]
+pub enum DbInconsistency {
+    /// A row in a provider specific table (e.g. `github_programs`) has no matching row in `programs`.
+    OrphanedProviderEntry { table: String, name: String },
+    /// A program has no matching row in the table of its provider.
+    MissingProviderEntry { name: String, provider: String },
+    /// A program uses a provider that is unknown.
+    UnknownProvider { name: String, provider: String },
+}
+
+/// Provider identifiers and the tables in which their provider specific data is stored.
+const PROVIDER_TABLES: [(&str, &str); 5] = [
+    ("github", "github_programs"),
+    ("githubcommit", "githubcommit_programs"),
+    ("homebrew", "homebrew_programs"),
+    ("fdroid", "fdroid_programs"),
+    ("command", "command_programs"),
+];
+
+impl Db {
+    /// Runs consistency checks on the database and returns all inconsistencies that where found.
+    pub async fn find_inconsistencies(&self) -> Result<Vec<DbInconsistency>> {
+        let mut inconsistencies = Vec::new();
+        for (provider, table) in PROVIDER_TABLES {
+            let sql = format!(
+                "SELECT name FROM {table} WHERE name NOT IN (SELECT name FROM programs) ORDER BY name"
+            );
+            for (name,) in sqlx::query_as::<_, (String,)>(&sql)
+                .fetch_all(&self.pool)
+                .await?
+            {
+                inconsistencies.push(DbInconsistency::OrphanedProviderEntry {
+                    table: table.to_string(),
+                    name,
+                });
+            }
+            let sql = format!(
+                "SELECT name FROM programs WHERE provider = ? AND name NOT IN (SELECT name FROM {table}) ORDER BY name"
+            );
+            for (name,) in sqlx::query_as::<_, (String,)>(&sql)
+                .bind(provider)
+                .fetch_all(&self.pool)
+                .await?
+            {
+                inconsistencies.push(DbInconsistency::MissingProviderEntry {
+                    name,
+                    provider: provider.to_string(),
+                });
+            }
+        }
+        let known_providers = PROVIDER_TABLES
+            .iter()
+            .map(|(provider, _)| format!("'{provider}'"))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let sql = format!(
+            "SELECT name, provider FROM programs WHERE provider NOT IN ({known_providers}) ORDER BY name"
+        );
+        for (name, provider) in sqlx::query_as::<_, (String, String)>(&sql)
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .filter(|(_, provider)| !self.providers.contains(provider))
+        {
+            inconsistencies.push(DbInconsistency::UnknownProvider { name, provider });
+        }
+        Ok(inconsistencies)
+    }
+
+    /// Removes all rows from provider specific tables that have no matching row in `programs`.
+    ///
+    /// Returns the number of removed rows.
+    pub async fn remove_orphaned_provider_entries(&self) -> Result<u64> {
+        let mut removed = 0;
+        for (_, table) in PROVIDER_TABLES {
+            let sql = format!("DELETE FROM {table} WHERE name NOT IN (SELECT name FROM programs)");
+            removed += sqlx::query(&sql).execute(&self.pool).await?.rows_affected();
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::SqlitePool;
+
+    use super::DbInconsistency;
+    use crate::db::tests;
+
+    /// Inserts inconsistent rows, like they could be created by manually editing the database.
+    async fn insert_inconsistencies(db: &crate::db::Db) {
+        let mut conn = db.pool.acquire().await.unwrap();
+        // foreign keys are usually not enforced when the database is edited manually
+        sqlx::query("PRAGMA foreign_keys = OFF")
+            .execute(&mut *conn)
+            .await
+            .unwrap();
+        let sql = r"INSERT INTO programs (name, latest_version, provider, current_version) VALUES ('missing_repo', '1.0.0', 'github', '1.0.0'), ('unknown', '1.0.0', 'unknown_provider', '1.0.0')";
+        sqlx::query(sql).execute(&mut *conn).await.unwrap();
+        let sql =
+            r"INSERT INTO github_programs (name, repository) VALUES ('orphan', 'LMH01/orphan')";
+        sqlx::query(sql).execute(&mut *conn).await.unwrap();
+        sqlx::query("PRAGMA foreign_keys = ON")
+            .execute(&mut *conn)
+            .await
+            .unwrap();
+    }
+
+    #[sqlx::test]
+    fn test_db_find_inconsistencies(pool: SqlitePool) {
+        let db = tests::db(pool);
+        assert!(db.find_inconsistencies().await.unwrap().is_empty());
+        insert_inconsistencies(&db).await;
+        let res = db.find_inconsistencies().await.unwrap();
+        assert_eq!(
+            vec![
+                DbInconsistency::OrphanedProviderEntry {
+                    table: "github_programs".to_string(),
+                    name: "orphan".to_string()
+                },
+                DbInconsistency::MissingProviderEntry {
+                    name: "missing_repo".to_string(),
+                    provider: "github".to_string()
+                },
+                DbInconsistency::UnknownProvider {
+                    name: "unknown".to_string(),
+                    provider: "unknown_provider".to_string()
+                },
+            ],
+            res
+        );
+    }
+
+    #[sqlx::test]
+    fn test_db_remove_orphaned_provider_entries(pool: SqlitePool) {
+        let db = tests::db(pool);
+        insert_inconsistencies(&db).await;
+        assert_eq!(1, db.remove_orphaned_provider_entries().await.unwrap());
+        let res = db.find_inconsistencies().await.unwrap();
+        assert_eq!(2, res.len());
+        assert!(
+            !res.iter()
+                .any(|i| matches!(i, DbInconsistency::OrphanedProviderEntry { .. }))
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use anyhow::Result;
+
+use crate::VersionHistoryEntry;
+
+use super::Db;
+
+impl Db {
+    /// Add a `VersionHistoryEntry` to `version_history`.
+    pub async fn insert_found_version(
+        &self,
+        version_history_entry: &VersionHistoryEntry,
+    ) -> Result<()> {
+        let sql = r"INSERT INTO version_history (date, name, version) VALUES (?, ?, ?)";
+        sqlx::query(sql)
+            .bind(version_history_entry.date)
+            .bind(&version_history_entry.name)
+            .bind(&version_history_entry.version)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Retrieve the versions that where found for the program named `name`, newest first.
+    pub async fn get_version_history(
+        &self,
+        name: &str,
+        max_entries: Option<u32>,
+    ) -> Result<Vec<VersionHistoryEntry>> {
+        let sql = r"SELECT date, name, version FROM version_history WHERE name = ? ORDER BY date DESC LIMIT ?";
+        let entries = sqlx::query_as::<_, VersionHistoryEntry>(sql)
+            .bind(name)
+            .bind(max_entries.unwrap_or(100))
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::{
+        SqlitePool,
+        types::chrono::{NaiveDate, NaiveDateTime, NaiveTime},
+    };
+
+    use crate::{VersionHistoryEntry, db::tests};
+
+    #[sqlx::test]
+    fn test_db_version_history(pool: SqlitePool) {
+        let db = tests::db(pool);
+        let entry = VersionHistoryEntry {
+            date: NaiveDateTime::new(
+                NaiveDate::parse_from_str("12.03.2025", "%d.%m.%Y").unwrap(),
+                NaiveTime::parse_from_str("13:45:00", "%H:%M:%S").unwrap(),
+            ),
+            name: "alpha_tui".to_string(),
+            version: "v1.1.0".to_string(),
+        };
+        let entry2 = VersionHistoryEntry {
+            date: NaiveDateTime::new(
+                NaiveDate::parse_from_str("02.04.2025", "%d.%m.%Y").unwrap(),
+                NaiveTime::parse_from_str("08:00:00", "%H:%M:%S").unwrap(),
+            ),
+            name: "alpha_tui".to_string(),
+            version: "v1.2.0".to_string(),
+        };
+        let other = VersionHistoryEntry {
+            date: NaiveDateTime::new(
+                NaiveDate::parse_from_str("01.04.2025", "%d.%m.%Y").unwrap(),
+                NaiveTime::parse_from_str("08:00:00", "%H:%M:%S").unwrap(),
+            ),
+            name: "simple_update_checker".to_string(),
+            version: "v1.7.0".to_string(),
+        };
+        db.insert_found_version(&entry).await.unwrap();
+        db.insert_found_version(&entry2).await.unwrap();
+        db.insert_found_version(&other).await.unwrap();
+        let res = db.get_version_history("alpha_tui", None).await.unwrap();
+        assert_eq!(vec![entry2, entry], res);
+        let res = db.get_version_history("alpha_tui", Some(1)).await.unwrap();
+        assert_eq!(1, res.len());
+    }
+}
@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+use reqwest::{Client, header};
+
+/// User agent that is sent with every request.
+pub const USER_AGENT: &str = concat!(
+    "simple_update_checker/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://github.com/LMH01/simple_update_checker)"
+);
+
+/// Creates the client that is used to send requests.
+///
+/// The client sends the user agent of this program with every request.
+pub fn client() -> Client {
+    client_with_timeout(None)
+}
+
+/// Creates the client that is used to send requests, requests fail when they take longer than `timeout`.
+///
+/// The client requests compressed responses with the `Accept-Encoding` header and decompresses them transparently.
+pub fn client_with_timeout(timeout: Option<Duration>) -> Client {
+    let mut builder = Client::builder()
+        .default_headers(header::HeaderMap::from_iter([(
+            header::USER_AGENT,
+            header::HeaderValue::from_static(USER_AGENT),
+        )]))
+        .gzip(true)
+        .deflate(true)
+        .brotli(true);
+    if let Some(timeout) = timeout {
+        builder = builder.timeout(timeout);
+    }
+    builder
+        .build()
+        .expect("http client should be constructable")
+}
+
+#[cfg(test)]
+mod tests {
+    use mockito::Matcher;
+
+    use super::client;
+
+    #[tokio::test]
+    async fn test_client_decompresses_responses() {
+        // gzip compressed '{ "tag_name": "v1.8.0" }'
+        let body = [
+            0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xab, 0x56, 0x50, 0x2a,
+            0x49, 0x4c, 0x8f, 0xcf, 0x4b, 0xcc, 0x4d, 0x55, 0xb2, 0x52, 0x50, 0x2a, 0x33, 0xd4,
+            0xb3, 0xd0, 0x33, 0x50, 0x52, 0xa8, 0x05, 0x00, 0xcd, 0xba, 0xd5, 0x59, 0x18, 0x00,
+            0x00, 0x00,
+        ];
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/release")
+            .match_header(
+                "Accept-Encoding",
+                Matcher::AllOf(vec![
+                    Matcher::Regex("gzip".to_string()),
+                    Matcher::Regex("deflate".to_string()),
+                    Matcher::Regex("br".to_string()),
+                ]),
+            )
+            .with_header("Content-Encoding", "gzip")
+            .with_body(body)
+            .create_async()
+            .await;
+        let response = client()
+            .get(format!("{}/release", server.url()))
+            .send()
+            .await
+            .unwrap();
+        mock.assert_async().await;
+        assert_eq!(
+            r#"{ "tag_name": "v1.8.0" }"#,
+            response.text().await.unwrap()
+        );
+    }
+}
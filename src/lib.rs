@@ -1,22 +1,46 @@
-use std::{fmt::Display, str::FromStr, vec};
+use std::{
+    fmt::Display,
+    iter,
+    str::FromStr,
+    sync::{Arc, atomic::AtomicUsize},
+    time::Duration,
+    vec,
+};
 
 use anyhow::Result;
-use cli::DbArgs;
-use config::ConfigFile;
+use cli::{AddProgramArgs, Cli, Command, DbArgs, TableStyle};
+use config::{ConfigFile, ProgramConfig};
+use db::Db;
+use provider::{AuthContext, CustomProvider, UpdateProvider};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
 use sqlx::{
     prelude::FromRow,
-    types::chrono::{Local, NaiveDateTime, TimeZone, Utc},
+    types::chrono::{DateTime, Local, NaiveDateTime, NaiveTime, TimeZone, Utc},
 };
 use tabled::Tabled;
 
 pub mod actions;
 pub mod cli;
 pub mod config;
+pub mod credentials;
 pub mod db;
+pub mod http;
 mod notification;
+pub mod provider;
+mod rfc3339;
 mod update_check;
+mod version;
+
+pub use update_check::{LatestVersion, Release, VersionList};
+pub use version::{VersionConstraint, VersionScheme};
 
-#[derive(PartialEq, Debug, Tabled, Clone)]
+/// A program for which updates are checked.
+///
+/// When (de)serialized with serde, the field names below are used as they are and are part of the external schema,
+/// they are not renamed in future versions. Dates are serialized as RFC 3339 strings in UTC.
+/// Optional fields, `version_scheme` and `allow_rollback` can be omitted when deserializing.
+#[derive(PartialEq, Debug, Tabled, Clone, Serialize, Deserialize)]
 pub struct Program {
     #[tabled(rename = "Name")]
     name: String,
@@ -25,33 +49,440 @@ pub struct Program {
     current_version: String,
     /// Last time '`current_version`' was updated
     #[tabled(rename = "CV last updated", display("format_datetime"))]
+    #[serde(with = "rfc3339")]
     current_version_last_updated: NaiveDateTime,
     /// Newest version that is available
     #[tabled(rename = "Latest version (LV)")]
     latest_version: String,
     /// Last time '`current_version`' was updated
     #[tabled(rename = "LV last updated", display("format_datetime"))]
+    #[serde(with = "rfc3339")]
     latest_version_last_updated: NaiveDateTime,
     #[tabled(rename = "Provider")]
     provider: Provider,
+    /// If set, only versions with this major version are considered when checking for updates.
+    #[tabled(skip)]
+    #[serde(default)]
+    pin_major: Option<u32>,
+    /// Defines how versions are compared to decide if a found version is newer.
+    #[tabled(skip)]
+    #[serde(default)]
+    version_scheme: VersionScheme,
+    /// If set, `latest_version` is set to an older version, when the provider reports an older version
+    /// (e.g. because the latest release was removed).
+    #[tabled(skip)]
+    #[serde(default)]
+    allow_rollback: bool,
+    /// Version reported by the provider during the last update check, if it was older than `latest_version`.
+    #[tabled(skip)]
+    #[serde(default)]
+    older_upstream_version: Option<String>,
+    /// If set, only versions that satisfy this constraint are considered when checking for updates.
+    #[tabled(skip)]
+    #[serde(default)]
+    version_constraint: Option<VersionConstraint>,
+    /// Time at which `latest_version` was published, if the provider reports it.
+    #[tabled(skip)]
+    #[serde(default, with = "rfc3339::option")]
+    latest_version_published_at: Option<NaiveDateTime>,
+    /// If set, the program is only checked for updates when it was last checked at least this many seconds ago.
+    #[tabled(skip)]
+    #[serde(default)]
+    check_interval_secs: Option<u32>,
+    /// Last time the latest version of the program was successfully requested from the provider.
+    #[tabled(skip)]
+    #[serde(default, with = "rfc3339::option")]
+    last_checked: Option<NaiveDateTime>,
 }
 
 impl Program {
+    /// Creates a program whose current and latest version is `version`, without requesting the latest version from
+    /// the provider. Use [`Program::init`] to request it.
+    ///
+    /// All other settings use their default value, e.g. no major version is pinned.
+    ///
+    /// ```
+    /// use simple_update_checker::{GithubProvider, Program, Provider};
+    ///
+    /// let program = Program::new(
+    ///     "alpha_tui",
+    ///     "v1.8.0",
+    ///     Provider::Github(GithubProvider::new("LMH01/alpha_tui")),
+    /// );
+    /// assert_eq!("alpha_tui", program.name());
+    /// assert_eq!("v1.8.0", program.current_version());
+    /// assert_eq!("v1.8.0", program.latest_version());
+    /// assert_eq!(None, program.last_checked());
+    /// ```
+    #[must_use]
+    pub fn new(name: &str, version: &str, provider: Provider) -> Self {
+        let now = Utc::now().naive_utc();
+        Self {
+            name: name.to_string(),
+            current_version: version.to_string(),
+            current_version_last_updated: now,
+            latest_version: version.to_string(),
+            latest_version_last_updated: now,
+            provider,
+            pin_major: None,
+            version_scheme: VersionScheme::default(),
+            allow_rollback: false,
+            older_upstream_version: None,
+            version_constraint: None,
+            latest_version_published_at: None,
+            check_interval_secs: None,
+            last_checked: None,
+        }
+    }
+
+    /// Creates the program with the settings of `add_program_args`, the current and latest version are set to the
+    /// latest version reported by `provider`.
     pub async fn init(
-        name: &str,
         provider: Provider,
-        github_access_token: Option<String>,
+        add_program_args: &AddProgramArgs,
+        client: &Client,
+        auth: &AuthContext,
     ) -> Result<Self> {
-        let latest_version = provider
-            .check_for_latest_version(&github_access_token)
-            .await?;
-        Ok(Self {
-            name: name.to_string(),
-            current_version: latest_version.clone(),
-            current_version_last_updated: Utc::now().naive_utc(),
+        let (latest_version, published_at) = match (
+            add_program_args.pin_major,
+            &add_program_args.version_constraint,
+        ) {
+            (Some(pin_major), _) => {
+                let pinned = provider
+                    .check_for_latest_version_in_major(client, auth, pin_major)
+                    .await?;
+                (pinned.latest_version, pinned.published_at)
+            }
+            (None, Some(version_constraint)) => {
+                let latest = provider
+                    .check_for_latest_matching_version(client, auth, version_constraint)
+                    .await?;
+                (latest.version, latest.published_at)
+            }
+            (None, None) => {
+                let latest = provider.check_for_latest_version(client, auth).await?;
+                (latest.version, latest.published_at)
+            }
+        };
+        Self::builder()
+            .name(&add_program_args.name)
+            .current_version(&latest_version)
+            .provider(provider)
+            .pin_major(add_program_args.pin_major)
+            .version_scheme(add_program_args.version_scheme)
+            .allow_rollback(add_program_args.allow_rollback)
+            .version_constraint(add_program_args.version_constraint.clone())
+            .latest_version_published_at(published_at)
+            .check_interval_secs(add_program_args.check_interval)
+            .last_checked(Some(Utc::now().naive_utc()))
+            .build()
+    }
+
+    /// Returns a builder with which a program can be created from explicit versions and timestamps, without
+    /// requesting the latest version from the provider.
+    ///
+    /// ```
+    /// use simple_update_checker::{HomebrewKind, HomebrewProvider, Program, Provider};
+    ///
+    /// let program = Program::builder()
+    ///     .name("ripgrep")
+    ///     .provider(Provider::Homebrew(HomebrewProvider {
+    ///         package: "ripgrep".to_string(),
+    ///         kind: HomebrewKind::Formula,
+    ///     }))
+    ///     .current_version("14.1.0")
+    ///     .latest_version("14.1.1")
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!("14.1.0", program.current_version());
+    /// assert_eq!("14.1.1", program.latest_version());
+    /// assert!(Program::builder().name("ripgrep").current_version("14.1.0").build().is_err());
+    /// ```
+    pub fn builder() -> ProgramBuilder {
+        ProgramBuilder::default()
+    }
+
+    /// Returns the time at which the latest version was published.
+    ///
+    /// If the provider did not report when the latest version was published, the time at which the latest version
+    /// was found is returned instead.
+    #[must_use]
+    pub fn latest_version_date(&self) -> NaiveDateTime {
+        self.latest_version_published_at
+            .unwrap_or(self.latest_version_last_updated)
+    }
+
+    /// Checks if the latest version was published longer than `stale_after` before `now`.
+    #[must_use]
+    pub fn is_stale(&self, stale_after: Duration, now: NaiveDateTime) -> bool {
+        self.latest_version_date() + stale_after < now
+    }
+
+    /// Returns the time from which on the program is checked for updates again.
+    ///
+    /// `None` if the program has no check interval or was not yet checked, it is then checked on every update check.
+    #[must_use]
+    pub fn next_check_due(&self) -> Option<NaiveDateTime> {
+        let check_interval_secs = self.check_interval_secs?;
+        Some(self.last_checked? + Duration::from_secs(u64::from(check_interval_secs)))
+    }
+
+    /// Checks if the check interval of the program has passed at `now`.
+    #[must_use]
+    pub fn is_check_due(&self, now: NaiveDateTime) -> bool {
+        self.next_check_due().is_none_or(|due| due <= now)
+    }
+
+    /// Checks if the program was checked for updates less than `max_age` before `now`.
+    #[must_use]
+    pub fn checked_within(&self, max_age: Duration, now: NaiveDateTime) -> bool {
+        self.last_checked
+            .is_some_and(|last_checked| last_checked + max_age > now)
+    }
+
+    /// Returns the name under which the program is stored in the database.
+    ///
+    /// ```
+    /// use simple_update_checker::{FDroidProvider, Identifier, Program, Provider};
+    ///
+    /// let provider = Provider::FDroid(FDroidProvider {
+    ///     package: "org.fdroid.fdroid".to_string(),
+    /// });
+    /// let program = Program::new("fdroid", "1.20.0", provider);
+    /// assert_eq!("fdroid", program.name());
+    /// assert_eq!("fdroid", program.provider().identifier());
+    /// ```
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the version that is currently in use.
+    #[must_use]
+    pub fn current_version(&self) -> &str {
+        &self.current_version
+    }
+
+    /// Returns the last time the current version was updated.
+    #[must_use]
+    pub fn current_version_last_updated(&self) -> NaiveDateTime {
+        self.current_version_last_updated
+    }
+
+    /// Returns the newest version that is available.
+    #[must_use]
+    pub fn latest_version(&self) -> &str {
+        &self.latest_version
+    }
+
+    /// Returns the last time the latest version was updated.
+    #[must_use]
+    pub fn latest_version_last_updated(&self) -> NaiveDateTime {
+        self.latest_version_last_updated
+    }
+
+    /// Returns the provider from which the update information is taken.
+    #[must_use]
+    pub fn provider(&self) -> &Provider {
+        &self.provider
+    }
+
+    /// Returns the major version to which the update check is restricted.
+    #[must_use]
+    pub fn pin_major(&self) -> Option<u32> {
+        self.pin_major
+    }
+
+    /// Returns how versions are compared to decide if a found version is newer.
+    #[must_use]
+    pub fn version_scheme(&self) -> VersionScheme {
+        self.version_scheme
+    }
+
+    /// Returns if the latest version is set to an older version, when the provider reports an older version.
+    #[must_use]
+    pub fn allow_rollback(&self) -> bool {
+        self.allow_rollback
+    }
+
+    /// Returns the version reported during the last update check, if it was older than the latest version.
+    #[must_use]
+    pub fn older_upstream_version(&self) -> Option<&str> {
+        self.older_upstream_version.as_deref()
+    }
+
+    /// Returns the constraint that versions have to satisfy to be considered by the update check.
+    #[must_use]
+    pub fn version_constraint(&self) -> Option<&VersionConstraint> {
+        self.version_constraint.as_ref()
+    }
+
+    /// Returns the time at which the latest version was published, if the provider reports it.
+    #[must_use]
+    pub fn latest_version_published_at(&self) -> Option<NaiveDateTime> {
+        self.latest_version_published_at
+    }
+
+    /// Returns the number of seconds that have to pass between two update checks of the program.
+    #[must_use]
+    pub fn check_interval_secs(&self) -> Option<u32> {
+        self.check_interval_secs
+    }
+
+    /// Returns the last time the latest version was successfully requested from the provider.
+    #[must_use]
+    pub fn last_checked(&self) -> Option<NaiveDateTime> {
+        self.last_checked
+    }
+}
+
+/// Builder for [`Program`], see [`Program::builder`].
+///
+/// Name, provider and current version have to be set. The latest version defaults to the current version,
+/// the timestamps default to the time at which the program is built and all other settings to their default value.
+#[derive(Debug, Default, Clone)]
+#[must_use]
+pub struct ProgramBuilder {
+    name: Option<String>,
+    provider: Option<Provider>,
+    current_version: Option<String>,
+    current_version_last_updated: Option<NaiveDateTime>,
+    latest_version: Option<String>,
+    latest_version_last_updated: Option<NaiveDateTime>,
+    pin_major: Option<u32>,
+    version_scheme: VersionScheme,
+    allow_rollback: bool,
+    older_upstream_version: Option<String>,
+    version_constraint: Option<VersionConstraint>,
+    latest_version_published_at: Option<NaiveDateTime>,
+    check_interval_secs: Option<u32>,
+    last_checked: Option<NaiveDateTime>,
+}
+
+impl ProgramBuilder {
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn provider(mut self, provider: Provider) -> Self {
+        self.provider = Some(provider);
+        self
+    }
+
+    pub fn current_version(mut self, version: &str) -> Self {
+        self.current_version = Some(version.to_string());
+        self
+    }
+
+    pub fn current_version_last_updated(mut self, date: NaiveDateTime) -> Self {
+        self.current_version_last_updated = Some(date);
+        self
+    }
+
+    pub fn latest_version(mut self, version: &str) -> Self {
+        self.latest_version = Some(version.to_string());
+        self
+    }
+
+    pub fn latest_version_last_updated(mut self, date: NaiveDateTime) -> Self {
+        self.latest_version_last_updated = Some(date);
+        self
+    }
+
+    pub fn pin_major(mut self, pin_major: Option<u32>) -> Self {
+        self.pin_major = pin_major;
+        self
+    }
+
+    pub fn version_scheme(mut self, version_scheme: VersionScheme) -> Self {
+        self.version_scheme = version_scheme;
+        self
+    }
+
+    pub fn allow_rollback(mut self, allow_rollback: bool) -> Self {
+        self.allow_rollback = allow_rollback;
+        self
+    }
+
+    pub fn older_upstream_version(mut self, version: Option<&str>) -> Self {
+        self.older_upstream_version = version.map(str::to_string);
+        self
+    }
+
+    pub fn version_constraint(mut self, version_constraint: Option<VersionConstraint>) -> Self {
+        self.version_constraint = version_constraint;
+        self
+    }
+
+    pub fn latest_version_published_at(mut self, date: Option<NaiveDateTime>) -> Self {
+        self.latest_version_published_at = date;
+        self
+    }
+
+    pub fn check_interval_secs(mut self, check_interval_secs: Option<u32>) -> Self {
+        self.check_interval_secs = check_interval_secs;
+        self
+    }
+
+    pub fn last_checked(mut self, date: Option<NaiveDateTime>) -> Self {
+        self.last_checked = date;
+        self
+    }
+
+    /// Creates the program.
+    ///
+    /// Fails if name, provider or current version are not set, if the name is empty or contains a comma, or if a
+    /// version is empty or starts or ends with whitespace.
+    pub fn build(self) -> Result<Program> {
+        let Some(name) = self.name else {
+            anyhow::bail!("Name of the program is not set");
+        };
+        if name.trim().is_empty() {
+            anyhow::bail!("Name of the program must not be empty");
+        }
+        // names are separated by commas in lists, e.g. in the programs of an update check
+        if name.contains(',') {
+            anyhow::bail!("Name of program {name} must not contain a comma");
+        }
+        let Some(provider) = self.provider else {
+            anyhow::bail!("Provider of program {name} is not set");
+        };
+        let Some(current_version) = self.current_version else {
+            anyhow::bail!("Current version of program {name} is not set");
+        };
+        let latest_version = self
+            .latest_version
+            .unwrap_or_else(|| current_version.clone());
+        for version in [&current_version, &latest_version]
+            .into_iter()
+            .chain(&self.older_upstream_version)
+        {
+            if version.trim().is_empty() {
+                anyhow::bail!("Versions of program {name} must not be empty");
+            }
+            if version.trim() != version {
+                anyhow::bail!(
+                    "Version '{version}' of program {name} must not start or end with whitespace"
+                );
+            }
+        }
+        let now = Utc::now().naive_utc();
+        Ok(Program {
+            name,
+            current_version,
+            current_version_last_updated: self.current_version_last_updated.unwrap_or(now),
             latest_version,
-            latest_version_last_updated: Utc::now().naive_utc(),
+            latest_version_last_updated: self.latest_version_last_updated.unwrap_or(now),
             provider,
+            pin_major: self.pin_major,
+            version_scheme: self.version_scheme,
+            allow_rollback: self.allow_rollback,
+            older_upstream_version: self.older_upstream_version,
+            version_constraint: self.version_constraint,
+            latest_version_published_at: self.latest_version_published_at,
+            check_interval_secs: self.check_interval_secs,
+            last_checked: self.last_checked,
         })
     }
 }
@@ -67,94 +498,594 @@ pub fn format_datetime(value: &NaiveDateTime) -> String {
     local_time.format("%Y-%m-%d %H:%M:%S").to_string()
 }
 
+/// Displays the value or an empty string if it is `None`.
+fn display_option<T: Display>(value: &Option<T>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => String::new(),
+    }
+}
+
 /// Returns an identifier for this type.
 pub trait Identifier {
     fn identifier(&self) -> String;
 }
 
-#[derive(PartialEq, Debug, Clone)]
+/// The provider from which the latest version of a program is requested.
+///
+/// The variants are the built-in providers, providers of library users are stored as [`Provider::Custom`].
+/// All behavior of a provider is implemented with [`UpdateProvider`], see [`Provider::update_provider`].
+///
+/// Serialized with serde as a map whose `type` field contains the identifier of the provider,
+/// and whose other fields are the settings of the provider, e.g. `{ "type": "github", "repository": "owner/repo" }`.
+/// Custom providers can not be (de)serialized with serde.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
 pub enum Provider {
-    // String contains the gihub repository. For example: LMH01/simple_update_checker
-    Github(String),
+    #[serde(rename = "github")]
+    Github(GithubProvider),
+    #[serde(rename = "githubcommit")]
+    GithubCommit(GithubCommitProvider),
+    #[serde(rename = "homebrew")]
+    Homebrew(HomebrewProvider),
+    #[serde(rename = "fdroid")]
+    FDroid(FDroidProvider),
+    #[serde(rename = "command")]
+    Command(CommandProvider),
+    /// A provider that was registered with [`Db::register_provider`].
+    #[serde(skip)]
+    Custom(CustomProvider),
 }
 
-impl Identifier for Provider {
+/// Settings of a program for which the update information is taken from github.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct GithubProvider {
+    /// The github repository. For example: LMH01/simple_update_checker
+    pub repository: String,
+    /// Glob pattern (e.g. `*linux-x86_64*`) that at least one asset of a release has to match,
+    /// before the release is treated as the latest version.
+    #[serde(default)]
+    pub required_asset_pattern: Option<String>,
+    /// Name of the environment variable from which the access token for this repository is read.
+    /// If set, the token is used instead of the globally configured access token.
+    #[serde(default)]
+    pub access_token_env: Option<String>,
+    /// Defines which release is used as latest version.
+    #[serde(default)]
+    pub release_selector: ReleaseSelector,
+}
+
+/// Defines which github release is used as latest version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReleaseSelector {
+    /// The release github marks as latest release, pre-releases are never used.
+    #[default]
+    Latest,
+    /// The newest release, including pre-releases.
+    Newest,
+    /// The newest release that is not a pre-release.
+    NewestStable,
+}
+
+impl Identifier for ReleaseSelector {
+    fn identifier(&self) -> String {
+        match self {
+            Self::Latest => "latest".to_string(),
+            Self::Newest => "newest".to_string(),
+            Self::NewestStable => "newest-stable".to_string(),
+        }
+    }
+}
+
+impl Display for ReleaseSelector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.identifier())
+    }
+}
+
+impl FromStr for ReleaseSelector {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "latest" => Ok(Self::Latest),
+            "newest" => Ok(Self::Newest),
+            "newest-stable" => Ok(Self::NewestStable),
+            _ => Err(anyhow::anyhow!(
+                "ReleaseSelector could not be parsed from {s}"
+            )),
+        }
+    }
+}
+
+impl GithubProvider {
+    /// Creates settings for the repository with all optional settings unset.
+    #[must_use]
+    pub fn new(repository: &str) -> Self {
+        Self {
+            repository: repository.to_string(),
+            required_asset_pattern: None,
+            access_token_env: None,
+            release_selector: ReleaseSelector::Latest,
+        }
+    }
+}
+
+/// Settings of a program for which the latest commit of a github repository is used as version.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct GithubCommitProvider {
+    /// The github repository. For example: LMH01/simple_update_checker
+    pub repository: String,
+    /// Branch of which the latest commit is used. If not set, the default branch of the repository is used.
+    #[serde(default)]
+    pub branch: Option<String>,
+}
+
+/// Settings of a program for which the stable version of a homebrew formula or cask is used as version.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct HomebrewProvider {
+    /// Name of the formula or cask. For example: ripgrep
+    pub package: String,
+    /// Whether `package` is a formula or a cask.
+    pub kind: HomebrewKind,
+}
+
+/// Settings of a program for which the version of an android app in the F-Droid repository is used as version.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct FDroidProvider {
+    /// Package id of the app. For example: org.fdroid.fdroid
+    pub package: String,
+}
+
+/// Settings of a program for which the latest version is taken from the output of a local command.
+///
+/// The command is run with `sh -c`, so everything that can be run by the user can be run by the update checker.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct CommandProvider {
+    /// Shell command that prints the latest version. For example: kubectl version --client
+    pub command: String,
+    /// Regex that is applied to the output of the command. The first capture group is used as version,
+    /// or the whole match if the regex has no capture group.
+    pub regex: String,
+}
+
+/// Kind of a homebrew package.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HomebrewKind {
+    Formula,
+    Cask,
+}
+
+impl Identifier for HomebrewKind {
     fn identifier(&self) -> String {
         match self {
-            Self::Github(_) => "github".to_string(),
+            Self::Formula => "formula".to_string(),
+            Self::Cask => "cask".to_string(),
         }
     }
 }
 
+impl Display for HomebrewKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.identifier())
+    }
+}
+
+impl FromStr for HomebrewKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "formula" => Ok(Self::Formula),
+            "cask" => Ok(Self::Cask),
+            _ => Err(anyhow::anyhow!("HomebrewKind could not be parsed from {s}")),
+        }
+    }
+}
+
+impl Provider {
+    /// Returns the implementation of this provider.
+    #[must_use]
+    pub fn update_provider(&self) -> &dyn UpdateProvider {
+        match self {
+            Self::Github(github) => github,
+            Self::GithubCommit(github_commit) => github_commit,
+            Self::Homebrew(homebrew) => homebrew,
+            Self::FDroid(fdroid) => fdroid,
+            Self::Command(command) => command,
+            Self::Custom(custom) => custom.provider(),
+        }
+    }
+
+    /// Checks what the latest version for the program using this provider is.
+    pub async fn check_for_latest_version(
+        &self,
+        client: &Client,
+        auth: &AuthContext,
+    ) -> Result<LatestVersion, ProviderError> {
+        self.update_provider()
+            .check_for_latest_version(client, auth)
+            .await
+    }
+
+    /// Returns the url of the web page of the release with the version `version`.
+    ///
+    /// `None` if the provider has no web page.
+    #[must_use]
+    pub fn web_url(&self, version: &str) -> Option<String> {
+        self.update_provider().web_url(version)
+    }
+}
+
+impl Identifier for Provider {
+    fn identifier(&self) -> String {
+        self.update_provider().identifier()
+    }
+}
+
 impl Display for Provider {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.identifier())
     }
 }
 
-pub struct DbConfig {
-    pub db_path: String,
+/// Errors that can occur while a provider is queried for the latest version of a program.
+#[derive(Debug, thiserror::Error)]
+pub enum ProviderError {
+    /// The requested resource (e.g. the repository or its latest release) does not exist.
+    #[error("Requested resource was not found")]
+    NotFound,
+    /// The repository does not exist or is not visible with the used access token.
+    #[error("Repository {0} not found")]
+    RepositoryNotFound(String),
+    /// The repository was not found and no access token was used.
+    ///
+    /// Github answers with the same status for private repositories, so the repository may exist.
+    #[error("Repository {0} not found — it may be private; configure a GitHub token")]
+    RepositoryNotFoundUnauthenticated(String),
+    /// The repository exists but has no published releases.
+    #[error("Repository {0} exists but has no published releases")]
+    NoReleases(String),
+    /// The homebrew formula or cask does not exist.
+    #[error("Unknown homebrew {kind} {package}")]
+    HomebrewPackageNotFound { kind: HomebrewKind, package: String },
+    /// The app does not exist in the F-Droid repository.
+    #[error("Unknown F-Droid package {0}")]
+    FDroidPackageNotFound(String),
+    /// The command could not be run, did not finish in time or exited with an error.
+    #[error("Command failed: {0}")]
+    CommandFailed(String),
+    /// The output of the command did not match the regex.
+    #[error("Output of the command did not match regex {0}")]
+    NoCommandOutputMatch(String),
+    /// The configured regex of the command is invalid.
+    #[error("Invalid regex: {0}")]
+    InvalidRegex(#[from] regex::Error),
+    /// The provider denied access, e.g. because the repository is private or the access token is invalid.
+    #[error("Access denied (private repository or invalid access token)")]
+    AccessDenied,
+    /// The rate limit of the provider has been exceeded.
+    ///
+    /// `reset` contains the time at which the rate limit is reset, if the provider told us.
+    #[error("Rate limit exceeded{}", format_rate_limit_reset(.reset))]
+    RateLimited { reset: Option<DateTime<Utc>> },
+    /// The provider answered with a non success status code.
+    #[error("Request failed with status {0}")]
+    Http(StatusCode),
+    /// The request could not be sent or the response could not be received.
+    #[error("Network error: {0}")]
+    Network(#[from] reqwest::Error),
+    /// The response was received but did not contain the expected data.
+    #[error("Unable to parse response: {0}")]
+    Parse(String),
+    /// A release was found but none of its assets matches the required asset pattern.
+    #[error("Release {tag} found but no asset matching '{pattern}' is available yet")]
+    AssetNotAvailable { tag: String, pattern: String },
+    /// The environment variable that should contain the access token is not set.
+    #[error("Environment variable {0} that should contain the access token is not set")]
+    MissingAccessToken(String),
+    /// No version with the major version to which the program is pinned was found.
+    #[error("No release with major version {0} found")]
+    NoVersionInMajor(u32),
+    /// No version that satisfies the version constraint of the program was found.
+    #[error("No release matching version constraint {0} found")]
+    NoVersionMatchingConstraint(String),
+    /// The configured asset pattern is not a valid glob.
+    #[error("Invalid asset pattern: {0}")]
+    InvalidAssetPattern(#[from] globset::Error),
 }
 
-impl DbConfig {
-    /// Tries to create a db config by trying to load the config file from
-    /// '~/.`config/simple_update_checker/config.toml`'.
-    /// If the config is found and the cli argument '--db-path' is not set, the value from that config is taken.
-    /// If the cli argument is set, its value will be used instead of the value from the config.
-    pub fn try_create(db_args: DbArgs) -> Result<Self> {
-        // try to load config at ~/.config/simple_update_checker/config.toml
-        let db_config = match ConfigFile::try_parse() {
+impl ProviderError {
+    /// Checks if the error was caused by the network being unreachable, e.g. because the dns lookup failed
+    /// or no connection to the server could be established.
+    #[must_use]
+    pub fn is_offline(&self) -> bool {
+        matches!(self, Self::Network(e) if e.is_connect() || e.is_timeout())
+    }
+}
+
+fn format_rate_limit_reset(reset: &Option<DateTime<Utc>>) -> String {
+    match reset {
+        Some(reset) => format!(", resets on {}", format_datetime(&reset.naive_utc())),
+        None => String::new(),
+    }
+}
+
+/// A secret, like an access token, that is not shown in debug output.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    /// Returns the value of the secret.
+    #[must_use]
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Debug for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Secret(redacted)")
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl FromStr for Secret {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(Self(s.to_string()))
+    }
+}
+
+/// Settings that are used when the github api is queried.
+#[derive(Debug, Clone)]
+pub struct GithubApiConfig {
+    /// Base url of the github api.
+    pub api_url: String,
+    /// Access token that is used to increase the rate limit.
+    pub access_token: Option<Secret>,
+    /// Access tokens that are used in turn, when the rate limit of the current access token is exceeded.
+    pub fallback_access_tokens: Vec<Secret>,
+    /// Index of the access token in [`GithubApiConfig::access_tokens`] that is currently used.
+    ///
+    /// Shared between the clones of the config, so that all requests continue with the token that was rotated to.
+    pub active_access_token: Arc<AtomicUsize>,
+    /// Maximum number of pages that are requested when a list (e.g. of releases) is fetched.
+    pub max_pages: u32,
+    /// If set, the stored repository of a program is updated when the repository was renamed.
+    pub auto_follow_renames: bool,
+}
+
+impl Default for GithubApiConfig {
+    fn default() -> Self {
+        Self {
+            api_url: "https://api.github.com".to_string(),
+            access_token: None,
+            fallback_access_tokens: Vec::new(),
+            active_access_token: Arc::new(AtomicUsize::new(0)),
+            max_pages: 5,
+            auto_follow_renames: true,
+        }
+    }
+}
+
+impl GithubApiConfig {
+    /// Returns the access tokens that requests are sent with, in the order in which they are rotated through.
+    ///
+    /// The first entry is the access token, `None` if requests are unauthenticated, followed by the fallback access
+    /// tokens.
+    #[must_use]
+    pub fn access_tokens(&self) -> Vec<Option<&Secret>> {
+        iter::once(self.access_token.as_ref())
+            .chain(self.fallback_access_tokens.iter().map(Some))
+            .collect()
+    }
+}
+
+/// Base urls of the apis that are queried by the providers other than github.
+#[derive(Debug, Clone)]
+pub struct ProviderApiConfig {
+    /// Base url of the homebrew api.
+    pub homebrew_api_url: String,
+    /// Base url of the F-Droid api.
+    pub fdroid_api_url: String,
+}
+
+impl Default for ProviderApiConfig {
+    fn default() -> Self {
+        Self {
+            homebrew_api_url: "https://formulae.brew.sh/api".to_string(),
+            fdroid_api_url: "https://f-droid.org/api/v1".to_string(),
+        }
+    }
+}
+
+/// Settings that are used by the actions, resolved from the cli, environment variables and the config file.
+pub struct Settings {
+    pub db_config: DbConfig,
+    /// Settings and credentials that are used by the providers.
+    pub auth: AuthContext,
+    /// Timeout of a single http request.
+    pub request_timeout: Option<Duration>,
+    /// Programs defined in the config file, `None` if the config file does not define programs.
+    pub config_programs: Option<Vec<ProgramConfig>>,
+    /// Remove programs that are not defined in the config file when the programs are synced.
+    pub prune: bool,
+    /// Style in which the borders of tables are drawn.
+    pub table_style: TableStyle,
+}
+
+impl Settings {
+    /// Tries to resolve the settings by trying to load the config file from '--config' or
+    /// [`config::default_config_path`].
+    /// Returns an error if the config file set with '--config' can not be loaded, other config files that can not be
+    /// parsed are ignored with a warning.
+    /// Values set with cli arguments or environment variables are used instead of the values from the config,
+    /// see [`Settings::resolve_with_config`].
+    /// If `print_messages` is false, only warnings are printed.
+    pub fn resolve(cli: &mut Cli, print_messages: bool) -> Result<Self> {
+        // try to load config from --config or from the default location
+        let config = match ConfigFile::try_parse(cli.config.as_deref()) {
+            Err(e) if cli.config.is_some() => {
+                return Err(e.context("Unable to load config file set with --config"));
+            }
             Err(e) => {
                 println!(
-                    "Warning: unable to parse config at ~/.config/simple_update_checker/config.toml : {e}"
+                    "Warning: unable to parse config at {} : {e}",
+                    config::default_config_path().display()
                 );
-                DbConfig::from(db_args)
+                None
             }
             Ok(Some(config)) => {
-                println!("Using config file found at {}", config.path);
+                if print_messages {
+                    println!("Using config file found at {}", config.path);
+                }
                 // check if db_path is set using cli
-                if let Some(db_path) = &db_args.db_path {
+                if print_messages && let Some(db_path) = &cli.db_args.db_path {
                     println!(
                         "Not using db_path setting found in config file ({}) as --db-path is set ({})",
                         config.db_path, db_path
                     );
-                    DbConfig::from(db_args)
-                } else {
-                    DbConfig {
-                        db_path: config.db_path,
-                    }
                 }
+                Some(config)
             }
-            Ok(None) => DbConfig::from(db_args),
+            Ok(None) => None,
         };
 
-        println!("Using database file: {}", db_config.db_path);
+        let settings = Settings::resolve_with_config(cli, config.as_ref())?;
 
-        Ok(db_config)
+        if print_messages {
+            println!("Using database file: {}", settings.db_config.db_path);
+        }
+
+        Ok(settings)
+    }
+
+    /// Resolves the settings from the cli and the config file.
+    ///
+    /// The config file is applied to the cli with [`Cli::apply_config_file`], so that values set with cli arguments
+    /// or environment variables take precedence over values from the config file. The database is determined with
+    /// [`DbConfig::resolve`].
+    ///
+    /// Fails if the deadline of the update checks of `run-timed` is not longer than the request timeout.
+    pub fn resolve_with_config(cli: &mut Cli, config: Option<&ConfigFile>) -> Result<Self> {
+        if let Some(config) = config {
+            cli.apply_config_file(config);
+        }
+        if let Command::RunTimed(run_timed_args) = &cli.command
+            && let Some(request_timeout) = cli.request_timeout
+            && u64::from(run_timed_args.check_deadline) <= request_timeout
+        {
+            anyhow::bail!(
+                "The check deadline ({} seconds) has to be longer than the request timeout ({request_timeout} seconds)",
+                run_timed_args.check_deadline
+            );
+        }
+        Ok(Self {
+            db_config: DbConfig::resolve(cli.db_args.clone(), config)?,
+            auth: AuthContext::from(GithubApiConfig {
+                access_token: cli.github_access_token.clone(),
+                fallback_access_tokens: cli.github_access_token_fallback.clone(),
+                max_pages: cli.github_max_pages,
+                auto_follow_renames: cli.auto_follow_renames,
+                ..Default::default()
+            }),
+            request_timeout: cli.request_timeout.map(Duration::from_secs),
+            config_programs: config.and_then(|config| config.programs.clone()),
+            prune: config.is_some_and(|config| config.prune),
+            table_style: cli.table_style,
+        })
+    }
+
+    /// Creates the client that is used to send requests, using the configured request timeout.
+    #[must_use]
+    pub fn client(&self) -> Client {
+        http::client_with_timeout(self.request_timeout)
     }
 }
 
-impl Default for DbConfig {
-    fn default() -> Self {
-        Self {
-            db_path: "programs.db".to_string(),
+pub struct DbConfig {
+    pub db_path: String,
+    /// If set, the database is opened read-only and commands never write to it.
+    pub read_only: bool,
+}
+
+impl DbConfig {
+    /// Determines the database that should be used.
+    ///
+    /// The database path is taken from the first of these sources that is set:
+    /// 1. '--db-path'
+    /// 2. 'db_path' in the '[profiles.<PROFILE>]' section of the config file, when a profile is selected
+    /// 3. '~/.config/simple_update_checker/<PROFILE>.db', when a profile is selected
+    /// 4. 'db_path' in the config file
+    /// 5. 'programs.db'
+    pub fn resolve(db_args: DbArgs, config: Option<&ConfigFile>) -> Result<Self> {
+        let read_only = db_args.read_only;
+        if let Some(db_path) = db_args.db_path {
+            return Ok(DbConfig { db_path, read_only });
+        }
+        if let Some(profile) = db_args.profile {
+            if let Some(db_path) = config
+                .and_then(|config| config.profiles.get(&profile))
+                .and_then(|profile| profile.db_path.clone())
+            {
+                return Ok(DbConfig { db_path, read_only });
+            }
+            let db_path = config::default_profile_db_path(&profile);
+            if let Some(parent) = db_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            return Ok(DbConfig {
+                db_path: db_path.to_string_lossy().to_string(),
+                read_only,
+            });
+        }
+        if let Some(config) = config {
+            return Ok(DbConfig {
+                db_path: config.db_path.clone(),
+                read_only,
+            });
+        }
+        Ok(DbConfig {
+            read_only,
+            ..Default::default()
+        })
+    }
+
+    /// Connects to the database, read-only when `read_only` is set.
+    pub async fn connect(&self) -> Result<Db> {
+        if self.read_only {
+            Db::connect_read_only(&self.db_path).await
+        } else {
+            Db::connect(&self.db_path).await
         }
     }
 }
 
-impl From<DbArgs> for DbConfig {
-    fn from(value: DbArgs) -> Self {
-        if let Some(path) = value.db_path {
-            return DbConfig { db_path: path };
+impl Default for DbConfig {
+    fn default() -> Self {
+        Self {
+            db_path: "programs.db".to_string(),
+            read_only: false,
         }
-        DbConfig::default()
     }
 }
 
 /// Represents a single update check.
-#[derive(FromRow, Debug, PartialEq, Tabled)]
+///
+/// The field names are part of the external schema when the entry is (de)serialized with serde.
+#[derive(FromRow, Debug, PartialEq, Clone, Tabled, Serialize, Deserialize)]
 pub struct UpdateCheckHistoryEntry {
     #[tabled(rename = "Date", display("format_datetime"))]
+    #[serde(with = "rfc3339")]
     pub date: NaiveDateTime,
     #[tabled(rename = "Type")]
     pub r#type: UpdateCheckType,
@@ -162,12 +1093,35 @@ pub struct UpdateCheckHistoryEntry {
     pub updates_available: u32,
     #[tabled(rename = "Programs")]
     pub programs: String,
+    /// How long the update check took. Not available for update checks performed with older versions.
+    #[tabled(rename = "Duration (ms)", display("display_option"))]
+    #[serde(default)]
+    pub duration_ms: Option<u32>,
+    /// Number of programs for which the update check failed. Not available for update checks performed with older versions.
+    #[tabled(rename = "Errors", display("display_option"))]
+    #[serde(default)]
+    pub errors: Option<u32>,
+    /// The shard of programs that was checked, `None` if all programs where checked.
+    #[tabled(rename = "Shard", display("display_option"))]
+    #[serde(default)]
+    pub shard: Option<Shard>,
+    /// Set when the update check was stopped before all programs where checked, e.g. because its deadline was exceeded.
+    #[tabled(rename = "Truncated")]
+    #[serde(default)]
+    pub truncated: bool,
 }
 
 impl UpdateCheckHistoryEntry {
     /// Creates a new `UpdateCheck` entry from the current time and date.
     #[must_use]
-    pub fn from_now(r#type: UpdateCheckType, mut programs_with_updates: Vec<Program>) -> Self {
+    pub fn from_now(
+        r#type: UpdateCheckType,
+        mut programs_with_updates: Vec<Program>,
+        duration: Duration,
+        errors: u32,
+        shard: Option<Shard>,
+        truncated: bool,
+    ) -> Self {
         programs_with_updates.sort_by(|a, b| a.name.cmp(&b.name));
         Self {
             date: Utc::now().naive_utc(),
@@ -178,14 +1132,21 @@ impl UpdateCheckHistoryEntry {
                 .map(|f| format!("{} ({})", f.name, f.latest_version))
                 .collect::<Vec<String>>()
                 .join(", "),
+            duration_ms: Some(u32::try_from(duration.as_millis()).unwrap_or(u32::MAX)),
+            errors: Some(errors),
+            shard,
+            truncated,
         }
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum UpdateCheckType {
     Manual,
     Timed,
+    /// Update check of `run-timed` that was requested with `SIGUSR1` or the trigger endpoint.
+    Triggered,
 }
 
 impl Identifier for UpdateCheckType {
@@ -193,6 +1154,7 @@ impl Identifier for UpdateCheckType {
         match self {
             UpdateCheckType::Manual => "manual".to_string(),
             UpdateCheckType::Timed => "timed".to_string(),
+            UpdateCheckType::Triggered => "triggered".to_string(),
         }
     }
 }
@@ -210,6 +1172,7 @@ impl FromStr for UpdateCheckType {
         match s {
             "manual" => Ok(UpdateCheckType::Manual),
             "timed" => Ok(UpdateCheckType::Timed),
+            "triggered" => Ok(UpdateCheckType::Triggered),
             _ => Err(anyhow::anyhow!(
                 "UpdateCheckType could not be parsed from {s}"
             )),
@@ -217,14 +1180,203 @@ impl FromStr for UpdateCheckType {
     }
 }
 
+/// A part of all programs, used to spread the update checks of many programs over multiple runs.
+///
+/// Programs are assigned to a shard by a stable hash of their name.
+///
+/// Serialized with serde as string in the format `INDEX/COUNT`, e.g. `1/4`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Shard {
+    /// Number of the shard, starting at 1.
+    pub index: u32,
+    /// Total number of shards.
+    pub count: u32,
+}
+
+impl Shard {
+    /// Checks if the program named `name` belongs to this shard.
+    #[must_use]
+    pub fn contains(&self, name: &str) -> bool {
+        fnv1a(name) % u64::from(self.count) == u64::from(self.index - 1)
+    }
+}
+
+/// Template for the summary line of `check`, e.g. `{pending}/{total} updates`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputTemplate(String);
+
+impl OutputTemplate {
+    /// Placeholders that can be used in the template.
+    const PLACEHOLDERS: [&str; 4] = ["total", "pending", "errors", "timestamp"];
+
+    /// Replaces the placeholders with the values of an update check.
+    #[must_use]
+    pub fn render(&self, total: usize, pending: usize, errors: usize, timestamp: &str) -> String {
+        self.0
+            .replace("{total}", &total.to_string())
+            .replace("{pending}", &pending.to_string())
+            .replace("{errors}", &errors.to_string())
+            .replace("{timestamp}", timestamp)
+    }
+}
+
+impl FromStr for OutputTemplate {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut rest = s;
+        while let Some(start) = rest.find('{') {
+            let Some(end) = rest[start..].find('}') else {
+                anyhow::bail!("Placeholder at '{}' is not closed", &rest[start..]);
+            };
+            let placeholder = &rest[start + 1..start + end];
+            if !Self::PLACEHOLDERS.contains(&placeholder) {
+                anyhow::bail!(
+                    "Unknown placeholder '{{{placeholder}}}', supported placeholders are {{total}}, {{pending}}, {{errors}} and {{timestamp}}"
+                );
+            }
+            rest = &rest[start + end + 1..];
+        }
+        Ok(Self(s.to_string()))
+    }
+}
+
+/// Calculates the 64 bit FNV-1a hash of `value`.
+///
+/// Used instead of the std hasher, as its output is not guaranteed to be stable between releases.
+fn fnv1a(value: &str) -> u64 {
+    value.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+impl Display for Shard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.index, self.count)
+    }
+}
+
+impl FromStr for Shard {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let Some((index, count)) = s.split_once('/') else {
+            anyhow::bail!("Shard should be in the format INDEX/COUNT, for example 1/4");
+        };
+        let index: u32 = index.trim().parse()?;
+        let count: u32 = count.trim().parse()?;
+        if count == 0 || index == 0 || index > count {
+            anyhow::bail!("Shard index should be between 1 and {count}");
+        }
+        Ok(Self { index, count })
+    }
+}
+
+impl TryFrom<String> for Shard {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> std::result::Result<Self, Self::Error> {
+        Self::from_str(&value)
+    }
+}
+
+impl From<Shard> for String {
+    fn from(value: Shard) -> Self {
+        value.to_string()
+    }
+}
+
+/// Time window of the day in which update checks are performed in timed mode, in local time.
+///
+/// The window may cross midnight, e.g. `22:00-06:00`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActiveHours {
+    /// Time at which the window opens.
+    pub start: NaiveTime,
+    /// Time at which the window closes.
+    pub end: NaiveTime,
+}
+
+impl ActiveHours {
+    /// Checks if `time` is within the window, the start is included and the end is excluded.
+    #[must_use]
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start < self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+
+    /// Returns how long it takes from `time` until the window opens, zero if `time` is within the window.
+    #[must_use]
+    pub fn until_open(&self, time: NaiveTime) -> Duration {
+        if self.contains(time) {
+            return Duration::ZERO;
+        }
+        match (self.start - time).to_std() {
+            Ok(until_open) => until_open,
+            // the window opens on the next day
+            Err(_) => {
+                Duration::from_secs(24 * 60 * 60) - (time - self.start).to_std().unwrap_or_default()
+            }
+        }
+    }
+}
+
+impl Display for ActiveHours {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+impl FromStr for ActiveHours {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let Some((start, end)) = s.split_once('-') else {
+            anyhow::bail!(
+                "Active hours should be in the format HH:MM-HH:MM, for example 07:00-23:00"
+            );
+        };
+        let start = NaiveTime::parse_from_str(start.trim(), "%H:%M")?;
+        let end = NaiveTime::parse_from_str(end.trim(), "%H:%M")?;
+        if start == end {
+            anyhow::bail!("Start and end of the active hours should differ");
+        }
+        Ok(Self { start, end })
+    }
+}
+
 pub struct NotificationInfo {
     pub sent: bool,
     pub sent_on: Option<NaiveDateTime>,
 }
 
-#[derive(FromRow, Debug, PartialEq, Tabled)]
+/// State of the notification backoff of a program.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct NotificationBackoff {
+    /// Notifications for the program are suppressed until this time.
+    pub until: Option<NaiveDateTime>,
+    /// Length of the last backoff interval in seconds.
+    pub secs: Option<u32>,
+    /// Version for which the last notification was sent.
+    pub notified_version: Option<String>,
+}
+
+/// An update of a program from `old_version` to `updated_to`.
+///
+/// The field names are part of the external schema when the entry is (de)serialized with serde.
+#[derive(FromRow, Debug, PartialEq, Tabled, Serialize, Deserialize)]
 pub struct UpdateHistoryEntry {
     #[tabled(rename = "Date", display("format_datetime"))]
+    #[serde(with = "rfc3339")]
     pub date: NaiveDateTime,
     #[tabled(rename = "Name")]
     pub name: String,
@@ -233,3 +1385,706 @@ pub struct UpdateHistoryEntry {
     #[tabled(rename = "Updated to")]
     pub updated_to: String,
 }
+
+/// A version of a program that was found during an update check.
+#[derive(FromRow, Debug, PartialEq, Tabled)]
+pub struct VersionHistoryEntry {
+    #[tabled(rename = "Found on", display("format_datetime"))]
+    pub date: NaiveDateTime,
+    #[tabled(rename = "Name")]
+    pub name: String,
+    #[tabled(rename = "Version")]
+    pub version: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, str::FromStr, time::Duration};
+
+    use clap::Parser;
+    use sqlx::types::chrono::{NaiveDateTime, NaiveTime};
+
+    use crate::{
+        ActiveHours, CommandProvider, DbConfig, FDroidProvider, GithubCommitProvider,
+        GithubProvider, HomebrewKind, HomebrewProvider, Identifier, OutputTemplate, Program,
+        Provider, ReleaseSelector, Secret, Settings, Shard, UpdateCheckHistoryEntry,
+        UpdateCheckType, UpdateHistoryEntry, VersionConstraint, VersionScheme,
+        cli::{Cli, Command, DbArgs, RunTimedArgs, TableStyle},
+        config::{ConfigFile, NotificationsConfig, NtfyConfig, ProfileConfig, SecretRef},
+    };
+
+    #[test]
+    fn test_active_hours_from_str() {
+        let active_hours = ActiveHours::from_str("07:00-23:30").unwrap();
+        assert_eq!(
+            NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+            active_hours.start
+        );
+        assert_eq!(
+            NaiveTime::from_hms_opt(23, 30, 0).unwrap(),
+            active_hours.end
+        );
+        assert_eq!("07:00-23:30", active_hours.to_string());
+        assert!(ActiveHours::from_str("22:00-06:00").is_ok());
+        assert!(ActiveHours::from_str("07:00").is_err());
+        assert!(ActiveHours::from_str("07:00-25:00").is_err());
+        assert!(ActiveHours::from_str("07:00-07:00").is_err());
+    }
+
+    #[test]
+    fn test_active_hours_until_open() {
+        let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        let day = ActiveHours::from_str("07:00-23:00").unwrap();
+        assert!(day.contains(time(7, 0)));
+        assert!(!day.contains(time(23, 0)));
+        assert_eq!(Duration::ZERO, day.until_open(time(12, 0)));
+        assert_eq!(Duration::from_secs(3600), day.until_open(time(6, 0)));
+        assert_eq!(Duration::from_secs(8 * 3600), day.until_open(time(23, 0)));
+        // the window crosses midnight
+        let night = ActiveHours::from_str("22:00-06:00").unwrap();
+        assert!(night.contains(time(23, 0)));
+        assert!(night.contains(time(5, 59)));
+        assert!(!night.contains(time(6, 0)));
+        assert_eq!(Duration::ZERO, night.until_open(time(1, 0)));
+        assert_eq!(Duration::from_secs(16 * 3600), night.until_open(time(6, 0)));
+        assert_eq!(Duration::from_secs(30 * 60), night.until_open(time(21, 30)));
+        assert_eq!(
+            Duration::from_millis(500),
+            night.until_open(NaiveTime::from_hms_milli_opt(21, 59, 59, 500).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_update_check_type_identifier() {
+        for update_check_type in [
+            UpdateCheckType::Manual,
+            UpdateCheckType::Timed,
+            UpdateCheckType::Triggered,
+        ] {
+            assert_eq!(
+                update_check_type,
+                UpdateCheckType::from_str(&update_check_type.identifier()).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_shard_from_str() {
+        assert_eq!(
+            Shard { index: 1, count: 4 },
+            Shard::from_str("1/4").unwrap()
+        );
+        assert!(Shard::from_str("0/4").is_err());
+        assert!(Shard::from_str("5/4").is_err());
+        assert!(Shard::from_str("1/0").is_err());
+        assert!(Shard::from_str("1").is_err());
+    }
+
+    #[test]
+    fn test_output_template() {
+        let template = OutputTemplate::from_str("{pending}/{total} updates").unwrap();
+        assert_eq!("2/5 updates", template.render(5, 2, 0, "2026-10-17 12:00"));
+        let template =
+            OutputTemplate::from_str("{errors} errors at {timestamp}, {pending} pending").unwrap();
+        assert_eq!(
+            "1 errors at 2026-10-17 12:00, 0 pending",
+            template.render(3, 0, 1, "2026-10-17 12:00")
+        );
+        assert_eq!(
+            "no placeholders",
+            OutputTemplate::from_str("no placeholders")
+                .unwrap()
+                .render(1, 1, 1, "")
+        );
+        assert!(OutputTemplate::from_str("{updates}").is_err());
+        assert!(OutputTemplate::from_str("{pending").is_err());
+        assert!(OutputTemplate::from_str("{}").is_err());
+    }
+
+    #[test]
+    fn test_shard_contains() {
+        let names = (0..100).map(|i| format!("program_{i}")).collect::<Vec<_>>();
+        let shards = (1..=4).map(|index| Shard { index, count: 4 });
+        let mut checked = 0;
+        for shard in shards {
+            let in_shard = names.iter().filter(|name| shard.contains(name)).count();
+            // the programs should be spread roughly evenly
+            assert!(
+                in_shard > 10,
+                "shard {shard} contains only {in_shard} programs"
+            );
+            checked += in_shard;
+        }
+        // every program belongs to exactly one shard
+        assert_eq!(names.len(), checked);
+        assert!(Shard { index: 1, count: 1 }.contains("simple_update_checker"));
+    }
+
+    #[test]
+    fn test_provider_web_url() {
+        let provider = Provider::Github(GithubProvider::new("LMH01/simple_update_checker"));
+        assert_eq!(
+            "https://github.com/LMH01/simple_update_checker/releases/tag/v1.2.0",
+            provider.web_url("v1.2.0").unwrap()
+        );
+    }
+
+    /// Program whose versions where found at `found` and that was last checked at `last_checked`.
+    fn alpha_tui(found: NaiveDateTime, last_checked: Option<NaiveDateTime>) -> Program {
+        Program::builder()
+            .name("alpha_tui")
+            .current_version("v1.0.0")
+            .current_version_last_updated(found)
+            .latest_version_last_updated(found)
+            .provider(Provider::Github(GithubProvider::new("LMH01/alpha_tui")))
+            .last_checked(last_checked)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_program_is_stale() {
+        let date = |date: &str| NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S").unwrap();
+        let mut program = alpha_tui(date("2025-03-12 13:45:00"), None);
+        let stale_after = Duration::from_secs(180 * 24 * 60 * 60);
+        assert!(!program.is_stale(stale_after, date("2025-06-01 00:00:00")));
+        assert!(program.is_stale(stale_after, date("2025-10-01 00:00:00")));
+        // the publish date takes precedence over the date at which the version was found
+        program.latest_version_published_at = Some(date("2024-01-01 00:00:00"));
+        assert!(program.is_stale(stale_after, date("2025-06-01 00:00:00")));
+    }
+
+    #[test]
+    fn test_program_is_check_due() {
+        let date = |date: &str| NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S").unwrap();
+        let mut program = alpha_tui(
+            date("2025-03-12 13:45:00"),
+            Some(date("2025-03-12 13:45:00")),
+        );
+        // without check interval the program is checked every time
+        assert_eq!(None, program.next_check_due());
+        assert!(program.is_check_due(date("2025-03-12 13:46:00")));
+        program.check_interval_secs = Some(24 * 60 * 60);
+        assert_eq!(Some(date("2025-03-13 13:45:00")), program.next_check_due());
+        assert!(!program.is_check_due(date("2025-03-13 13:44:59")));
+        assert!(program.is_check_due(date("2025-03-13 13:45:00")));
+        program.last_checked = None;
+        assert!(program.is_check_due(date("2025-03-12 13:46:00")));
+    }
+
+    #[test]
+    fn test_program_checked_within() {
+        let date = |date: &str| NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S").unwrap();
+        let mut program = alpha_tui(
+            date("2025-03-12 13:45:00"),
+            Some(date("2025-03-12 13:45:00")),
+        );
+        let max_age = Duration::from_secs(10 * 60);
+        assert!(program.checked_within(max_age, date("2025-03-12 13:54:59")));
+        assert!(!program.checked_within(max_age, date("2025-03-12 13:55:00")));
+        // programs that where never checked are always checked
+        program.last_checked = None;
+        assert!(!program.checked_within(max_age, date("2025-03-12 13:46:00")));
+    }
+
+    fn config_file() -> ConfigFile {
+        let mut profiles = HashMap::new();
+        profiles.insert(
+            "work".to_string(),
+            ProfileConfig {
+                db_path: Some("work_profile.db".to_string()),
+                github_access_token: None,
+            },
+        );
+        ConfigFile {
+            path: "config.toml".to_string(),
+            db_path: "config.db".to_string(),
+            github_access_token: None,
+            github_access_token_file: None,
+            request_timeout: Some(30),
+            ntfy_topic: Some("config_topic".to_string()),
+            ntfy_server: Some("https://ntfy.example.com".to_string()),
+            check_interval: Some(7200),
+            default_provider: None,
+            profiles,
+            notifications: NotificationsConfig::default(),
+            programs: None,
+            prune: false,
+        }
+    }
+
+    fn run_timed_args(cli: &Cli) -> &RunTimedArgs {
+        match &cli.command {
+            Command::RunTimed(run_timed_args) => run_timed_args,
+            _ => panic!("expected run-timed"),
+        }
+    }
+
+    #[test]
+    fn test_settings_cli_takes_precedence() {
+        let mut cli = Cli::parse_from([
+            "suc",
+            "--request-timeout",
+            "5",
+            "--github-access-token",
+            "cli_token",
+            "run-timed",
+            "--ntfy-topic",
+            "cli_topic",
+            "--ntfy-server",
+            "https://ntfy.cli.com",
+            "--check-interval",
+            "60",
+        ]);
+        let mut config_file = config_file();
+        config_file.github_access_token = Some(SecretRef::from("config_token"));
+        let settings = Settings::resolve_with_config(&mut cli, Some(&config_file)).unwrap();
+        assert_eq!(Some(Duration::from_secs(5)), settings.request_timeout);
+        assert_eq!(
+            Some("cli_token"),
+            settings
+                .auth
+                .github
+                .access_token
+                .as_ref()
+                .map(Secret::expose)
+        );
+        let run_timed_args = run_timed_args(&cli);
+        assert_eq!(Some("cli_topic".to_string()), run_timed_args.ntfy_topic);
+        assert_eq!("https://ntfy.cli.com", run_timed_args.ntfy_server());
+        assert_eq!(60, run_timed_args.check_interval());
+    }
+
+    #[test]
+    fn test_settings_table_style() {
+        let resolve = |args: &[&str]| {
+            let mut cli = Cli::parse_from(args);
+            Settings::resolve_with_config(&mut cli, None).unwrap()
+        };
+        assert_eq!(
+            TableStyle::Ascii,
+            resolve(&["suc", "list-programs"]).table_style
+        );
+        // the option is global, so it can also be set after the subcommand
+        assert_eq!(
+            TableStyle::Markdown,
+            resolve(&["suc", "list-programs", "--table-style", "markdown"]).table_style
+        );
+    }
+
+    #[test]
+    fn test_settings_check_deadline() {
+        let resolve = |args: &[&str], config_file: Option<&ConfigFile>| {
+            let mut cli = Cli::parse_from(args);
+            Settings::resolve_with_config(&mut cli, config_file)
+        };
+        // the default deadline of 600 seconds is shorter than the request timeout
+        assert!(resolve(&["suc", "--request-timeout", "900", "run-timed"], None).is_err());
+        assert!(
+            resolve(
+                &[
+                    "suc",
+                    "--request-timeout",
+                    "5",
+                    "run-timed",
+                    "--check-deadline",
+                    "30"
+                ],
+                None
+            )
+            .is_ok()
+        );
+        // the request timeout of the config file is used
+        let config_file = config_file();
+        assert!(
+            resolve(
+                &["suc", "run-timed", "--check-deadline", "30"],
+                Some(&config_file)
+            )
+            .is_err()
+        );
+        assert!(
+            resolve(
+                &["suc", "run-timed", "--check-deadline", "31"],
+                Some(&config_file)
+            )
+            .is_ok()
+        );
+        // without request timeout any deadline can be used
+        assert!(resolve(&["suc", "run-timed", "--check-deadline", "1"], None).is_ok());
+        // the deadline is only used by run-timed
+        assert!(resolve(&["suc", "--request-timeout", "900", "list-programs"], None).is_ok());
+    }
+
+    #[test]
+    fn test_settings_from_config() {
+        let mut cli = Cli::parse_from(["suc", "run-timed"]);
+        let mut config_file = config_file();
+        config_file.github_access_token = Some(SecretRef::from("config_token"));
+        let settings = Settings::resolve_with_config(&mut cli, Some(&config_file)).unwrap();
+        assert_eq!("config.db", settings.db_config.db_path);
+        assert_eq!(Some(Duration::from_secs(30)), settings.request_timeout);
+        assert_eq!(
+            Some("config_token"),
+            settings
+                .auth
+                .github
+                .access_token
+                .as_ref()
+                .map(Secret::expose)
+        );
+        let run_timed_args = run_timed_args(&cli);
+        assert_eq!(Some("config_topic".to_string()), run_timed_args.ntfy_topic);
+        assert_eq!("https://ntfy.example.com", run_timed_args.ntfy_server());
+        assert_eq!(7200, run_timed_args.check_interval());
+    }
+
+    #[tokio::test]
+    async fn test_settings_config_token_is_sent() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/repos/LMH01/alpha_tui/releases/latest")
+            .match_header("Authorization", "Bearer config_token")
+            .with_status(200)
+            .with_body(r#"{ "tag_name": "v1.8.0", "assets": [] }"#)
+            .create_async()
+            .await;
+        let mut cli = Cli::parse_from(["suc", "check"]);
+        let mut config_file = config_file();
+        config_file.github_access_token = Some(SecretRef::from("config_token"));
+        let mut settings = Settings::resolve_with_config(&mut cli, Some(&config_file)).unwrap();
+        settings.auth.github.api_url = server.url();
+        let res = Provider::Github(GithubProvider::new("LMH01/alpha_tui"))
+            .check_for_latest_version(&settings.client(), &settings.auth)
+            .await;
+        mock.assert_async().await;
+        assert_eq!("v1.8.0", res.unwrap().version);
+    }
+
+    #[test]
+    fn test_settings_notifications_section() {
+        let path = std::env::temp_dir().join(format!(
+            "simple_update_checker_topic_{}",
+            std::process::id()
+        ));
+        std::fs::write(&path, "secret_topic\n").unwrap();
+        let mut config_file = config_file();
+        config_file.notifications.ntfy = Some(NtfyConfig {
+            topic: Some(SecretRef::from(format!("file:{}", path.display()))),
+            server: None,
+            email: Some(SecretRef::from("user@example.com")),
+        });
+        let mut cli = Cli::parse_from(["suc", "run-timed"]);
+        Settings::resolve_with_config(&mut cli, Some(&config_file)).unwrap();
+        let run_timed_args = run_timed_args(&cli);
+        // the section takes precedence over the top level values
+        assert_eq!(Some("secret_topic".to_string()), run_timed_args.ntfy_topic);
+        assert_eq!("https://ntfy.example.com", run_timed_args.ntfy_server());
+        assert_eq!(
+            Some("user@example.com".to_string()),
+            run_timed_args.ntfy_email
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_settings_without_config() {
+        let mut cli = Cli::parse_from(["suc", "run-timed"]);
+        let settings = Settings::resolve_with_config(&mut cli, None).unwrap();
+        assert_eq!("programs.db", settings.db_config.db_path);
+        assert_eq!(None, settings.request_timeout);
+        let run_timed_args = run_timed_args(&cli);
+        assert_eq!(None, run_timed_args.ntfy_topic);
+        assert_eq!("https://ntfy.sh", run_timed_args.ntfy_server());
+        assert_eq!(3600, run_timed_args.check_interval());
+    }
+
+    #[test]
+    fn test_settings_github_access_token_file() {
+        let path = std::env::temp_dir().join(format!(
+            "simple_update_checker_token_{}",
+            std::process::id()
+        ));
+        std::fs::write(&path, "file_token\n").unwrap();
+        let mut config_file = config_file();
+        config_file.github_access_token_file = Some(path.clone());
+        let mut cli = Cli::parse_from(["suc", "check"]);
+        let settings = Settings::resolve_with_config(&mut cli, Some(&config_file)).unwrap();
+        assert_eq!(
+            Some("file_token"),
+            settings
+                .auth
+                .github
+                .access_token
+                .as_ref()
+                .map(Secret::expose)
+        );
+        // the token in the config file takes precedence over the token file
+        config_file.github_access_token = Some(SecretRef::from("config_token"));
+        let mut cli = Cli::parse_from(["suc", "check"]);
+        let settings = Settings::resolve_with_config(&mut cli, Some(&config_file)).unwrap();
+        assert_eq!(
+            Some("config_token"),
+            settings
+                .auth
+                .github
+                .access_token
+                .as_ref()
+                .map(Secret::expose)
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_db_config_resolve_cli_takes_precedence() {
+        let db_args = DbArgs {
+            db_path: Some("cli.db".to_string()),
+            profile: Some("work".to_string()),
+            read_only: true,
+        };
+        let db_config = DbConfig::resolve(db_args, Some(&config_file())).unwrap();
+        assert_eq!("cli.db", db_config.db_path);
+        assert!(db_config.read_only);
+    }
+
+    #[test]
+    fn test_db_config_resolve_profile_from_config() {
+        let db_args = DbArgs {
+            db_path: None,
+            profile: Some("work".to_string()),
+            read_only: false,
+        };
+        let db_config = DbConfig::resolve(db_args, Some(&config_file())).unwrap();
+        assert_eq!("work_profile.db", db_config.db_path);
+    }
+
+    #[test]
+    fn test_db_config_resolve_profile_default_path() {
+        let db_args = DbArgs {
+            db_path: None,
+            profile: Some("home".to_string()),
+            read_only: false,
+        };
+        let db_config = DbConfig::resolve(db_args, Some(&config_file())).unwrap();
+        assert!(db_config.db_path.ends_with("simple_update_checker/home.db"));
+    }
+
+    #[test]
+    fn test_db_config_resolve_without_profile() {
+        let db_args = DbArgs {
+            db_path: None,
+            profile: None,
+            read_only: false,
+        };
+        let db_config = DbConfig::resolve(db_args.clone(), Some(&config_file())).unwrap();
+        assert_eq!("config.db", db_config.db_path);
+        let db_config = DbConfig::resolve(db_args, None).unwrap();
+        assert_eq!("programs.db", db_config.db_path);
+    }
+
+    #[test]
+    fn test_serde_provider() {
+        let provider = Provider::Github(GithubProvider::new("owner/repo"));
+        let json = serde_json::to_value(&provider).unwrap();
+        assert_eq!(
+            serde_json::json!({
+                "type": "github",
+                "repository": "owner/repo",
+                "required_asset_pattern": null,
+                "access_token_env": null,
+                "release_selector": "latest",
+            }),
+            json
+        );
+        assert_eq!(provider, serde_json::from_value(json).unwrap());
+        let provider: Provider = serde_json::from_str(
+            r#"{ "type": "github", "repository": "owner/repo", "release_selector": "newest-stable" }"#,
+        )
+        .unwrap();
+        let Provider::Github(github) = &provider else {
+            panic!("expected github provider");
+        };
+        assert_eq!(ReleaseSelector::NewestStable, github.release_selector);
+        let providers = [
+            Provider::GithubCommit(GithubCommitProvider {
+                repository: "owner/repo".to_string(),
+                branch: Some("main".to_string()),
+            }),
+            Provider::Homebrew(HomebrewProvider {
+                package: "firefox".to_string(),
+                kind: HomebrewKind::Cask,
+            }),
+            Provider::FDroid(FDroidProvider {
+                package: "org.fdroid.fdroid".to_string(),
+            }),
+            Provider::Command(CommandProvider {
+                command: "kubectl version --client".to_string(),
+                regex: r"v(\S+)".to_string(),
+            }),
+        ];
+        for provider in providers {
+            let json = serde_json::to_value(&provider).unwrap();
+            assert_eq!(provider.identifier(), json["type"]);
+            assert_eq!(provider, serde_json::from_value(json).unwrap());
+        }
+        assert!(serde_json::from_str::<Provider>(r#"{ "type": "gitlab" }"#).is_err());
+    }
+
+    #[test]
+    fn test_serde_program() {
+        let date =
+            NaiveDateTime::parse_from_str("2025-03-12 13:45:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let mut program = Program::new(
+            "simple_update_checker",
+            "v0.1.0",
+            Provider::Github(GithubProvider::new("LMH01/simple_update_checker")),
+        );
+        program.current_version_last_updated = date;
+        program.latest_version_last_updated = date;
+        program.version_scheme = VersionScheme::Semver;
+        program.version_constraint = Some(VersionConstraint::from_str("^0.1").unwrap());
+        program.last_checked = Some(date);
+        let json = serde_json::to_value(&program).unwrap();
+        assert_eq!(
+            "2025-03-12T13:45:00+00:00",
+            json["current_version_last_updated"]
+        );
+        assert_eq!("2025-03-12T13:45:00+00:00", json["last_checked"]);
+        assert_eq!(serde_json::Value::Null, json["latest_version_published_at"]);
+        assert_eq!("semver", json["version_scheme"]);
+        assert_eq!("^0.1", json["version_constraint"]);
+        assert_eq!("github", json["provider"]["type"]);
+        assert_eq!(program, serde_json::from_value(json).unwrap());
+
+        // dates with offsets are converted to utc and optional fields can be omitted
+        let program: Program = serde_json::from_str(
+            r#"{
+                "name": "ripgrep",
+                "current_version": "14.1.0",
+                "current_version_last_updated": "2025-03-12T15:45:00+02:00",
+                "latest_version": "14.1.1",
+                "latest_version_last_updated": "2025-03-12T13:45:00Z",
+                "provider": { "type": "homebrew", "package": "ripgrep", "kind": "formula" }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(date, program.current_version_last_updated);
+        assert_eq!(date, program.latest_version_last_updated);
+        assert_eq!(VersionScheme::None, program.version_scheme);
+        assert_eq!(None, program.last_checked);
+        assert!(
+            serde_json::from_value::<Program>(serde_json::json!({
+                "name": "ripgrep",
+                "current_version": "14.1.0",
+                "current_version_last_updated": "2025-03-12 13:45:00",
+                "latest_version": "14.1.1",
+                "latest_version_last_updated": "2025-03-12T13:45:00Z",
+                "provider": { "type": "fdroid", "package": "org.fdroid.fdroid" }
+            }))
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_serde_history_entries() {
+        let date =
+            NaiveDateTime::parse_from_str("2025-03-12 13:45:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let update_check = UpdateCheckHistoryEntry {
+            date,
+            r#type: UpdateCheckType::Triggered,
+            updates_available: 1,
+            programs: "ripgrep (14.1.1)".to_string(),
+            duration_ms: Some(1200),
+            errors: Some(0),
+            shard: Some(Shard::from_str("2/4").unwrap()),
+            truncated: false,
+        };
+        let json = serde_json::to_value(&update_check).unwrap();
+        assert_eq!(
+            serde_json::json!({
+                "date": "2025-03-12T13:45:00+00:00",
+                "type": "triggered",
+                "updates_available": 1,
+                "programs": "ripgrep (14.1.1)",
+                "duration_ms": 1200,
+                "errors": 0,
+                "shard": "2/4",
+                "truncated": false,
+            }),
+            json
+        );
+        assert_eq!(update_check, serde_json::from_value(json).unwrap());
+        assert!(
+            serde_json::from_str::<UpdateCheckHistoryEntry>(
+                r#"{ "date": "2025-03-12T13:45:00Z", "type": "timed", "updates_available": 0, "programs": "", "shard": "5/4" }"#
+            )
+            .is_err()
+        );
+
+        let update = UpdateHistoryEntry {
+            date,
+            name: "ripgrep".to_string(),
+            old_version: "14.1.0".to_string(),
+            updated_to: "14.1.1".to_string(),
+        };
+        let json = serde_json::to_value(&update).unwrap();
+        assert_eq!(
+            serde_json::json!({
+                "date": "2025-03-12T13:45:00+00:00",
+                "name": "ripgrep",
+                "old_version": "14.1.0",
+                "updated_to": "14.1.1",
+            }),
+            json
+        );
+        assert_eq!(update, serde_json::from_value(json).unwrap());
+    }
+
+    #[test]
+    fn test_program_builder() {
+        let provider = Provider::Github(GithubProvider::new("LMH01/alpha_tui"));
+        let date =
+            NaiveDateTime::parse_from_str("2025-03-12 13:45:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let builder = Program::builder()
+            .name("alpha_tui")
+            .provider(provider.clone())
+            .current_version("v1.8.0");
+        let program = builder
+            .clone()
+            .current_version_last_updated(date)
+            .pin_major(Some(1))
+            .build()
+            .unwrap();
+        assert_eq!("v1.8.0", program.latest_version());
+        assert_eq!(date, program.current_version_last_updated());
+        assert!(program.latest_version_last_updated() > date);
+        assert_eq!(Some(1), program.pin_major());
+        assert_eq!(&provider, program.provider());
+
+        assert!(builder.clone().name("").build().is_err());
+        assert!(builder.clone().name("alpha,tui").build().is_err());
+        assert!(builder.clone().latest_version(" ").build().is_err());
+        assert!(builder.clone().current_version("v1.8.0\n").build().is_err());
+        assert!(
+            builder
+                .clone()
+                .older_upstream_version(Some(""))
+                .build()
+                .is_err()
+        );
+        assert!(
+            Program::builder()
+                .name("alpha_tui")
+                .current_version("v1.8.0")
+                .build()
+                .is_err()
+        );
+        assert!(
+            Program::builder()
+                .name("alpha_tui")
+                .provider(provider)
+                .build()
+                .is_err()
+        );
+    }
+}
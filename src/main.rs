@@ -1,8 +1,11 @@
-use std::env;
+use std::{env, process};
 
-use clap::Parser;
+use anyhow::Result;
 use simple_update_checker::{
-    actions::{self, add_program, run_timed}, cli::{Cli, Command, UpdateProviderAdd}, config::ConfigFile, DbConfig
+    Secret, Settings,
+    actions::{self, ActionOutcome, add_program, config, run_timed},
+    cli::{Cli, Command, ConfigCommand},
+    credentials,
 };
 use tracing::Level;
 
@@ -14,46 +17,128 @@ async fn main() {
     // setup logging
     init_logger();
 
-    let mut cli = Cli::parse();
+    let mut cli = Cli::parse_env();
 
-    // apply values from config file to cli, when cli does not yet contain values defined in config file
-    match ConfigFile::try_parse() {
-        Ok(Some(config_file)) => cli.apply_config_file(config_file),
-        _ => (),
-    };
-
-    let db_config = DbConfig::try_create(cli.db_args).unwrap();
+    // the token in the keyring is used when no token is provided by the cli or the environment
+    if cli.github_access_token.is_none() {
+        match credentials::load_github_access_token().await {
+            Ok(token) => cli.github_access_token = token.map(Secret::from),
+            Err(e) => tracing::warn!("Unable to load github access token from keyring: {e}"),
+        }
+    }
 
-    match cli.command {
-        Command::AddProgram(add_program_args) => match &add_program_args.provider {
-            UpdateProviderAdd::Github(add_github_program_args) => {
-                add_program::add_program_github(
-                    db_config,
-                    &add_program_args,
-                    add_github_program_args,
-                    cli.github_access_token,
-                )
-                .await;
+    // config has to work with an invalid config file, so the settings are not resolved beforehand
+    if let Command::Config(config_args) = &cli.command {
+        let outcome = match config_args.command.clone() {
+            ConfigCommand::Validate(validate_args) => {
+                config::validate(cli.config.as_deref(), &validate_args).await
             }
-        },
+            ConfigCommand::Show => config::show(cli).await,
+            ConfigCommand::Init(init_args) => config::init(cli.config.as_deref(), &init_args),
+        };
+        exit(outcome);
+    }
+
+    // only the summary should be printed when --summary-only is set
+    let print_messages = !matches!(&cli.command, Command::Check(check_args) if check_args.summary_only || check_args.output_template.is_some())
+        // resolve and self-version-check do not use the database
+        && !matches!(&cli.command, Command::Resolve(_) | Command::SelfVersionCheck);
+    // values from the config file are applied to the cli, when the cli does not yet contain them
+    let settings = match Settings::resolve(&mut cli, print_messages) {
+        Ok(settings) => settings,
+        Err(e) => {
+            println!("{e:#}");
+            process::exit(1);
+        }
+    };
+
+    let outcome = match cli.command {
+        Command::AddProgram(add_program_args) => {
+            add_program::add_program(settings.db_config, &add_program_args, settings.auth).await
+        }
+        Command::Resolve(resolve_args) => actions::resolve(&resolve_args, settings.auth).await,
         Command::RemoveProgram(remove_program_args) => {
-            actions::remove_program(db_config, remove_program_args).await;
+            actions::remove_program(settings.db_config, remove_program_args, cli.assume_yes).await
         }
-        Command::ListPrograms => actions::list_programs(db_config).await,
-        Command::Check(check_args) => {
-            actions::check(db_config, check_args, cli.github_access_token).await;
+        Command::CloneProgram(clone_program_args) => {
+            actions::clone_program(settings.db_config, clone_program_args).await
         }
-        Command::Update(update_args) => actions::update(db_config, update_args).await,
+        Command::ListPrograms(list_programs_args) => {
+            actions::list_programs(settings.db_config, list_programs_args, settings.table_style)
+                .await
+        }
+        Command::ShowProgram(show_program_args) => {
+            actions::show_program(settings.db_config, show_program_args).await
+        }
+        Command::Open(open_args) => actions::open(settings.db_config, open_args).await,
+        Command::Check(check_args) => actions::check(settings, check_args).await,
+        Command::RefreshLatest(refresh_latest_args) => {
+            actions::refresh_latest(settings.db_config, refresh_latest_args, settings.auth).await
+        }
+        Command::Update(update_args) => actions::update(settings.db_config, update_args).await,
+        Command::VerifyDb(verify_db_args) => {
+            actions::verify_db(settings.db_config, verify_db_args).await
+        }
+        Command::RateLimit => {
+            actions::rate_limit(
+                settings.db_config,
+                settings.auth.github,
+                settings.table_style,
+            )
+            .await
+        }
+        Command::SetToken(set_token_args) => actions::set_token(set_token_args).await,
+        Command::ClearToken => actions::clear_token().await,
         Command::UpdateHistory(update_history_args) => {
-            actions::update_history(db_config, update_history_args).await;
+            actions::update_history(
+                settings.db_config,
+                update_history_args,
+                settings.table_style,
+            )
+            .await
+        }
+        Command::VersionHistory(version_history_args) => {
+            actions::version_history(
+                settings.db_config,
+                version_history_args,
+                settings.table_style,
+            )
+            .await
         }
         Command::UpdateCheckHistory(update_check_history_args) => {
-            actions::update_check_history(db_config, update_check_history_args).await;
+            actions::update_check_history(
+                settings.db_config,
+                update_check_history_args,
+                settings.table_style,
+            )
+            .await
         }
+        // run-timed logs instead of printing
         Command::RunTimed(run_timed_args) => {
-            run_timed::run(db_config, run_timed_args, cli.github_access_token).await;
+            run_timed::run(settings, run_timed_args).await.or_else(|e| {
+                tracing::error!("{e:#}");
+                Ok(ActionOutcome::Failed)
+            })
         }
+        Command::SelfVersionCheck => actions::self_version_check(settings.auth).await,
+        Command::SyncConfig => config::sync_config(settings, cli.assume_yes).await,
+        // handled before the settings are resolved
+        Command::Config(_) => unreachable!(),
+    };
+    exit(outcome);
+}
+
+/// Prints the message of the outcome of an action, or the error when the action failed, and exits with the exit
+/// code of the outcome.
+fn exit(outcome: Result<ActionOutcome>) -> ! {
+    let outcome = outcome.unwrap_or_else(|e| {
+        println!("{e:#}");
+        ActionOutcome::Failed
+    });
+    if let Some(message) = outcome.message() {
+        println!("{message}");
     }
+    process::exit(outcome.exit_code());
 }
 
 fn init_logger() {
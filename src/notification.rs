@@ -1,23 +1,302 @@
+use std::time::Duration;
+
 use anyhow::Result;
-use reqwest::{Client, Method};
+use reqwest::{Client, Method, Response, StatusCode, header::RETRY_AFTER};
+use tokio::{sync::Mutex, time::Instant};
+
+/// Server to which the notifications are sent, when no other server is configured.
+pub const NTFY_SERVER: &str = "https://ntfy.sh";
+
+/// Number of times a notification is sent again when the ntfy server responds that the rate limit is exceeded.
+const RATE_LIMIT_RETRIES: u32 = 3;
+/// Time after which a notification is sent again when the rate limit is exceeded and the server does not send a
+/// `Retry-After` header.
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(10);
+/// Longest time that is waited before a notification is sent again.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(5 * 60);
 
-pub async fn send_update_notification(topic: &str, message: &str) -> Result<()> {
-    send_notification(topic, message, "Updates available", "arrow_up").await
+/// Topic of a ntfy server to which notifications are sent.
+///
+/// Consecutive notifications are sent at least `throttle` apart, to not exceed the rate limit of the ntfy server.
+pub struct Ntfy {
+    server: String,
+    topic: String,
+    /// If set, the ntfy server also forwards the notifications to this email address.
+    email: Option<String>,
+    throttle: Duration,
+    /// Time at which the last notification was sent, held while a notification is sent.
+    last_sent: Mutex<Option<Instant>>,
 }
 
-pub async fn send_error_notifictaion(topic: &str, message: &str) -> Result<()> {
-    send_notification(topic, message, "Error while checking for updates", "x").await
+impl Ntfy {
+    #[must_use]
+    pub fn new(server: &str, topic: &str, email: Option<&str>, throttle: Duration) -> Self {
+        Self {
+            server: server.to_string(),
+            topic: topic.to_string(),
+            email: email.map(str::to_string),
+            throttle,
+            last_sent: Mutex::new(None),
+        }
+    }
+
+    #[must_use]
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    pub async fn send_update_notification(&self, client: &Client, message: &str) -> Result<()> {
+        self.send(client, message, "Updates available", "arrow_up")
+            .await
+    }
+
+    pub async fn send_error_notification(&self, client: &Client, message: &str) -> Result<()> {
+        self.send(client, message, "Error while checking for updates", "x")
+            .await
+    }
+
+    /// Sends a notification, waits until `throttle` has passed since the last notification first.
+    async fn send(
+        &self,
+        client: &Client,
+        message: &str,
+        title: &str,
+        icon_str: &str,
+    ) -> Result<()> {
+        let mut last_sent = self.last_sent.lock().await;
+        if let Some(last_sent) = *last_sent {
+            tokio::time::sleep_until(last_sent + self.throttle).await;
+        }
+        let result = send_notification(
+            client,
+            &self.server,
+            &self.topic,
+            self.email.as_deref(),
+            message,
+            title,
+            icon_str,
+        )
+        .await;
+        *last_sent = Some(Instant::now());
+        result
+    }
 }
 
-/// Sends a notification the the ntfy.sh servers containing the message and using
+/// Sends a notification the the ntfy server containing the message and using
 /// the provided topic.
-async fn send_notification(topic: &str, message: &str, title: &str, icon_str: &str) -> Result<()> {
-    Client::new()
-        .request(Method::POST, format!("https://ntfy.sh/{topic}"))
-        .body(message.to_string())
-        .header("Title", title)
-        .header("Tags", icon_str)
-        .send()
-        .await?;
-    Ok(())
+///
+/// If `email` is set, the ntfy server also forwards the notification to this email address.
+///
+/// When the server responds that the rate limit is exceeded, the notification is sent again after the time requested
+/// in the `Retry-After` header, up to [`RATE_LIMIT_RETRIES`] times.
+///
+/// Returns an error if the server does not respond with a success status code.
+async fn send_notification(
+    client: &Client,
+    server: &str,
+    topic: &str,
+    email: Option<&str>,
+    message: &str,
+    title: &str,
+    icon_str: &str,
+) -> Result<()> {
+    let mut retries = 0;
+    loop {
+        let mut request = client
+            .request(Method::POST, format!("{server}/{topic}"))
+            .body(message.to_string())
+            .header("Title", title)
+            .header("Tags", icon_str);
+        if let Some(email) = email {
+            request = request.header("Email", email);
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        if status == StatusCode::TOO_MANY_REQUESTS && retries < RATE_LIMIT_RETRIES {
+            let retry_after = retry_after(&response);
+            tracing::warn!(
+                "ntfy server rate limit exceeded, sending notification again in {} seconds",
+                retry_after.as_secs()
+            );
+            tokio::time::sleep(retry_after).await;
+            retries += 1;
+            continue;
+        }
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("ntfy server responded with status {status}: {body}");
+        }
+        return Ok(());
+    }
+}
+
+/// Returns the time after which a request should be sent again, as requested by the `Retry-After` header.
+///
+/// Only values in seconds are supported, [`DEFAULT_RETRY_AFTER`] is used for other values.
+fn retry_after(response: &Response) -> Duration {
+    response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+        .map_or(DEFAULT_RETRY_AFTER, Duration::from_secs)
+        .min(MAX_RETRY_AFTER)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{Ntfy, send_notification};
+    use crate::http::{self, USER_AGENT};
+
+    #[tokio::test]
+    async fn test_send_notification() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/topic")
+            .match_header("Title", "Updates available")
+            .match_header("User-Agent", USER_AGENT)
+            .match_body("alpha_tui: v1.7.0 -> v1.8.0")
+            .with_status(200)
+            .create_async()
+            .await;
+        let res = send_notification(
+            &http::client(),
+            &server.url(),
+            "topic",
+            None,
+            "alpha_tui: v1.7.0 -> v1.8.0",
+            "Updates available",
+            "arrow_up",
+        )
+        .await;
+        mock.assert_async().await;
+        assert!(res.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_send_notification_forbidden() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/topic")
+            .with_status(403)
+            .with_body("forbidden")
+            .create_async()
+            .await;
+        let res = send_notification(
+            &http::client(),
+            &server.url(),
+            "topic",
+            None,
+            "message",
+            "Updates available",
+            "arrow_up",
+        )
+        .await;
+        mock.assert_async().await;
+        let e = res.unwrap_err().to_string();
+        assert!(e.contains("403"));
+        assert!(e.contains("forbidden"));
+    }
+
+    #[tokio::test]
+    async fn test_send_notification_email() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/topic")
+            .match_header("Email", "user@example.com")
+            .with_status(200)
+            .create_async()
+            .await;
+        let res = send_notification(
+            &http::client(),
+            &server.url(),
+            "topic",
+            Some("user@example.com"),
+            "alpha_tui: v1.7.0 -> v1.8.0",
+            "Updates available",
+            "arrow_up",
+        )
+        .await;
+        mock.assert_async().await;
+        assert!(res.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_send_notification_rate_limited() {
+        let mut server = mockito::Server::new_async().await;
+        let rate_limited = server
+            .mock("POST", "/topic")
+            .with_status(429)
+            .with_header("Retry-After", "0")
+            .expect(1)
+            .create_async()
+            .await;
+        let sent = server
+            .mock("POST", "/topic")
+            .with_status(200)
+            .expect(1)
+            .create_async()
+            .await;
+        let res = send_notification(
+            &http::client(),
+            &server.url(),
+            "topic",
+            None,
+            "message",
+            "Updates available",
+            "arrow_up",
+        )
+        .await;
+        rate_limited.assert_async().await;
+        sent.assert_async().await;
+        assert!(res.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_send_notification_rate_limit_retries_exceeded() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/topic")
+            .with_status(429)
+            .with_header("Retry-After", "0")
+            .expect(4)
+            .create_async()
+            .await;
+        let res = send_notification(
+            &http::client(),
+            &server.url(),
+            "topic",
+            None,
+            "message",
+            "Updates available",
+            "arrow_up",
+        )
+        .await;
+        mock.assert_async().await;
+        assert!(res.unwrap_err().to_string().contains("429"));
+    }
+
+    #[tokio::test]
+    async fn test_ntfy_throttle() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/topic")
+            .with_status(200)
+            .expect(2)
+            .create_async()
+            .await;
+        let ntfy = Ntfy::new(&server.url(), "topic", None, Duration::from_millis(300));
+        let client = http::client();
+        let start = Instant::now();
+        ntfy.send_update_notification(&client, "alpha_tui: v1.7.0 -> v1.8.0")
+            .await
+            .unwrap();
+        ntfy.send_error_notification(&client, "error")
+            .await
+            .unwrap();
+        mock.assert_async().await;
+        assert!(start.elapsed() >= Duration::from_millis(300));
+    }
 }
@@ -0,0 +1,170 @@
+//! Extension point for the providers from which the latest version of a program is requested.
+//!
+//! The built-in providers are the variants of [`Provider`]. Library users can add their own providers by implementing
+//! [`UpdateProvider`] and registering a factory for it with [`Db::register_provider`](crate::db::Db::register_provider).
+//! The settings of such providers are stored with [`UpdateProvider::serialize_config`] and restored with the factory
+//! when the program is read from the database.
+
+use std::{collections::HashMap, fmt, pin::Pin, sync::Arc};
+
+use anyhow::Result;
+use reqwest::Client;
+
+use crate::{
+    GithubApiConfig, Provider, ProviderApiConfig, ProviderError,
+    update_check::{LatestVersion, Release, VersionList},
+};
+
+/// Identifiers of the built-in providers, these can not be registered again.
+pub const BUILT_IN_PROVIDERS: [&str; 5] =
+    ["github", "githubcommit", "homebrew", "fdroid", "command"];
+
+/// Settings and credentials that providers use when they request the latest version of a program.
+#[derive(Debug, Clone, Default)]
+pub struct AuthContext {
+    /// Settings and access tokens of the github api, used by the github providers.
+    pub github: GithubApiConfig,
+    /// Base urls of the apis of the other built-in providers.
+    pub apis: ProviderApiConfig,
+}
+
+impl From<GithubApiConfig> for AuthContext {
+    fn from(github: GithubApiConfig) -> Self {
+        Self {
+            github,
+            apis: ProviderApiConfig::default(),
+        }
+    }
+}
+
+/// Future that is returned by [`UpdateProvider::check_for_latest_version`].
+pub type LatestVersionFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<LatestVersion, ProviderError>> + Send + 'a>>;
+
+/// Future that is returned by [`UpdateProvider::list_versions`].
+pub type VersionListFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<VersionList, ProviderError>> + Send + 'a>>;
+
+/// Creates a provider from the settings that where returned by [`UpdateProvider::serialize_config`].
+pub type ProviderFactory = Box<dyn Fn(&str) -> Result<Box<dyn UpdateProvider>> + Send + Sync>;
+
+/// A source from which the latest version of a program can be requested.
+pub trait UpdateProvider: fmt::Debug + Send + Sync {
+    /// Identifier under which the programs of this provider are stored, e.g. `github`.
+    fn identifier(&self) -> String;
+
+    /// Checks what the latest version of the program using this provider is.
+    fn check_for_latest_version<'a>(
+        &'a self,
+        client: &'a Client,
+        auth: &'a AuthContext,
+    ) -> LatestVersionFuture<'a>;
+
+    /// Serializes the settings of this provider, e.g. the repository.
+    fn serialize_config(&self) -> String;
+
+    /// Returns the url of the web page of the release with the version `version`.
+    ///
+    /// `None` if the provider has no web page.
+    fn web_url(&self, version: &str) -> Option<String>;
+
+    /// Lists all stable versions of the program, newest first.
+    ///
+    /// Used when the major version is pinned or a version constraint is set. By default only the latest version is
+    /// listed.
+    fn list_versions<'a>(
+        &'a self,
+        client: &'a Client,
+        auth: &'a AuthContext,
+    ) -> VersionListFuture<'a> {
+        Box::pin(async move {
+            let latest = self.check_for_latest_version(client, auth).await?;
+            Ok(VersionList {
+                releases: vec![Release {
+                    version: latest.version,
+                    published_at: latest.published_at,
+                }],
+                renamed_repository: latest.renamed_repository,
+            })
+        })
+    }
+
+    /// Repository of the program, if the provider reports when it is renamed, see
+    /// [`LatestVersion::renamed_repository`].
+    fn repository(&self) -> Option<&str> {
+        None
+    }
+
+    /// Returns this provider with the repository changed to `renamed_repository`.
+    ///
+    /// `None` if the provider has no repository that can be renamed.
+    fn follow_rename(&self, _renamed_repository: &str) -> Option<Provider> {
+        None
+    }
+}
+
+/// A provider that is not built-in, see [`ProviderRegistry`].
+#[derive(Debug, Clone)]
+pub struct CustomProvider(Arc<dyn UpdateProvider>);
+
+impl CustomProvider {
+    #[must_use]
+    pub fn new(provider: impl UpdateProvider + 'static) -> Self {
+        Self(Arc::new(provider))
+    }
+
+    #[must_use]
+    pub fn provider(&self) -> &dyn UpdateProvider {
+        self.0.as_ref()
+    }
+}
+
+impl From<Box<dyn UpdateProvider>> for CustomProvider {
+    fn from(value: Box<dyn UpdateProvider>) -> Self {
+        Self(Arc::from(value))
+    }
+}
+
+impl PartialEq for CustomProvider {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.identifier() == other.0.identifier()
+            && self.0.serialize_config() == other.0.serialize_config()
+    }
+}
+
+/// Factories of the providers that are not built-in, by their identifier.
+#[derive(Default)]
+pub struct ProviderRegistry {
+    factories: HashMap<String, ProviderFactory>,
+}
+
+impl ProviderRegistry {
+    /// Registers `factory` for the provider with the identifier `identifier`.
+    ///
+    /// Fails if the identifier is already used by a built-in or a registered provider.
+    pub fn register<F>(&mut self, identifier: &str, factory: F) -> Result<()>
+    where
+        F: Fn(&str) -> Result<Box<dyn UpdateProvider>> + Send + Sync + 'static,
+    {
+        if BUILT_IN_PROVIDERS.contains(&identifier) || self.factories.contains_key(identifier) {
+            anyhow::bail!("Provider {identifier} is already registered");
+        }
+        self.factories
+            .insert(identifier.to_string(), Box::new(factory));
+        Ok(())
+    }
+
+    /// Creates the provider with the identifier `identifier` from its serialized settings.
+    ///
+    /// Returns `None` if no provider is registered for `identifier`.
+    pub fn create(&self, identifier: &str, config: &str) -> Option<Result<Provider>> {
+        let factory = self.factories.get(identifier)?;
+        Some(factory(config).map(|provider| Provider::Custom(CustomProvider::from(provider))))
+    }
+
+    /// Checks if a provider is registered for `identifier`.
+    #[must_use]
+    pub fn contains(&self, identifier: &str) -> bool {
+        self.factories.contains_key(identifier)
+    }
+}
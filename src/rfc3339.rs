@@ -0,0 +1,50 @@
+//! Serde helpers that (de)serialize `NaiveDateTime` values, which are stored in UTC, as RFC 3339 strings
+//! like `2025-03-12T13:45:00+00:00`.
+//!
+//! Use with `#[serde(with = "rfc3339")]` or `#[serde(with = "rfc3339::option")]`.
+
+use serde::{Deserialize, Deserializer, Serializer, de::Error};
+use sqlx::types::chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+
+pub fn serialize<S: Serializer>(value: &NaiveDateTime, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format(value))
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDateTime, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    parse(&value).map_err(D::Error::custom)
+}
+
+/// Same as the parent module, but for optional values. `None` is (de)serialized as `null`.
+pub mod option {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+    use sqlx::types::chrono::NaiveDateTime;
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<NaiveDateTime>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.serialize_some(&super::format(value)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<NaiveDateTime>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|value| super::parse(&value).map_err(D::Error::custom))
+            .transpose()
+    }
+}
+
+fn format(value: &NaiveDateTime) -> String {
+    Utc.from_utc_datetime(value).to_rfc3339()
+}
+
+fn parse(value: &str) -> Result<NaiveDateTime, String> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|date| date.naive_utc())
+        .map_err(|e| format!("invalid RFC 3339 date {value}: {e}"))
+}
@@ -1,133 +1,3500 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::ControlFlow,
+    sync::Arc,
+    sync::atomic::Ordering,
+    time::{Duration, Instant},
+};
+
 use anyhow::Result;
-use reqwest::Client;
+use globset::{Glob, GlobMatcher};
+use regex::Regex;
+use reqwest::{Client, Response, StatusCode};
+use semver::Version;
+use serde::Serialize;
 use serde_json::Value;
-use sqlx::types::chrono::Utc;
+use sqlx::types::chrono::{DateTime, NaiveDateTime, Utc};
+use tabled::Tabled;
+use tokio::{sync::Semaphore, task::JoinSet};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    CommandProvider, FDroidProvider, GithubApiConfig, GithubCommitProvider, GithubProvider,
+    HomebrewKind, HomebrewProvider, Identifier, Program, Provider, ProviderApiConfig,
+    ProviderError, ReleaseSelector, Secret, UpdateCheckHistoryEntry, UpdateCheckType,
+    VersionConstraint, VersionHistoryEntry,
+    cli::CheckArgs,
+    db::{ProgramChanges, ProgramStore},
+    provider::{AuthContext, LatestVersionFuture, UpdateProvider, VersionListFuture},
+    version::parse_semver,
+};
+
+/// Number of characters of a commit hash that are used as version.
+const SHORT_SHA_LENGTH: usize = 7;
+/// Time after which a command that should print the latest version is killed.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(60);
+
+impl UpdateProvider for GithubProvider {
+    fn identifier(&self) -> String {
+        "github".to_string()
+    }
+
+    fn check_for_latest_version<'a>(
+        &'a self,
+        client: &'a Client,
+        auth: &'a AuthContext,
+    ) -> LatestVersionFuture<'a> {
+        Box::pin(github_latest_version(client, self, &auth.github))
+    }
+
+    fn serialize_config(&self) -> String {
+        serialize_config(self)
+    }
+
+    fn web_url(&self, version: &str) -> Option<String> {
+        Some(format!(
+            "https://github.com/{}/releases/tag/{version}",
+            self.repository
+        ))
+    }
+
+    fn list_versions<'a>(
+        &'a self,
+        client: &'a Client,
+        auth: &'a AuthContext,
+    ) -> VersionListFuture<'a> {
+        Box::pin(github_versions(client, self, &auth.github))
+    }
+
+    fn repository(&self) -> Option<&str> {
+        Some(&self.repository)
+    }
+
+    fn follow_rename(&self, renamed_repository: &str) -> Option<Provider> {
+        Some(Provider::Github(GithubProvider {
+            repository: renamed_repository.to_string(),
+            ..self.clone()
+        }))
+    }
+}
+
+impl UpdateProvider for GithubCommitProvider {
+    fn identifier(&self) -> String {
+        "githubcommit".to_string()
+    }
+
+    fn check_for_latest_version<'a>(
+        &'a self,
+        client: &'a Client,
+        auth: &'a AuthContext,
+    ) -> LatestVersionFuture<'a> {
+        Box::pin(github_commit_latest_version(client, self, &auth.github))
+    }
+
+    fn serialize_config(&self) -> String {
+        serialize_config(self)
+    }
+
+    fn web_url(&self, version: &str) -> Option<String> {
+        Some(format!(
+            "https://github.com/{}/commit/{version}",
+            self.repository
+        ))
+    }
+
+    // commits have no versions
+    fn list_versions<'a>(
+        &'a self,
+        _client: &'a Client,
+        _auth: &'a AuthContext,
+    ) -> VersionListFuture<'a> {
+        Box::pin(async {
+            Ok(VersionList {
+                releases: Vec::new(),
+                renamed_repository: None,
+            })
+        })
+    }
+
+    fn repository(&self) -> Option<&str> {
+        Some(&self.repository)
+    }
+
+    fn follow_rename(&self, renamed_repository: &str) -> Option<Provider> {
+        Some(Provider::GithubCommit(GithubCommitProvider {
+            repository: renamed_repository.to_string(),
+            ..self.clone()
+        }))
+    }
+}
+
+impl UpdateProvider for HomebrewProvider {
+    fn identifier(&self) -> String {
+        "homebrew".to_string()
+    }
+
+    fn check_for_latest_version<'a>(
+        &'a self,
+        client: &'a Client,
+        auth: &'a AuthContext,
+    ) -> LatestVersionFuture<'a> {
+        Box::pin(homebrew_latest_version(client, self, &auth.apis))
+    }
+
+    fn serialize_config(&self) -> String {
+        serialize_config(self)
+    }
+
+    // homebrew has no page per version
+    fn web_url(&self, _version: &str) -> Option<String> {
+        Some(format!(
+            "https://formulae.brew.sh/{}/{}",
+            self.kind.identifier(),
+            self.package
+        ))
+    }
+}
+
+impl UpdateProvider for FDroidProvider {
+    fn identifier(&self) -> String {
+        "fdroid".to_string()
+    }
+
+    fn check_for_latest_version<'a>(
+        &'a self,
+        client: &'a Client,
+        auth: &'a AuthContext,
+    ) -> LatestVersionFuture<'a> {
+        Box::pin(fdroid_latest_version(client, self, &auth.apis))
+    }
+
+    fn serialize_config(&self) -> String {
+        serialize_config(self)
+    }
+
+    fn web_url(&self, _version: &str) -> Option<String> {
+        Some(format!("https://f-droid.org/packages/{}/", self.package))
+    }
+
+    fn list_versions<'a>(
+        &'a self,
+        client: &'a Client,
+        auth: &'a AuthContext,
+    ) -> VersionListFuture<'a> {
+        Box::pin(fdroid_version_list(client, self, &auth.apis))
+    }
+}
+
+impl UpdateProvider for CommandProvider {
+    fn identifier(&self) -> String {
+        "command".to_string()
+    }
+
+    fn check_for_latest_version<'a>(
+        &'a self,
+        _client: &'a Client,
+        _auth: &'a AuthContext,
+    ) -> LatestVersionFuture<'a> {
+        Box::pin(async move {
+            Ok(LatestVersion {
+                version: command_version(self).await?,
+                renamed_repository: None,
+                published_at: None,
+            })
+        })
+    }
+
+    fn serialize_config(&self) -> String {
+        serialize_config(self)
+    }
+
+    fn web_url(&self, _version: &str) -> Option<String> {
+        None
+    }
+}
+
+/// Lists the stable releases of `github`, newest first.
+async fn github_versions(
+    client: &Client,
+    github: &GithubProvider,
+    github_api_config: &GithubApiConfig,
+) -> Result<VersionList, ProviderError> {
+    let github_api_config = &github.api_config(github_api_config)?;
+    let matcher = match &github.required_asset_pattern {
+        Some(pattern) => Some(Glob::new(pattern)?.compile_matcher()),
+        None => None,
+    };
+    let url = format!(
+        "{}/repos/{}/releases?per_page=100",
+        github_api_config.api_url, github.repository
+    );
+    let include_prereleases = github.release_selector == ReleaseSelector::Newest;
+    let releases = filter_github_list(client, &url, github_api_config, |release| {
+        !release["draft"].as_bool().unwrap_or(false)
+            && (include_prereleases || !release["prerelease"].as_bool().unwrap_or(false))
+            && matcher
+                .as_ref()
+                .is_none_or(|matcher| release_has_matching_asset(release, matcher))
+    })
+    .await?;
+    let renamed_repository = releases
+        .first()
+        .and_then(|release| github_renamed_repository(&github.repository, release));
+    let releases = releases
+        .iter()
+        .map(|release| {
+            Ok(Release {
+                version: github_tag_name(release)?,
+                published_at: github_published_at(release),
+            })
+        })
+        .collect::<Result<Vec<Release>, ProviderError>>()?;
+    Ok(VersionList {
+        releases,
+        renamed_repository,
+    })
+}
+
+/// Serializes the settings of a built-in provider as json.
+fn serialize_config(provider: &impl Serialize) -> String {
+    serde_json::to_string(provider).expect("provider settings can always be serialized")
+}
+
+async fn github_latest_version(
+    client: &Client,
+    github: &GithubProvider,
+    github_api_config: &GithubApiConfig,
+) -> Result<LatestVersion, ProviderError> {
+    let github_api_config = &github.api_config(github_api_config)?;
+    if github.release_selector != ReleaseSelector::Latest {
+        return github_newest_release(client, github, github_api_config).await;
+    }
+    let repo = &github.repository;
+    let url = format!("{}/repos/{repo}/releases/latest", github_api_config.api_url);
+    let response = match github_get(client, &url, github_api_config).await {
+        Err(ProviderError::NotFound) => {
+            return Err(github_latest_release_not_found(client, repo, github_api_config).await);
+        }
+        response => response?,
+    };
+    let json: Value = response.json().await?;
+    let tag_name = github_tag_name(&json)?;
+    let renamed_repository = github_renamed_repository(&github.repository, &json);
+    let Some(pattern) = &github.required_asset_pattern else {
+        return Ok(LatestVersion {
+            version: tag_name,
+            renamed_repository,
+            published_at: github_published_at(&json),
+        });
+    };
+    let matcher = Glob::new(pattern)?.compile_matcher();
+    if release_has_matching_asset(&json, &matcher) {
+        return Ok(LatestVersion {
+            version: tag_name,
+            renamed_repository,
+            published_at: github_published_at(&json),
+        });
+    }
+    // the latest release does not yet contain the required asset, so we use the newest
+    // release that does. As releases are ordered newest first, we can stop at the first match.
+    let url = format!(
+        "{}/repos/{repo}/releases?per_page=100",
+        github_api_config.api_url
+    );
+    let release = find_in_github_list(client, &url, github_api_config, |release| {
+        !release["draft"].as_bool().unwrap_or(false)
+            && !release["prerelease"].as_bool().unwrap_or(false)
+            && release_has_matching_asset(release, &matcher)
+    })
+    .await?;
+    match release {
+        Some(release) => {
+            let fallback_tag_name = github_tag_name(&release)?;
+            tracing::info!(
+                "{repo}: release {tag_name} found but asset not yet available, using release {fallback_tag_name}"
+            );
+            Ok(LatestVersion {
+                version: fallback_tag_name,
+                renamed_repository,
+                published_at: github_published_at(&release),
+            })
+        }
+        None => Err(ProviderError::AssetNotAvailable {
+            tag: tag_name,
+            pattern: pattern.to_string(),
+        }),
+    }
+}
+
+async fn github_commit_latest_version(
+    client: &Client,
+    github_commit: &GithubCommitProvider,
+    github_api_config: &GithubApiConfig,
+) -> Result<LatestVersion, ProviderError> {
+    // HEAD refers to the latest commit of the default branch
+    let url = format!(
+        "{}/repos/{}/commits/{}",
+        github_api_config.api_url,
+        github_commit.repository,
+        github_commit.branch.as_deref().unwrap_or("HEAD")
+    );
+    let json: Value = github_get(client, &url, github_api_config)
+        .await?
+        .json()
+        .await?;
+    let Some(sha) = json["sha"].as_str() else {
+        return Err(ProviderError::Parse(
+            "Response was success but did not contain sha".to_string(),
+        ));
+    };
+    Ok(LatestVersion {
+        version: sha.chars().take(SHORT_SHA_LENGTH).collect(),
+        renamed_repository: github_renamed_repository(&github_commit.repository, &json),
+        published_at: parse_github_date(&json["commit"]["committer"]["date"]),
+    })
+}
+
+async fn homebrew_latest_version(
+    client: &Client,
+    homebrew: &HomebrewProvider,
+    apis: &ProviderApiConfig,
+) -> Result<LatestVersion, ProviderError> {
+    let url = format!(
+        "{}/{}/{}.json",
+        apis.homebrew_api_url,
+        homebrew.kind.identifier(),
+        homebrew.package
+    );
+    let response = client.get(&url).send().await?;
+    if response.status() == StatusCode::NOT_FOUND {
+        return Err(ProviderError::HomebrewPackageNotFound {
+            kind: homebrew.kind,
+            package: homebrew.package.clone(),
+        });
+    }
+    if !response.status().is_success() {
+        return Err(ProviderError::Http(response.status()));
+    }
+    let json: Value = response.json().await?;
+    // formulae can have multiple versions (e.g. head), casks only have a single version
+    let version = match homebrew.kind {
+        HomebrewKind::Formula => &json["versions"]["stable"],
+        HomebrewKind::Cask => &json["version"],
+    };
+    let Some(version) = version.as_str() else {
+        return Err(ProviderError::Parse(
+            "Response was success but did not contain version".to_string(),
+        ));
+    };
+    Ok(LatestVersion {
+        version: version.to_string(),
+        renamed_repository: None,
+        published_at: None,
+    })
+}
 
-use crate::{Program, Provider, UpdateCheckHistoryEntry, UpdateCheckType, cli::CheckArgs, db::Db};
+async fn fdroid_latest_version(
+    client: &Client,
+    fdroid: &FDroidProvider,
+    apis: &ProviderApiConfig,
+) -> Result<LatestVersion, ProviderError> {
+    let json = fdroid_package(client, fdroid, apis).await?;
+    // the suggested version is the version the F-Droid client would install
+    let version = match json["suggestedVersionName"].as_str() {
+        Some(version) => version.to_string(),
+        None => {
+            let versions = fdroid_versions(&json)?;
+            let suggested = json["suggestedVersionCode"].as_u64();
+            // the newest version is used when no version is suggested
+            let Some((_, version)) = versions
+                .iter()
+                .find(|(code, _)| Some(*code) == suggested)
+                .or(versions.first())
+            else {
+                return Err(ProviderError::Parse(
+                    "Response was success but did not contain any version".to_string(),
+                ));
+            };
+            version.clone()
+        }
+    };
+    Ok(LatestVersion {
+        version,
+        renamed_repository: None,
+        published_at: None,
+    })
+}
+
+/// Runs the command with `sh -c` and returns the part of its output that matches the regex.
+async fn command_version(command: &CommandProvider) -> Result<String, ProviderError> {
+    let regex = Regex::new(&command.regex)?;
+    let output = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(&command.command)
+        .kill_on_drop(true)
+        .output();
+    let output = match tokio::time::timeout(COMMAND_TIMEOUT, output).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => return Err(ProviderError::CommandFailed(e.to_string())),
+        Err(_) => {
+            return Err(ProviderError::CommandFailed(format!(
+                "'{}' did not finish within {} seconds",
+                command.command,
+                COMMAND_TIMEOUT.as_secs()
+            )));
+        }
+    };
+    if !output.status.success() {
+        return Err(ProviderError::CommandFailed(format!(
+            "'{}' exited with {}",
+            command.command, output.status
+        )));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let Some(captures) = regex.captures(&stdout) else {
+        return Err(ProviderError::NoCommandOutputMatch(command.regex.clone()));
+    };
+    let version = captures.get(1).or_else(|| captures.get(0));
+    Ok(version.map_or_else(String::new, |version| version.as_str().to_string()))
+}
+
+/// Lists the stable versions of `fdroid`, newest first.
+async fn fdroid_version_list(
+    client: &Client,
+    fdroid: &FDroidProvider,
+    api_config: &ProviderApiConfig,
+) -> Result<VersionList, ProviderError> {
+    let json = fdroid_package(client, fdroid, api_config).await?;
+    // versions newer than the suggested version are usually pre-releases
+    let suggested = json["suggestedVersionCode"].as_u64();
+    let releases = fdroid_versions(&json)?
+        .into_iter()
+        .filter(|(code, _)| suggested.is_none_or(|suggested| *code <= suggested))
+        .map(|(_, version)| Release {
+            version,
+            published_at: None,
+        })
+        .collect();
+    Ok(VersionList {
+        releases,
+        renamed_repository: None,
+    })
+}
+
+/// Requests the information about the app `fdroid` from the F-Droid api.
+async fn fdroid_package(
+    client: &Client,
+    fdroid: &FDroidProvider,
+    apis: &ProviderApiConfig,
+) -> Result<Value, ProviderError> {
+    let url = format!("{}/packages/{}", apis.fdroid_api_url, fdroid.package);
+    let response = client.get(&url).send().await?;
+    if response.status() == StatusCode::NOT_FOUND {
+        return Err(ProviderError::FDroidPackageNotFound(fdroid.package.clone()));
+    }
+    if !response.status().is_success() {
+        return Err(ProviderError::Http(response.status()));
+    }
+    Ok(response.json().await?)
+}
+
+/// Returns the version codes and names of all versions of an F-Droid app, newest first.
+fn fdroid_versions(json: &Value) -> Result<Vec<(u64, String)>, ProviderError> {
+    let Some(packages) = json["packages"].as_array() else {
+        return Err(ProviderError::Parse(
+            "Response was success but did not contain packages".to_string(),
+        ));
+    };
+    let mut versions = packages
+        .iter()
+        .filter_map(|package| {
+            Some((
+                package["versionCode"].as_u64()?,
+                package["versionName"].as_str()?.to_string(),
+            ))
+        })
+        .collect::<Vec<_>>();
+    versions.sort_by_key(|(code, _)| std::cmp::Reverse(*code));
+    Ok(versions)
+}
+
+/// Result of a check for the latest version.
+#[derive(Debug, PartialEq)]
+pub struct LatestVersion {
+    /// Latest version of the program.
+    pub version: String,
+    /// New name of the repository, if the repository was renamed or transferred.
+    pub renamed_repository: Option<String>,
+    /// Time at which the latest version was published, if the provider reports it.
+    pub published_at: Option<NaiveDateTime>,
+}
+
+/// Result of a check for the latest version within a single major version.
+#[derive(Debug, PartialEq)]
+pub struct PinnedVersion {
+    /// Newest version that has the pinned major version.
+    pub latest_version: String,
+    /// Newest version with a higher major version, if such a version exists.
+    pub newer_major_version: Option<String>,
+    /// New name of the repository, if the repository was renamed or transferred.
+    pub renamed_repository: Option<String>,
+    /// Time at which the newest version with the pinned major version was published, if the provider reports it.
+    pub published_at: Option<NaiveDateTime>,
+}
+
+/// A release of a program, see [`VersionList`].
+#[derive(Debug, PartialEq)]
+pub struct Release {
+    /// Version of the release.
+    pub version: String,
+    /// Time at which the release was published, if the provider reports it.
+    pub published_at: Option<NaiveDateTime>,
+}
+
+/// Result of listing all stable versions of a program, see [`UpdateProvider::list_versions`].
+#[derive(Debug, PartialEq)]
+pub struct VersionList {
+    /// Releases of the program, newest first.
+    pub releases: Vec<Release>,
+    /// New name of the repository, if the repository was renamed or transferred.
+    pub renamed_repository: Option<String>,
+}
 
 impl Provider {
-    // Checks what the latest version for the program using this provider is.
-    pub async fn check_for_latest_version(
+    /// Checks what the latest version with the major version `major` is.
+    ///
+    /// Only versions that can be parsed as semantic version are considered.
+    pub async fn check_for_latest_version_in_major(
+        &self,
+        client: &Client,
+        auth: &AuthContext,
+        major: u32,
+    ) -> Result<PinnedVersion, ProviderError> {
+        let VersionList {
+            releases,
+            renamed_repository,
+        } = self.update_provider().list_versions(client, auth).await?;
+        let versions = releases
+            .iter()
+            .map(|release| release.version.clone())
+            .collect::<Vec<String>>();
+        let pinned = latest_version_in_major(&versions, major)
+            .ok_or(ProviderError::NoVersionInMajor(major))?;
+        let published_at = releases
+            .iter()
+            .find(|release| release.version == pinned.latest_version)
+            .and_then(|release| release.published_at);
+        Ok(PinnedVersion {
+            renamed_repository,
+            published_at,
+            ..pinned
+        })
+    }
+
+    /// Checks what the newest version is that satisfies `version_constraint`.
+    pub async fn check_for_latest_matching_version(
+        &self,
+        client: &Client,
+        auth: &AuthContext,
+        version_constraint: &VersionConstraint,
+    ) -> Result<LatestVersion, ProviderError> {
+        let VersionList {
+            releases,
+            renamed_repository,
+        } = self.update_provider().list_versions(client, auth).await?;
+        let Some(release) = releases.into_iter().find(|release| {
+            let matches = version_constraint.matches(&release.version);
+            if !matches {
+                tracing::debug!(
+                    "Ignoring version {} as it does not satisfy version constraint {version_constraint}",
+                    release.version
+                );
+            }
+            matches
+        }) else {
+            return Err(ProviderError::NoVersionMatchingConstraint(
+                version_constraint.to_string(),
+            ));
+        };
+        Ok(LatestVersion {
+            version: release.version,
+            renamed_repository,
+            published_at: release.published_at,
+        })
+    }
+}
+
+/// Returns the newest version in `versions` that has the major version `major`, together with the newest
+/// version that has a higher major version.
+///
+/// Returns `None` if no version with major version `major` exists.
+fn latest_version_in_major(versions: &[String], major: u32) -> Option<PinnedVersion> {
+    let mut latest: Option<(Version, &String)> = None;
+    let mut newer_major: Option<(Version, &String)> = None;
+    for version in versions {
+        let Some(parsed) = parse_semver(version) else {
+            continue;
+        };
+        let newest = match parsed.major.cmp(&u64::from(major)) {
+            std::cmp::Ordering::Equal => &mut latest,
+            std::cmp::Ordering::Greater => &mut newer_major,
+            std::cmp::Ordering::Less => continue,
+        };
+        if newest.as_ref().is_none_or(|(newest, _)| parsed > *newest) {
+            *newest = Some((parsed, version));
+        }
+    }
+    latest.map(|(_, latest_version)| PinnedVersion {
+        latest_version: latest_version.clone(),
+        newer_major_version: newer_major.map(|(_, version)| version.clone()),
+        renamed_repository: None,
+        published_at: None,
+    })
+}
+
+impl GithubProvider {
+    /// Returns the api config that should be used for requests for this program.
+    ///
+    /// If `access_token_env` is set, the access token is read from that environment variable
+    /// and is used instead of the globally configured access token.
+    fn api_config(
         &self,
-        github_access_token: &Option<String>,
-    ) -> Result<String> {
+        github_api_config: &GithubApiConfig,
+    ) -> Result<GithubApiConfig, ProviderError> {
+        let mut github_api_config = github_api_config.clone();
+        if let Some(access_token_env) = &self.access_token_env {
+            match std::env::var(access_token_env) {
+                Ok(access_token) => {
+                    github_api_config.access_token = Some(Secret::from(access_token));
+                }
+                Err(_) => {
+                    return Err(ProviderError::MissingAccessToken(
+                        access_token_env.to_string(),
+                    ));
+                }
+            }
+        }
+        Ok(github_api_config)
+    }
+}
+
+/// Rate limit of a single resource of the github api.
+#[derive(Debug, PartialEq, Tabled)]
+pub struct GithubRateLimit {
+    #[tabled(rename = "Resource")]
+    pub resource: String,
+    #[tabled(rename = "Remaining")]
+    pub remaining: u64,
+    #[tabled(rename = "Limit")]
+    pub limit: u64,
+    #[tabled(rename = "Resets on", display("crate::format_datetime"))]
+    pub reset: NaiveDateTime,
+}
+
+/// Github repository of this tool.
+pub const SELF_REPOSITORY: &str = "LMH01/simple_update_checker";
+
+/// Returns the provider that is used to check for new releases of this tool.
+#[must_use]
+pub fn self_provider() -> Provider {
+    Provider::Github(GithubProvider {
+        repository: SELF_REPOSITORY.to_string(),
+        required_asset_pattern: None,
+        access_token_env: None,
+        release_selector: ReleaseSelector::Latest,
+    })
+}
+
+/// Checks if `version` is newer than the version of this tool that is running.
+///
+/// Returns `false` if `version` can not be parsed as semantic version.
+#[must_use]
+pub fn is_newer_than_self(version: &str) -> bool {
+    parse_semver(version)
+        .zip(parse_semver(env!("CARGO_PKG_VERSION")))
+        .is_some_and(|(version, current)| version > current)
+}
+
+/// Requests the current rate limits of the `core`, `search` and `graphql` resources of the github api.
+pub async fn github_rate_limits(
+    client: &Client,
+    github_api_config: &GithubApiConfig,
+) -> Result<Vec<GithubRateLimit>, ProviderError> {
+    let url = format!("{}/rate_limit", github_api_config.api_url);
+    let json: Value = github_get(client, &url, github_api_config)
+        .await?
+        .json()
+        .await?;
+    ["core", "search", "graphql"]
+        .into_iter()
+        .map(|resource| {
+            let value = &json["resources"][resource];
+            let field = |name: &str| {
+                value[name].as_u64().ok_or_else(|| {
+                    ProviderError::Parse(format!("Rate limit of {resource} did not contain {name}"))
+                })
+            };
+            let reset = i64::try_from(field("reset")?)
+                .ok()
+                .and_then(|reset| DateTime::from_timestamp(reset, 0))
+                .ok_or_else(|| {
+                    ProviderError::Parse(format!("Rate limit of {resource} has invalid reset time"))
+                })?;
+            Ok(GithubRateLimit {
+                resource: resource.to_string(),
+                remaining: field("remaining")?,
+                limit: field("limit")?,
+                reset: reset.naive_utc(),
+            })
+        })
+        .collect()
+}
+
+/// Sends a get request to the github api and returns the response, if the request was successful.
+///
+/// When the rate limit of the active access token is exceeded, the request is repeated with the next fallback access
+/// token. Every token is tried once, later requests start with the token that was rotated to.
+async fn github_get(
+    client: &Client,
+    url: &str,
+    github_api_config: &GithubApiConfig,
+) -> Result<Response, ProviderError> {
+    let access_tokens = github_api_config.access_tokens();
+    let active = &github_api_config.active_access_token;
+    let mut index = active.load(Ordering::Relaxed) % access_tokens.len();
+    let mut untried = access_tokens.len() - 1;
+    loop {
+        let mut request = client
+            .get(url)
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", "2022-11-28");
+        if let Some(token) = access_tokens[index] {
+            request = request.header("Authorization", format!("Bearer {}", token.expose()));
+        };
+        let response = request.send().await?;
+        if response.status().is_success() {
+            return Ok(response);
+        }
+        let e = github_error_from_response(&response);
+        if !matches!(e, ProviderError::RateLimited { .. }) || untried == 0 {
+            return Err(e);
+        }
+        untried -= 1;
+        index = (index + 1) % access_tokens.len();
+        active.store(index, Ordering::Relaxed);
+        tracing::debug!("Github rate limit exceeded, continuing with access token {index}");
+    }
+}
+
+/// Returns the newest release of `github` that is selected by its release selector.
+///
+/// In contrast to the latest release endpoint, the list of all releases is used, so that pre-releases can be selected.
+async fn github_newest_release(
+    client: &Client,
+    github: &GithubProvider,
+    github_api_config: &GithubApiConfig,
+) -> Result<LatestVersion, ProviderError> {
+    let matcher = match &github.required_asset_pattern {
+        Some(pattern) => Some(Glob::new(pattern)?.compile_matcher()),
+        None => None,
+    };
+    let include_prereleases = github.release_selector == ReleaseSelector::Newest;
+    let url = format!(
+        "{}/repos/{}/releases?per_page=100",
+        github_api_config.api_url, github.repository
+    );
+    // newest selected release, regardless if it contains the required asset
+    let mut newest_release = None;
+    let release = find_in_github_list(client, &url, github_api_config, |release| {
+        if release["draft"].as_bool().unwrap_or(false)
+            || (!include_prereleases && release["prerelease"].as_bool().unwrap_or(false))
+        {
+            return false;
+        }
+        if newest_release.is_none() {
+            newest_release = Some(release.clone());
+        }
+        matcher
+            .as_ref()
+            .is_none_or(|matcher| release_has_matching_asset(release, matcher))
+    })
+    .await;
+    let release = match release {
+        Ok(release) => release,
+        Err(ProviderError::NotFound) => {
+            return Err(repository_not_found(&github.repository, github_api_config));
+        }
+        Err(e) => return Err(e),
+    };
+    match (release, newest_release, &github.required_asset_pattern) {
+        (Some(release), _, _) => Ok(LatestVersion {
+            version: github_tag_name(&release)?,
+            renamed_repository: github_renamed_repository(&github.repository, &release),
+            published_at: github_published_at(&release),
+        }),
+        (None, Some(newest_release), Some(pattern)) => Err(ProviderError::AssetNotAvailable {
+            tag: github_tag_name(&newest_release)?,
+            pattern: pattern.to_string(),
+        }),
+        (None, _, _) => Err(ProviderError::NoReleases(github.repository.clone())),
+    }
+}
+
+/// Determines why the latest release of `repo` was not found, by checking if the repository exists.
+async fn github_latest_release_not_found(
+    client: &Client,
+    repo: &str,
+    github_api_config: &GithubApiConfig,
+) -> ProviderError {
+    let url = format!("{}/repos/{repo}", github_api_config.api_url);
+    match github_get(client, &url, github_api_config).await {
+        Ok(_) => ProviderError::NoReleases(repo.to_string()),
+        Err(ProviderError::NotFound) => repository_not_found(repo, github_api_config),
+        Err(e) => e,
+    }
+}
+
+/// Returns the error for the repository `repo` that github reported as not found.
+///
+/// Without access token, github reports private repositories as not found as well, in that case a hint is added.
+fn repository_not_found(repo: &str, github_api_config: &GithubApiConfig) -> ProviderError {
+    if github_api_config
+        .access_tokens()
+        .iter()
+        .all(Option::is_none)
+    {
+        ProviderError::RepositoryNotFoundUnauthenticated(repo.to_string())
+    } else {
+        ProviderError::RepositoryNotFound(repo.to_string())
+    }
+}
+
+/// Returns the first item of the github api list located at `url` for which `predicate` returns true.
+async fn find_in_github_list(
+    client: &Client,
+    url: &str,
+    github_api_config: &GithubApiConfig,
+    mut predicate: impl FnMut(&Value) -> bool,
+) -> Result<Option<Value>, ProviderError> {
+    let mut found = None;
+    walk_github_list(client, url, github_api_config, |item| {
+        if predicate(&item) {
+            found = Some(item);
+            return ControlFlow::Break(());
+        }
+        ControlFlow::Continue(())
+    })
+    .await?;
+    Ok(found)
+}
+
+/// Returns all items of the github api list located at `url` for which `predicate` returns true.
+async fn filter_github_list(
+    client: &Client,
+    url: &str,
+    github_api_config: &GithubApiConfig,
+    mut predicate: impl FnMut(&Value) -> bool,
+) -> Result<Vec<Value>, ProviderError> {
+    let mut items = Vec::new();
+    walk_github_list(client, url, github_api_config, |item| {
+        if predicate(&item) {
+            items.push(item);
+        }
+        ControlFlow::Continue(())
+    })
+    .await?;
+    Ok(items)
+}
+
+/// Walks through the pages of the github api list located at `url` and calls `visit` for each item
+/// until it returns `ControlFlow::Break`.
+///
+/// The next page is taken from the `Link` header. At most `max_pages` pages are requested.
+async fn walk_github_list(
+    client: &Client,
+    url: &str,
+    github_api_config: &GithubApiConfig,
+    mut visit: impl FnMut(Value) -> ControlFlow<()>,
+) -> Result<(), ProviderError> {
+    let mut next_url = Some(url.to_string());
+    let mut pages = 0;
+    while let Some(url) = next_url {
+        if pages >= github_api_config.max_pages {
+            tracing::debug!(
+                "Stopping search in {url} as the maximum number of pages ({}) was reached",
+                github_api_config.max_pages
+            );
+            break;
+        }
+        pages += 1;
+        let response = github_get(client, &url, github_api_config).await?;
+        next_url = response
+            .headers()
+            .get("link")
+            .and_then(|value| value.to_str().ok())
+            .and_then(next_page_url);
+        let items: Vec<Value> = response.json().await?;
+        for item in items {
+            if visit(item).is_break() {
+                return Ok(());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Parses the url with `rel="next"` from the value of a `Link` header.
+fn next_page_url(link_header: &str) -> Option<String> {
+    link_header.split(',').find_map(|link| {
+        let (url, params) = link.split_once(';')?;
+        params
+            .split(';')
+            .any(|param| param.trim() == r#"rel="next""#)
+            .then(|| {
+                url.trim()
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string()
+            })
+    })
+}
+
+/// Returns the new name of `repository`, if the repository of `item` (a release or a commit) differs from it.
+///
+/// Github redirects requests for renamed or transferred repositories, so the repository is taken from the
+/// `html_url` of the item.
+fn github_renamed_repository(repository: &str, item: &Value) -> Option<String> {
+    let url = reqwest::Url::parse(item["html_url"].as_str()?).ok()?;
+    let mut segments = url.path_segments()?;
+    let actual_repository = format!("{}/{}", segments.next()?, segments.next()?);
+    // github treats repository names case insensitive
+    (!actual_repository.eq_ignore_ascii_case(repository)).then_some(actual_repository)
+}
+
+/// Returns the time at which the github release contained in `release` was published.
+fn github_published_at(release: &Value) -> Option<NaiveDateTime> {
+    parse_github_date(&release["published_at"])
+}
+
+/// Parses a date of the github api like `2025-03-12T13:45:00Z`.
+fn parse_github_date(date: &Value) -> Option<NaiveDateTime> {
+    DateTime::parse_from_rfc3339(date.as_str()?)
+        .ok()
+        .map(|date| date.naive_utc())
+}
+
+/// Returns the tag name of the github release contained in `release`.
+fn github_tag_name(release: &Value) -> Result<String, ProviderError> {
+    match release["tag_name"].as_str() {
+        Some(tag_name) => Ok(tag_name.to_string()),
+        None => Err(ProviderError::Parse(
+            "Response was success but did not contain tag_name".to_string(),
+        )),
+    }
+}
+
+/// Checks if at least one asset of the github release contained in `release` has a name
+/// that matches `matcher`.
+fn release_has_matching_asset(release: &Value, matcher: &GlobMatcher) -> bool {
+    release["assets"].as_array().is_some_and(|assets| {
+        assets
+            .iter()
+            .filter_map(|asset| asset["name"].as_str())
+            .any(|name| matcher.is_match(name))
+    })
+}
+
+/// Maps a non success response of the github api to the matching `ProviderError`.
+fn github_error_from_response(response: &Response) -> ProviderError {
+    let status = response.status();
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+    };
+    let rate_limit_exhausted = header("x-ratelimit-remaining") == Some("0");
+    if status == StatusCode::TOO_MANY_REQUESTS
+        || (status == StatusCode::FORBIDDEN && rate_limit_exhausted)
+    {
+        let reset = header("x-ratelimit-reset")
+            .and_then(|reset| reset.parse::<i64>().ok())
+            .and_then(|reset| DateTime::from_timestamp(reset, 0));
+        return ProviderError::RateLimited { reset };
+    }
+    if status == StatusCode::NOT_FOUND {
+        return ProviderError::NotFound;
+    }
+    if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        return ProviderError::AccessDenied;
+    }
+    ProviderError::Http(status)
+}
+
+/// Progress of an update check, reported by [`check_for_updates`] while the programs are checked.
+#[derive(Debug)]
+pub enum CheckEvent<'a> {
+    /// The check of the program named `name` has started.
+    Started { name: &'a str },
+    /// The check of the program named `name` has finished.
+    Finished {
+        name: &'a str,
+        outcome: CheckOutcome<'a>,
+    },
+    /// The check of the program named `name` failed, the error is also added to the report.
+    Error {
+        name: &'a str,
+        error: &'a ProviderError,
+    },
+    /// Note about the update check, e.g. that programs are skipped.
+    Note(String),
+    /// Warning about the update check, e.g. that a release was probably removed.
+    Warning(String),
+}
+
+/// Outcome of the successful check of a single program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckOutcome<'a> {
+    /// The program is up to date.
+    UpToDate,
+    /// An update is available for the program, either a new version was found or the stored latest version is not
+    /// yet installed.
+    UpdateAvailable {
+        current_version: &'a str,
+        latest_version: &'a str,
+        /// Name of the product the program is a component of.
+        product: Option<&'a str>,
+    },
+    /// The found version is older than the stored latest version and is ignored, as rollbacks are not allowed for the
+    /// program.
+    RollbackIgnored,
+}
+
+/// Defines how messages about the progress of an update check are shown.
+///
+/// Provides the default callbacks for [`check_for_updates`], see [`MessageOutput::callback`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageOutput {
+    /// Messages are printed, this includes messages for programs for which no update was found.
+    Print,
+    /// Notes and warnings are logged.
+    Log,
+    /// No messages are shown.
+    Silent,
+}
+
+impl MessageOutput {
+    /// Shows the message for `event`.
+    pub fn handle(self, event: &CheckEvent<'_>) {
+        match event {
+            CheckEvent::Started { name } => {
+                if self == Self::Log {
+                    tracing::debug!("{name}: checking for updates");
+                }
+            }
+            CheckEvent::Finished { name, outcome } => {
+                if self != Self::Print {
+                    return;
+                }
+                match outcome {
+                    CheckOutcome::UpToDate => println!("{name}: no update found"),
+                    CheckOutcome::UpdateAvailable {
+                        current_version,
+                        latest_version,
+                        product,
+                    } => println!(
+                        "{name}: update found {current_version} -> {latest_version}{}",
+                        product
+                            .map(|product| format!(" (component of {product})"))
+                            .unwrap_or_default()
+                    ),
+                    CheckOutcome::RollbackIgnored => (),
+                }
+            }
+            CheckEvent::Error { name, error } => {
+                self.warn(&format!(
+                    "{name}: error while checking for updates: {error}"
+                ));
+            }
+            CheckEvent::Note(message) => self.info(message),
+            CheckEvent::Warning(message) => self.warn(message),
+        }
+    }
+
+    /// Returns a callback for [`check_for_updates`] that shows the messages of all events.
+    pub fn callback(self) -> impl FnMut(CheckEvent<'_>) {
+        move |event| self.handle(&event)
+    }
+
+    fn info(self, message: &str) {
         match self {
-            Self::Github(repo) => {
-                let url = format!("https://api.github.com/repos/{repo}/releases/latest");
-                let mut request = Client::new().get(&url).header("User-Agent", "reqwest");
+            Self::Print => println!("{message}"),
+            Self::Log => tracing::info!("{message}"),
+            Self::Silent => (),
+        }
+    }
+
+    fn warn(self, message: &str) {
+        match self {
+            Self::Print => println!("{message}"),
+            Self::Log => tracing::warn!("{message}"),
+            Self::Silent => (),
+        }
+    }
+}
+
+/// Options that define how the programs are checked for updates.
+#[derive(Debug, Clone, Default)]
+pub struct CheckOptions {
+    /// Request the latest versions of all programs at the same time.
+    pub concurrent: bool,
+    /// When cancelled, the remaining programs are not checked, the results of the already checked programs are
+    /// still stored.
+    pub cancel: Option<CancellationToken>,
+    /// Maximum duration of the update check, when it is exceeded the remaining programs are not checked like
+    /// when the update check is cancelled.
+    pub deadline: Option<Duration>,
+    /// Only report the found updates, nothing is written to the database. Required when the database is opened
+    /// read-only.
+    pub read_only: bool,
+}
+
+/// Result of an update check of all programs.
+pub struct UpdateCheckReport {
+    /// All programs for which updates are available.
+    pub programs_with_available_updates: Vec<Program>,
+    /// Names of the programs for which the update check failed together with the error.
+    pub errors: Vec<(String, ProviderError)>,
+    /// Set when the update check was aborted because the network is unreachable.
+    pub offline: bool,
+    /// Number of programs that where checked, including the programs for which the update check failed.
+    pub checked: usize,
+    /// Number of programs that where not checked because the update check was cancelled or exceeded its deadline.
+    pub not_checked: usize,
+    /// Programs with multiple providers that have updates available, together with the names of the components
+    /// that have updates available.
+    pub updated_products: Vec<(String, Vec<String>)>,
+}
+
+impl UpdateCheckReport {
+    /// Checks if the update check failed for all programs that where checked.
+    #[must_use]
+    pub fn all_failed(&self) -> bool {
+        !self.errors.is_empty() && self.errors.len() == self.checked
+    }
+}
+
+/// Handles a renamed repository of `program`.
+///
+/// The stored repository is updated, if `auto_follow_renames` is set in `github_api_config` and the database is not
+/// `read_only`.
+async fn follow_rename(
+    db: &impl ProgramStore,
+    program: &mut Program,
+    renamed_repository: String,
+    github_api_config: &GithubApiConfig,
+    on_progress: &mut impl FnMut(CheckEvent<'_>),
+    read_only: bool,
+) -> Result<()> {
+    let provider = program.provider.update_provider();
+    let (Some(repository), Some(renamed_provider)) = (
+        provider.repository().map(str::to_string),
+        provider.follow_rename(&renamed_repository),
+    ) else {
+        return Ok(());
+    };
+    if !github_api_config.auto_follow_renames {
+        on_progress(CheckEvent::Warning(format!(
+            "{}: repository {repository} was renamed to {renamed_repository}, the stored repository is not updated as following renames is disabled",
+            program.name
+        )));
+        return Ok(());
+    }
+    if read_only {
+        on_progress(CheckEvent::Warning(format!(
+            "{}: repository {repository} was renamed to {renamed_repository}, the stored repository is not updated as the database is read-only",
+            program.name
+        )));
+        return Ok(());
+    }
+    on_progress(CheckEvent::Note(format!(
+        "{}: repository {repository} was renamed to {renamed_repository}, updating stored repository",
+        program.name
+    )));
+    program.provider = renamed_provider;
+    db.update_provider(&program.name, &program.provider).await?;
+    Ok(())
+}
 
-                if let Some(token) = github_access_token {
-                    request = request.header("Authorization", format!("Bearer {token}"));
+/// Checks what the latest version of `program` is, honoring the pinned major version and the version constraint.
+///
+/// Also returns the note that a newer major version is available, if the major version of `program` is pinned.
+pub(crate) async fn latest_version_of(
+    program: &Program,
+    client: &Client,
+    auth: &AuthContext,
+) -> Result<(LatestVersion, Option<String>), ProviderError> {
+    match (program.pin_major, &program.version_constraint) {
+        (Some(pin_major), _) => program
+            .provider
+            .check_for_latest_version_in_major(client, auth, pin_major)
+            .await
+            .map(|pinned| {
+                let note = pinned.newer_major_version.map(|newer_major_version| {
+                    format!(
+                        "{}: new major version {newer_major_version} available, only versions {pin_major}.x are checked",
+                        program.name
+                    )
+                });
+                let latest = LatestVersion {
+                    version: pinned.latest_version,
+                    renamed_repository: pinned.renamed_repository,
+                    published_at: pinned.published_at,
                 };
-                let response = request.send().await?;
-
-                if response.status().is_success() {
-                    let json: Value = response.json().await?;
-                    if let Some(tag_name) = json["tag_name"].as_str() {
-                        return Ok(tag_name.to_string());
-                    } else {
-                        return Err(anyhow::anyhow!(
-                            "Response was success but did not contain tag_name"
-                        ));
-                    }
-                }
-                Err(anyhow::anyhow!("Request failed with error: {response:?}"))
+                (latest, note)
+            }),
+        (None, Some(version_constraint)) => {
+            program
+                .provider
+                .check_for_latest_matching_version(client, auth, version_constraint)
+                .await
+                .map(|latest| (latest, None))
+        }
+        (None, None) => {
+            program
+                .provider
+                .check_for_latest_version(client, auth)
+                .await
+                .map(|latest| (latest, None))
+        }
+    }
+}
+
+/// Maximum number of programs whose latest version is requested at the same time by a concurrent update check.
+const MAX_CONCURRENT_CHECKS: usize = 8;
+
+/// Checks the latest versions of all `programs` concurrently, at most [`MAX_CONCURRENT_CHECKS`] at the same time.
+///
+/// The results are returned in the same order as `programs`. The results of the checks that are not completed when
+/// `deadline` passes or `cancel` is cancelled are `None`, these checks are aborted.
+async fn latest_versions_concurrently(
+    programs: &[Program],
+    client: &Client,
+    auth: &AuthContext,
+    deadline: Option<Instant>,
+    cancel: Option<&CancellationToken>,
+) -> Vec<Option<Result<(LatestVersion, Option<String>), ProviderError>>> {
+    let permits = Arc::new(Semaphore::new(MAX_CONCURRENT_CHECKS));
+    let mut checks = JoinSet::new();
+    for (idx, program) in programs.iter().cloned().enumerate() {
+        let client = client.clone();
+        let auth = auth.clone();
+        let permits = Arc::clone(&permits);
+        checks.spawn(async move {
+            let _permit = permits
+                .acquire_owned()
+                .await
+                .expect("the semaphore is never closed");
+            let latest_version = latest_version_of(&program, &client, &auth).await;
+            (idx, latest_version)
+        });
+    }
+    let mut latest_versions = programs.iter().map(|_| None).collect::<Vec<_>>();
+    // the results are collected as they finish, so that the completed checks are kept when the deadline passes or
+    // the update check is cancelled
+    let collect = before_deadline(deadline, async {
+        while let Some(result) = checks.join_next().await {
+            let (idx, latest_version) =
+                result.unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()));
+            latest_versions[idx] = Some(latest_version);
+        }
+    });
+    match cancel {
+        Some(cancel) => {
+            tokio::select! {
+                _ = collect => {}
+                () = cancel.cancelled() => {}
             }
         }
+        None => {
+            collect.await;
+        }
     }
+    latest_versions
 }
 
 /// Checks all programs in the database for updates. Updates `latest_version` when update was found.
-/// Returns a vector containing all programs for which updates are available.
+///
+/// A failed check of a single program does not abort the update check, the error is instead added to the report.
+/// If the checks of all programs fail because the network is unreachable, no error and no history entry is stored and
+/// `offline` is set in the report.
+///
+/// If `concurrent` is set in `options`, the latest versions of all programs are requested at the same time. The results
+/// are still processed in the order of the program names, only notes that are shown while checking a program can be
+/// out of order. When the deadline passes, the results of the completed checks are still stored and only the programs
+/// whose check was not completed are counted as not checked.
+///
+/// If the cancellation token in `options` is cancelled, the update check stops before the next program is checked and
+/// the history entry is added for the programs that where already checked. Concurrent checks that are not completed
+/// when the update check is cancelled are aborted, the results of the completed checks are still stored.
+///
+/// The progress of the update check is reported to `on_progress`, use [`MessageOutput::callback`] to show the
+/// progress as messages.
 pub async fn check_for_updates(
-    db: &Db,
+    db: &impl ProgramStore,
+    client: &Client,
     check_args: Option<CheckArgs>,
-    github_access_token: &Option<String>,
-    print_messages: bool,
+    auth: &AuthContext,
+    mut on_progress: impl FnMut(CheckEvent<'_>),
     update_check_type: UpdateCheckType,
-) -> Result<Vec<Program>> {
-    let mut programs = db.get_all_programs().await.unwrap();
+    options: CheckOptions,
+) -> Result<UpdateCheckReport> {
+    let start = Instant::now();
+    let provider = check_args
+        .as_ref()
+        .and_then(|check_args| check_args.provider.as_deref());
+    let mut programs = if check_args
+        .as_ref()
+        .is_some_and(|check_args| check_args.failed_only)
+    {
+        db.get_failed_programs(provider).await?
+    } else {
+        match provider {
+            Some(provider) => db.get_programs_by_provider(provider).await?,
+            None => db.get_all_programs().await?,
+        }
+    };
     programs.sort_by(|a, b| a.name.cmp(&b.name));
+    let shard = check_args.as_ref().and_then(|check_args| check_args.shard);
+    if let Some(shard) = shard {
+        programs.retain(|program| shard.contains(&program.name));
+        on_progress(CheckEvent::Note(format!(
+            "Checking shard {shard}, containing {} programs",
+            programs.len()
+        )));
+    }
+    if let Some(name_pattern) = check_args
+        .as_ref()
+        .and_then(|check_args| check_args.name_pattern.as_ref())
+    {
+        programs.retain(|program| name_pattern.is_match(&program.name));
+    }
+    if !check_args
+        .as_ref()
+        .is_some_and(|check_args| check_args.force)
+    {
+        let now = Utc::now().naive_utc();
+        let program_count = programs.len();
+        programs.retain(|program| program.is_check_due(now));
+        let skipped = program_count - programs.len();
+        if skipped > 0 {
+            on_progress(CheckEvent::Note(format!(
+                "Skipping {skipped} programs whose check interval has not yet passed"
+            )));
+        }
+    }
+    if let Some(max_age) = check_args
+        .as_ref()
+        .and_then(|check_args| check_args.max_age)
+    {
+        let now = Utc::now().naive_utc();
+        let program_count = programs.len();
+        programs.retain(|program| !program.checked_within(max_age, now));
+        let skipped = program_count - programs.len();
+        if skipped > 0 {
+            on_progress(CheckEvent::Note(format!(
+                "Skipping {skipped} programs that where checked less than {} seconds ago",
+                max_age.as_secs()
+            )));
+        }
+    }
 
+    let products = db.get_products().await?;
     let mut programs_with_available_updates = Vec::new();
+    let mut errors = Vec::new();
+    // errors because the network is unreachable, only stored if not all checks failed this way
+    let mut offline_errors = Vec::new();
+    let deadline = options.deadline.map(|deadline| start + deadline);
+    // set when the deadline was exceeded while the latest versions where requested
+    let mut deadline_exceeded = false;
+    let mut concurrent_latest_versions = if options.concurrent {
+        let latest_versions = latest_versions_concurrently(
+            &programs,
+            client,
+            auth,
+            deadline,
+            options.cancel.as_ref(),
+        )
+        .await;
+        deadline_exceeded = latest_versions.iter().any(Option::is_none)
+            && !options
+                .cancel
+                .as_ref()
+                .is_some_and(CancellationToken::is_cancelled);
+        Some(latest_versions.into_iter())
+    } else {
+        None
+    };
 
-    for mut program in programs {
-        let latest_version = program
-            .provider
-            .check_for_latest_version(github_access_token)
-            .await?;
-        if latest_version != program.latest_version {
+    let program_count = programs.len();
+    let mut checked = 0;
+    let mut not_checked = 0;
+    for (idx, mut program) in programs.into_iter().enumerate() {
+        // the results of concurrent checks are already available, they are stored even after the deadline passed or
+        // the update check was cancelled
+        if concurrent_latest_versions.is_none() {
+            let cancelled = options
+                .cancel
+                .as_ref()
+                .is_some_and(CancellationToken::is_cancelled);
+            deadline_exceeded |= deadline.is_some_and(|deadline| Instant::now() >= deadline);
+            if cancelled || deadline_exceeded {
+                not_checked += program_count - idx;
+                break;
+            }
+        }
+        let latest_version = match concurrent_latest_versions.as_mut() {
+            Some(latest_versions) => {
+                let Some(latest_version) = latest_versions
+                    .next()
+                    .expect("a latest version is checked for every program")
+                else {
+                    // the check was not completed before the deadline or the cancellation
+                    not_checked += 1;
+                    continue;
+                };
+                on_progress(CheckEvent::Started {
+                    name: &program.name,
+                });
+                latest_version
+            }
+            None => {
+                on_progress(CheckEvent::Started {
+                    name: &program.name,
+                });
+                match before_deadline(deadline, latest_version_of(&program, client, auth)).await {
+                    Some(latest_version) => latest_version,
+                    None => {
+                        deadline_exceeded = true;
+                        not_checked += program_count - idx;
+                        break;
+                    }
+                }
+            }
+        };
+        checked += 1;
+        let (latest_version, published_at) = match latest_version {
+            Ok((latest, note)) => {
+                if let Some(note) = note {
+                    on_progress(CheckEvent::Note(note));
+                }
+                if let Some(renamed_repository) = latest.renamed_repository {
+                    follow_rename(
+                        db,
+                        &mut program,
+                        renamed_repository,
+                        &auth.github,
+                        &mut on_progress,
+                        options.read_only,
+                    )
+                    .await?;
+                }
+                (latest.version, latest.published_at)
+            }
+            Err(ProviderError::AssetNotAvailable { tag, .. }) => {
+                // keep the previous version until the release assets are uploaded
+                on_progress(CheckEvent::Note(format!(
+                    "{}: release {tag} found but asset not yet available",
+                    program.name
+                )));
+                (
+                    program.latest_version.clone(),
+                    program.latest_version_published_at,
+                )
+            }
+            Err(e) if e.is_offline() => {
+                on_progress(CheckEvent::Error {
+                    name: &program.name,
+                    error: &e,
+                });
+                offline_errors.push((program.name, e));
+                continue;
+            }
+            Err(e) => {
+                on_progress(CheckEvent::Error {
+                    name: &program.name,
+                    error: &e,
+                });
+                if !options.read_only {
+                    db.set_last_error(&program.name, Some(&e.to_string()))
+                        .await?;
+                }
+                errors.push((program.name, e));
+                continue;
+            }
+        };
+        // all changes to the program are stored in a single transaction
+        let mut changes = ProgramChanges {
+            last_checked: Some(Utc::now().naive_utc()),
+            clear_last_error: true,
+            ..Default::default()
+        };
+        let rollback = program
+            .version_scheme
+            .is_older(&latest_version, &program.latest_version);
+        if rollback {
+            // the latest release was probably removed, the version stored as latest_version is never
+            // reported as update
+            on_progress(CheckEvent::Warning(format!(
+                "{}: found version {latest_version} is older than the latest version {}, the release was probably removed",
+                program.name, program.latest_version
+            )));
+            if !program.allow_rollback {
+                changes.older_upstream_version = Some(Some(latest_version));
+                if !options.read_only {
+                    db.apply_program_changes(&program.name, &changes).await?;
+                }
+                on_progress(CheckEvent::Finished {
+                    name: &program.name,
+                    outcome: CheckOutcome::RollbackIgnored,
+                });
+                continue;
+            }
+        }
+        if program.older_upstream_version.is_some() {
+            changes.older_upstream_version = Some(None);
+        }
+        let new_version_found = program
+            .version_scheme
+            .is_newer(&latest_version, &program.latest_version);
+        // the publish date is also stored when it is not yet known for the stored latest version
+        if published_at.is_some()
+            && published_at != program.latest_version_published_at
+            && (new_version_found || rollback || latest_version == program.latest_version)
+        {
+            changes.latest_version_published_at = Some(published_at);
+            program.latest_version_published_at = published_at;
+        }
+        if new_version_found {
             // new version found that does not yet exist in database
             // reset notification info as new version is available and notification for that version was not yet sent
-
-            db.set_notification_sent(&program.name, false).await?;
-            db.set_notification_sent_on(&program.name, None).await?;
+            changes.notification_sent = Some(false);
+            changes.clear_notification_sent_on = true;
 
             // update version in db
-            db.update_latest_version(&program.name, &latest_version, Utc::now().naive_utc())
-                .await
-                .unwrap();
-            if let Some(check_args) = &check_args {
-                if check_args.set_current_version {
-                    db.update_current_version(
-                        &program.name,
-                        &latest_version,
-                        Utc::now().naive_utc(),
-                    )
-                    .await
-                    .unwrap();
-                }
+            changes.latest_version = Some((latest_version.clone(), Utc::now().naive_utc()));
+            if let Some(check_args) = &check_args
+                && check_args.set_current_version
+            {
+                changes.current_version = Some((latest_version.clone(), Utc::now().naive_utc()));
             }
+            changes.found_version = Some(VersionHistoryEntry {
+                date: Utc::now().naive_utc(),
+                name: program.name.clone(),
+                version: latest_version.clone(),
+            });
             program.latest_version = latest_version;
-            if print_messages {
-                println!(
-                    "{}: update found {} -> {}",
-                    program.name, program.current_version, program.latest_version
-                );
+        } else if rollback {
+            // the older version is stored as latest version, but it is no new version, so the notification state is
+            // kept and the program is only reported as update if it is still newer than the current version
+            changes.latest_version = Some((latest_version.clone(), Utc::now().naive_utc()));
+            program.latest_version = latest_version;
+        }
+        if !new_version_found
+            && !program
+                .version_scheme
+                .is_newer(&program.latest_version, &program.current_version)
+        {
+            if !options.read_only {
+                db.apply_program_changes(&program.name, &changes).await?;
             }
+            on_progress(CheckEvent::Finished {
+                name: &program.name,
+                outcome: CheckOutcome::UpToDate,
+            });
+            continue;
+        }
+        // either a new version was found or the newest latest_version already exists in database but program
+        // has not been updated yet (run-timed with --remind-pending notifies again for these programs),
+        // in both cases the program is added exactly once
+        on_progress(CheckEvent::Finished {
+            name: &program.name,
+            outcome: CheckOutcome::UpdateAvailable {
+                current_version: &program.current_version,
+                latest_version: &program.latest_version,
+                product: products.get(&program.name).map(String::as_str),
+            },
+        });
+
+        // if update check was performed manually we don't want so sent a notification when timed mode is run
+        // so we set notification sent to true
+        if update_check_type == UpdateCheckType::Manual
+            && let Some(check_args) = &check_args
+            && !check_args.allow_notification
+        {
+            changes.notification_sent = Some(true);
+        }
+        if !options.read_only {
+            db.apply_program_changes(&program.name, &changes).await?;
+        }
+
+        programs_with_available_updates.push(program);
+    }
+
+    if !offline_errors.is_empty() && offline_errors.len() == checked {
+        on_progress(CheckEvent::Warning(format!(
+            "Network is unreachable, skipping update check: {}",
+            offline_errors[0].1
+        )));
+        return Ok(UpdateCheckReport {
+            programs_with_available_updates,
+            errors,
+            offline: true,
+            checked,
+            not_checked,
+            updated_products: Vec::new(),
+        });
+    }
+    // some checks succeeded, so the network is reachable and the errors are stored like all other errors
+    for (name, e) in offline_errors {
+        if !options.read_only {
+            db.set_last_error(&name, Some(&e.to_string())).await?;
+        }
+        errors.push((name, e));
+    }
+    errors.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    if not_checked > 0 {
+        on_progress(CheckEvent::Warning(if deadline_exceeded {
+            format!(
+                "Update check exceeded its deadline of {} seconds, {not_checked} programs where not checked",
+                options.deadline.unwrap_or_default().as_secs()
+            )
+        } else {
+            format!("Update check was cancelled, {not_checked} programs where not checked")
+        }));
+    }
+
+    // add entry to database that update check was performed
+    if !options.read_only {
+        db.insert_update_check_history(&UpdateCheckHistoryEntry::from_now(
+            update_check_type,
+            programs_with_available_updates.clone(),
+            start.elapsed(),
+            errors.len() as u32,
+            shard,
+            not_checked > 0,
+        ))
+        .await?;
+    }
+
+    let updated_products = updated_products(&programs_with_available_updates, &products);
+    Ok(UpdateCheckReport {
+        programs_with_available_updates,
+        errors,
+        offline: false,
+        checked,
+        not_checked,
+        updated_products,
+    })
+}
+
+/// Awaits `future`, `None` if `deadline` passes before it is completed.
+async fn before_deadline<F: Future>(deadline: Option<Instant>, future: F) -> Option<F::Output> {
+    match deadline {
+        Some(deadline) => {
+            tokio::time::timeout(deadline.saturating_duration_since(Instant::now()), future)
+                .await
+                .ok()
+        }
+        None => Some(future.await),
+    }
+}
+
+/// Returns the programs with multiple providers of which at least one component is contained in `programs`,
+/// together with the names of these components.
+///
+/// `products` contains the product of every component, the key is the name of the component.
+/// The provider of the product itself counts as component as well.
+fn updated_products(
+    programs: &[Program],
+    products: &HashMap<String, String>,
+) -> Vec<(String, Vec<String>)> {
+    let mut updated_products: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for program in programs {
+        let product = match products.get(&program.name) {
+            Some(product) => product,
+            None if products.values().any(|product| product == &program.name) => &program.name,
+            None => continue,
+        };
+        updated_products
+            .entry(product.clone())
+            .or_default()
+            .push(program.name.clone());
+    }
+    updated_products.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        time::{Duration, Instant},
+    };
+
+    use globset::Glob;
+    use serde_json::json;
+    use sqlx::{SqlitePool, types::chrono::DateTime};
+
+    use clap::Parser;
+    use tokio_util::sync::CancellationToken;
+
+    use super::{
+        CheckEvent, CheckOptions, CheckOutcome, GithubRateLimit, LatestVersion,
+        MAX_CONCURRENT_CHECKS, MessageOutput, PinnedVersion, Release, VersionList,
+        check_for_updates, github_get, github_rate_limits, is_newer_than_self,
+        latest_version_in_major, next_page_url, release_has_matching_asset, self_provider,
+        updated_products,
+    };
+    use crate::{
+        CommandProvider, FDroidProvider, GithubApiConfig, GithubCommitProvider, GithubProvider,
+        HomebrewKind, HomebrewProvider, Program, Provider, ProviderApiConfig, ProviderError,
+        ReleaseSelector, Secret, UpdateCheckType, VersionScheme,
+        cli::CheckArgs,
+        db::{
+            self, Db, ProgramStore,
+            memory::{FailingProvider, MemoryStore, StaticProvider},
+        },
+        http::{self, USER_AGENT},
+        provider::{
+            AuthContext, CustomProvider, LatestVersionFuture, UpdateProvider, VersionListFuture,
+        },
+    };
+
+    /// Custom provider with several versions whose repository was renamed to `renamed/<repository>`.
+    #[derive(Debug)]
+    struct RenamedProvider(String);
 
-            // if update check was performed manually we don't want so sent a notification when timed mode is run
-            // so we set notification sent to true
-            if update_check_type == UpdateCheckType::Manual {
-                if let Some(check_args) = &check_args {
-                    if !check_args.allow_notification {
-                        db.set_notification_sent(&program.name, true).await?;
+    impl RenamedProvider {
+        fn renamed_repository(&self) -> Option<String> {
+            (!self.0.starts_with("renamed/")).then(|| format!("renamed/{}", self.0))
+        }
+    }
+
+    impl UpdateProvider for RenamedProvider {
+        fn identifier(&self) -> String {
+            "renamed".to_string()
+        }
+
+        fn check_for_latest_version<'a>(
+            &'a self,
+            _client: &'a reqwest::Client,
+            _auth: &'a AuthContext,
+        ) -> LatestVersionFuture<'a> {
+            Box::pin(async move {
+                Ok(LatestVersion {
+                    version: "2.0.0".to_string(),
+                    renamed_repository: self.renamed_repository(),
+                    published_at: None,
+                })
+            })
+        }
+
+        fn serialize_config(&self) -> String {
+            self.0.clone()
+        }
+
+        fn web_url(&self, _version: &str) -> Option<String> {
+            None
+        }
+
+        fn list_versions<'a>(
+            &'a self,
+            _client: &'a reqwest::Client,
+            _auth: &'a AuthContext,
+        ) -> VersionListFuture<'a> {
+            Box::pin(async move {
+                Ok(VersionList {
+                    releases: ["2.0.0", "1.2.0", "1.1.0"]
+                        .into_iter()
+                        .map(|version| Release {
+                            version: version.to_string(),
+                            published_at: None,
+                        })
+                        .collect(),
+                    renamed_repository: self.renamed_repository(),
+                })
+            })
+        }
+
+        fn repository(&self) -> Option<&str> {
+            Some(&self.0)
+        }
+
+        fn follow_rename(&self, renamed_repository: &str) -> Option<Provider> {
+            Some(Provider::Custom(CustomProvider::new(RenamedProvider(
+                renamed_repository.to_string(),
+            ))))
+        }
+    }
+
+    #[test]
+    fn test_release_has_matching_asset() {
+        let release = json!({
+            "tag_name": "v1.0.0",
+            "assets": [
+                { "name": "tool-v1.0.0-windows-x86_64.zip" },
+                { "name": "tool-v1.0.0-linux-x86_64.tar.gz" },
+            ]
+        });
+        let linux = Glob::new("*linux-x86_64*").unwrap().compile_matcher();
+        let darwin = Glob::new("*darwin*").unwrap().compile_matcher();
+        assert!(release_has_matching_asset(&release, &linux));
+        assert!(!release_has_matching_asset(&release, &darwin));
+    }
+
+    #[test]
+    fn test_release_has_matching_asset_no_assets() {
+        let release = json!({ "tag_name": "v1.0.0", "assets": [] });
+        let matcher = Glob::new("*").unwrap().compile_matcher();
+        assert!(!release_has_matching_asset(&release, &matcher));
+    }
+
+    #[sqlx::test]
+    async fn test_check_for_updates_products(pool: SqlitePool) {
+        let db = db::tests::db(pool);
+        for (name, version) in [
+            ("alpha_tui", "v1.1.0"),
+            ("suite", "v1.0.0"),
+            ("suite-server", "v2.0.0"),
+        ] {
+            db.insert_program(
+                &Program::builder()
+                    .name(name)
+                    .current_version("v1.0.0")
+                    .provider(Provider::Command(CommandProvider {
+                        command: format!("echo {version}"),
+                        regex: r"v[0-9.]+".to_string(),
+                    }))
+                    .version_scheme(VersionScheme::Semver)
+                    .build()
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        }
+        db.add_component("suite", "suite-server").await.unwrap();
+        let report = check_for_updates(
+            &db,
+            &http::client(),
+            None,
+            &AuthContext::default(),
+            MessageOutput::Silent.callback(),
+            UpdateCheckType::Manual,
+            CheckOptions::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(2, report.programs_with_available_updates.len());
+        assert_eq!(
+            vec![("suite".to_string(), vec!["suite-server".to_string()])],
+            report.updated_products
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_for_updates_read_only() {
+        let path = db::tests::temp_db_path("check_read_only");
+        Db::connect(&path)
+            .await
+            .unwrap()
+            .insert_program(
+                &Program::builder()
+                    .name("simple_update_checker")
+                    .current_version("v1.0.0")
+                    .provider(Provider::Github(GithubProvider {
+                        repository: "LMH01/simple_update_checker".to_string(),
+                        required_asset_pattern: None,
+                        access_token_env: None,
+                        release_selector: ReleaseSelector::Latest,
+                    }))
+                    .version_scheme(VersionScheme::Semver)
+                    .build()
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/repos/LMH01/simple_update_checker/releases/latest")
+            .with_status(200)
+            .with_body(r#"{ "tag_name": "v1.2.0", "assets": [] }"#)
+            .create_async()
+            .await;
+        let auth = AuthContext::from(GithubApiConfig {
+            api_url: server.url(),
+            ..Default::default()
+        });
+        let db = Db::connect_read_only(&path).await.unwrap();
+        let report = check_for_updates(
+            &db,
+            &http::client(),
+            None,
+            &auth,
+            MessageOutput::Silent.callback(),
+            UpdateCheckType::Manual,
+            CheckOptions {
+                read_only: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(1, report.programs_with_available_updates.len());
+        assert_eq!(
+            "v1.2.0",
+            report.programs_with_available_updates[0].latest_version
+        );
+        // nothing was written to the database
+        let program = db
+            .get_program("simple_update_checker")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!("v1.0.0", program.latest_version);
+        assert_eq!(None, program.last_checked);
+        assert!(
+            db.get_all_update_checks(None, false)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            db.get_version_history("simple_update_checker", None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[sqlx::test]
+    async fn test_check_for_updates_deadline(pool: SqlitePool) {
+        // the program that is checked before the slow program is stored
+        check_for_updates_deadline(db::tests::db(pool), false, "alpha_tui").await;
+    }
+
+    #[sqlx::test]
+    async fn test_check_for_updates_deadline_concurrent(pool: SqlitePool) {
+        // the result of a program that is completed before the deadline is stored, although a program before it in
+        // the order of names is not completed
+        check_for_updates_deadline(db::tests::db(pool), true, "tui").await;
+    }
+
+    /// Checks the program named `name`, which is completed before the deadline, and a program named `slow`, which is
+    /// not.
+    async fn check_for_updates_deadline(db: Db, concurrent: bool, name: &str) {
+        for (name, command) in [(name, "echo v1.1.0"), ("slow", "sleep 5")] {
+            db.insert_program(
+                &Program::builder()
+                    .name(name)
+                    .current_version("v1.0.0")
+                    .provider(Provider::Command(CommandProvider {
+                        command: command.to_string(),
+                        regex: r"v[0-9.]+".to_string(),
+                    }))
+                    .version_scheme(VersionScheme::Semver)
+                    .build()
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        }
+        let report = check_for_updates(
+            &db,
+            &http::client(),
+            None,
+            &AuthContext::default(),
+            MessageOutput::Silent.callback(),
+            UpdateCheckType::Timed,
+            CheckOptions {
+                concurrent,
+                deadline: Some(Duration::from_millis(500)),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let update_checks = db.get_all_update_checks(None, false).await.unwrap();
+        assert!(update_checks[0].truncated);
+        assert_eq!(1, report.checked);
+        assert_eq!(1, report.not_checked);
+        assert_eq!(1, report.programs_with_available_updates.len());
+        let program = db.get_program(name).await.unwrap().unwrap();
+        assert_eq!("v1.1.0", program.latest_version);
+    }
+
+    #[sqlx::test]
+    async fn test_check_for_updates_cancelled_concurrent(pool: SqlitePool) {
+        let db = db::tests::db(pool);
+        // more programs than are checked at the same time, so that some checks have to wait for a free slot
+        let fast = (0..MAX_CONCURRENT_CHECKS + 2)
+            .map(|idx| format!("fast-{idx}"))
+            .collect::<Vec<_>>();
+        for (name, command) in fast
+            .iter()
+            .map(|name| (name.as_str(), "echo v1.1.0"))
+            .chain([("slow", "sleep 5")])
+        {
+            db.insert_program(
+                &Program::builder()
+                    .name(name)
+                    .current_version("v1.0.0")
+                    .provider(Provider::Command(CommandProvider {
+                        command: command.to_string(),
+                        regex: r"v[0-9.]+".to_string(),
+                    }))
+                    .version_scheme(VersionScheme::Semver)
+                    .build()
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        }
+        let cancel = CancellationToken::new();
+        let cancel_later = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            cancel_later.cancel();
+        });
+        let start = Instant::now();
+        let report = check_for_updates(
+            &db,
+            &http::client(),
+            None,
+            &AuthContext::default(),
+            MessageOutput::Silent.callback(),
+            UpdateCheckType::Timed,
+            CheckOptions {
+                concurrent: true,
+                cancel: Some(cancel),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        // the slow check is aborted instead of awaited
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(fast.len(), report.checked);
+        assert_eq!(1, report.not_checked);
+        assert_eq!(fast.len(), report.programs_with_available_updates.len());
+        assert!(db.get_all_update_checks(None, false).await.unwrap()[0].truncated);
+    }
+
+    #[tokio::test]
+    async fn test_self_provider() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/repos/LMH01/simple_update_checker/releases/latest")
+            .with_status(200)
+            .with_body(r#"{ "tag_name": "v999.0.0", "assets": [] }"#)
+            .create_async()
+            .await;
+        let auth = AuthContext::from(GithubApiConfig {
+            api_url: server.url(),
+            ..Default::default()
+        });
+        let latest = self_provider()
+            .check_for_latest_version(&http::client(), &auth)
+            .await
+            .unwrap();
+        assert_eq!("v999.0.0", latest.version);
+        assert!(is_newer_than_self(&latest.version));
+    }
+
+    #[test]
+    fn test_is_newer_than_self() {
+        let current = env!("CARGO_PKG_VERSION");
+        assert!(!is_newer_than_self(current));
+        assert!(!is_newer_than_self(&format!("v{current}")));
+        assert!(!is_newer_than_self("v0.1.0"));
+        assert!(is_newer_than_self("v999.0.0"));
+        assert!(!is_newer_than_self(&format!("v{current}-rc.1")));
+        assert!(!is_newer_than_self("nightly"));
+    }
+
+    #[test]
+    fn test_updated_products() {
+        let program = |name: &str| {
+            Program::builder()
+                .name(name)
+                .current_version("v1.0.0")
+                .latest_version("v1.1.0")
+                .provider(Provider::Github(GithubProvider::new(&format!(
+                    "LMH01/{name}"
+                ))))
+                .build()
+                .unwrap()
+        };
+        let products = HashMap::from([
+            ("suite-cli".to_string(), "suite".to_string()),
+            ("suite-server".to_string(), "suite".to_string()),
+        ]);
+        // the provider of the product itself counts as component
+        let programs = [
+            program("alpha_tui"),
+            program("suite"),
+            program("suite-server"),
+        ];
+        assert_eq!(
+            vec![(
+                "suite".to_string(),
+                vec!["suite".to_string(), "suite-server".to_string()]
+            )],
+            updated_products(&programs, &products)
+        );
+        assert!(updated_products(&[program("alpha_tui")], &products).is_empty());
+    }
+
+    #[test]
+    fn test_next_page_url() {
+        let link = r#"<https://api.github.com/repositories/1/releases?page=2>; rel="next", <https://api.github.com/repositories/1/releases?page=5>; rel="last""#;
+        assert_eq!(
+            Some("https://api.github.com/repositories/1/releases?page=2".to_string()),
+            next_page_url(link)
+        );
+    }
+
+    #[test]
+    fn test_next_page_url_last_page() {
+        let link = r#"<https://api.github.com/repositories/1/releases?page=1>; rel="prev", <https://api.github.com/repositories/1/releases?page=1>; rel="first""#;
+        assert_eq!(None, next_page_url(link));
+    }
+
+    #[sqlx::test]
+    fn test_check_for_updates_program_added_once(pool: SqlitePool) {
+        let db = db::tests::db(pool);
+        // current_version is behind latest_version and a new latest_version is available
+        db.insert_program(
+            &Program::builder()
+                .name("simple_update_checker")
+                .current_version("v1.0.0")
+                .latest_version("v1.1.0")
+                .provider(Provider::Github(GithubProvider::new(
+                    "LMH01/simple_update_checker",
+                )))
+                .build()
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/repos/LMH01/simple_update_checker/releases/latest")
+            .with_status(200)
+            .with_body(
+                r#"{ "tag_name": "v1.2.0", "published_at": "2025-03-12T13:45:00Z", "assets": [] }"#,
+            )
+            .create_async()
+            .await;
+        let auth = AuthContext::from(GithubApiConfig {
+            api_url: server.url(),
+            ..Default::default()
+        });
+        let report = check_for_updates(
+            &db,
+            &http::client(),
+            None,
+            &auth,
+            MessageOutput::Silent.callback(),
+            UpdateCheckType::Timed,
+            CheckOptions::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(1, report.programs_with_available_updates.len());
+        assert_eq!(
+            "v1.2.0",
+            report.programs_with_available_updates[0].latest_version
+        );
+        assert_eq!(
+            Some(DateTime::from_timestamp(1741787100, 0).unwrap().naive_utc()),
+            report.programs_with_available_updates[0].latest_version_published_at
+        );
+        let history = db.get_all_update_checks(None, false).await.unwrap();
+        assert_eq!(1, history[0].updates_available);
+        let versions = db
+            .get_version_history("simple_update_checker", None)
+            .await
+            .unwrap();
+        assert_eq!(1, versions.len());
+        assert_eq!("v1.2.0", versions[0].version);
+    }
+
+    #[sqlx::test]
+    fn test_check_for_updates_events(pool: SqlitePool) {
+        let db = db::tests::db(pool);
+        let mut server = mockito::Server::new_async().await;
+        for (name, status, body) in [
+            ("current", 200, r#"{ "tag_name": "v1.0.0", "assets": [] }"#),
+            ("failing", 404, ""),
+            ("outdated", 200, r#"{ "tag_name": "v1.1.0", "assets": [] }"#),
+        ] {
+            db.insert_program(
+                &Program::builder()
+                    .name(name)
+                    .current_version("v1.0.0")
+                    .provider(Provider::Github(GithubProvider::new(&format!(
+                        "LMH01/{name}"
+                    ))))
+                    .build()
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+            server
+                .mock(
+                    "GET",
+                    format!("/repos/LMH01/{name}/releases/latest").as_str(),
+                )
+                .with_status(status)
+                .with_body(body)
+                .create_async()
+                .await;
+        }
+        let auth = AuthContext::from(GithubApiConfig {
+            api_url: server.url(),
+            ..Default::default()
+        });
+        let mut events = Vec::new();
+        let report = check_for_updates(
+            &db,
+            &http::client(),
+            None,
+            &auth,
+            |event| {
+                events.push(match event {
+                    CheckEvent::Started { name } => format!("started {name}"),
+                    CheckEvent::Finished { name, outcome } => {
+                        format!("finished {name} {outcome:?}")
                     }
-                }
-            }
+                    CheckEvent::Error { name, .. } => format!("error {name}"),
+                    CheckEvent::Note(message) | CheckEvent::Warning(message) => message,
+                });
+            },
+            UpdateCheckType::Timed,
+            CheckOptions::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(1, report.errors.len());
+        assert_eq!(
+            vec![
+                "started current".to_string(),
+                "finished current UpToDate".to_string(),
+                "started failing".to_string(),
+                "error failing".to_string(),
+                "started outdated".to_string(),
+                format!(
+                    "finished outdated {:?}",
+                    CheckOutcome::UpdateAvailable {
+                        current_version: "v1.0.0",
+                        latest_version: "v1.1.0",
+                        product: None,
+                    }
+                ),
+            ],
+            events
+        );
+    }
 
-            programs_with_available_updates.push(program);
-        } else if latest_version != program.current_version {
-            // newest latest_version already exists in database but program has not been updated yet
-            if print_messages {
-                println!(
-                    "{}: update found {} -> {}",
-                    program.name, program.current_version, program.latest_version
-                );
+    #[sqlx::test]
+    fn test_check_for_updates_failed_only(pool: SqlitePool) {
+        let db = db::tests::db(pool);
+        let mut server = mockito::Server::new_async().await;
+        for name in ["failing", "working"] {
+            db.insert_program(
+                &Program::builder()
+                    .name(name)
+                    .current_version("v1.0.0")
+                    .provider(Provider::Github(GithubProvider::new(&format!(
+                        "LMH01/{name}"
+                    ))))
+                    .build()
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        }
+        let failing = server
+            .mock("GET", "/repos/LMH01/failing/releases/latest")
+            .with_status(404)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/LMH01/working/releases/latest")
+            .with_status(200)
+            .with_body(r#"{ "tag_name": "v1.0.0", "assets": [] }"#)
+            .create_async()
+            .await;
+        let auth = AuthContext::from(GithubApiConfig {
+            api_url: server.url(),
+            ..Default::default()
+        });
+        let report = check_for_updates(
+            &db,
+            &http::client(),
+            None,
+            &auth,
+            MessageOutput::Silent.callback(),
+            UpdateCheckType::Timed,
+            CheckOptions::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(1, report.errors.len());
+        assert!(db.get_last_error("failing").await.unwrap().is_some());
+        assert_eq!(None, db.get_last_error("working").await.unwrap());
+        assert_eq!(
+            vec!["failing"],
+            db.get_failed_programs(None)
+                .await
+                .unwrap()
+                .iter()
+                .map(|program| program.name.as_str())
+                .collect::<Vec<_>>()
+        );
+
+        // the repository is reachable again, only the failed program is checked
+        failing.remove_async().await;
+        server
+            .mock("GET", "/repos/LMH01/failing/releases/latest")
+            .with_status(200)
+            .with_body(r#"{ "tag_name": "v1.1.0", "assets": [] }"#)
+            .create_async()
+            .await;
+        let report = check_for_updates(
+            &db,
+            &http::client(),
+            Some(CheckArgs::parse_from(["check", "--failed-only"])),
+            &auth,
+            MessageOutput::Silent.callback(),
+            UpdateCheckType::Timed,
+            CheckOptions::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(1, report.checked);
+        assert_eq!(1, report.programs_with_available_updates.len());
+        assert_eq!(None, db.get_last_error("failing").await.unwrap());
+        assert!(db.get_failed_programs(None).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_check_for_updates_memory_store() {
+        let store = MemoryStore::default();
+        let program = |name: &str, latest_version: &str, provider: Provider| {
+            Program::builder()
+                .name(name)
+                .current_version("1.0.0")
+                .latest_version(latest_version)
+                .provider(provider)
+                .version_scheme(VersionScheme::Semver)
+                .build()
+                .unwrap()
+        };
+        let static_provider = |version: &str| {
+            Provider::Custom(CustomProvider::new(StaticProvider(version.to_string())))
+        };
+        for program in [
+            program("current", "1.0.0", static_provider("1.0.0")),
+            program(
+                "failing",
+                "1.0.0",
+                Provider::Custom(CustomProvider::new(FailingProvider)),
+            ),
+            program("outdated", "1.0.0", static_provider("1.1.0")),
+            program("removed", "1.2.0", static_provider("1.1.0")),
+            program("component", "1.0.0", static_provider("2.0.0")),
+        ] {
+            store.insert_program(&program).await.unwrap();
+        }
+        store.add_component("suite", "component");
+
+        let report = check_for_updates(
+            &store,
+            &http::client(),
+            Some(CheckArgs::parse_from(["check"])),
+            &AuthContext::default(),
+            MessageOutput::Silent.callback(),
+            UpdateCheckType::Manual,
+            CheckOptions::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(5, report.checked);
+        assert_eq!(
+            vec!["component", "outdated"],
+            report
+                .programs_with_available_updates
+                .iter()
+                .map(|program| program.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![("suite".to_string(), vec!["component".to_string()])],
+            report.updated_products
+        );
+        assert_eq!(1, report.errors.len());
+        assert_eq!(
+            Some(ProviderError::NotFound.to_string()),
+            store.last_error("failing")
+        );
+
+        // found versions are stored, updates found manually are not notified
+        let outdated = store.get_program("outdated").await.unwrap().unwrap();
+        assert_eq!("1.1.0", outdated.latest_version);
+        assert!(outdated.last_checked.is_some());
+        assert_eq!(vec!["1.1.0"], store.version_history("outdated"));
+        assert!(
+            store
+                .get_notification_info("outdated")
+                .await
+                .unwrap()
+                .unwrap()
+                .sent
+        );
+        // the removed release is remembered but not reported
+        let removed = store.get_program("removed").await.unwrap().unwrap();
+        assert_eq!("1.2.0", removed.latest_version);
+        assert_eq!(Some("1.1.0".to_string()), removed.older_upstream_version);
+        assert!(store.version_history("removed").is_empty());
+        let current = store.get_program("current").await.unwrap().unwrap();
+        assert!(current.last_checked.is_some());
+
+        let update_checks = store.update_checks();
+        assert_eq!(1, update_checks.len());
+        assert_eq!(2, update_checks[0].updates_available);
+        assert_eq!(Some(1), update_checks[0].errors);
+    }
+
+    #[tokio::test]
+    async fn test_check_for_updates_custom_provider_versions() {
+        let store = MemoryStore::default();
+        store
+            .insert_program(
+                &Program::builder()
+                    .name("pinned")
+                    .current_version("1.1.0")
+                    .latest_version("1.1.0")
+                    .provider(Provider::Custom(CustomProvider::new(RenamedProvider(
+                        "LMH01/pinned".to_string(),
+                    ))))
+                    .version_scheme(VersionScheme::Semver)
+                    .pin_major(Some(1))
+                    .build()
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let report = check_for_updates(
+            &store,
+            &http::client(),
+            None,
+            &AuthContext::default(),
+            MessageOutput::Silent.callback(),
+            UpdateCheckType::Manual,
+            CheckOptions::default(),
+        )
+        .await
+        .unwrap();
+        assert!(report.errors.is_empty());
+        let program = store.get_program("pinned").await.unwrap().unwrap();
+        assert_eq!("1.2.0", program.latest_version);
+        assert_eq!(
+            Some("renamed/LMH01/pinned"),
+            program.provider.update_provider().repository()
+        );
+    }
+
+    #[sqlx::test]
+    fn test_check_for_updates_removed_release(pool: SqlitePool) {
+        let db = db::tests::db(pool);
+        let mut server = mockito::Server::new_async().await;
+        // the release v1.2.0 was removed, so v1.1.0 is reported as latest release again
+        for (name, current_version, allow_rollback) in [
+            ("keep", "v1.0.0", false),
+            ("rollback", "v1.0.0", true),
+            ("installed", "v1.2.0", true),
+        ] {
+            db.insert_program(
+                &Program::builder()
+                    .name(name)
+                    .current_version(current_version)
+                    .latest_version("v1.2.0")
+                    .provider(Provider::Github(GithubProvider::new(&format!(
+                        "LMH01/{name}"
+                    ))))
+                    .version_scheme(VersionScheme::Semver)
+                    .allow_rollback(allow_rollback)
+                    .build()
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+            db.set_notification_sent(name, true).await.unwrap();
+            server
+                .mock(
+                    "GET",
+                    format!("/repos/LMH01/{name}/releases/latest").as_str(),
+                )
+                .with_status(200)
+                .with_body(r#"{ "tag_name": "v1.1.0", "assets": [] }"#)
+                .create_async()
+                .await;
+        }
+        let auth = AuthContext::from(GithubApiConfig {
+            api_url: server.url(),
+            ..Default::default()
+        });
+        let report = check_for_updates(
+            &db,
+            &http::client(),
+            None,
+            &auth,
+            MessageOutput::Silent.callback(),
+            UpdateCheckType::Timed,
+            CheckOptions::default(),
+        )
+        .await
+        .unwrap();
+        // the removed release is never reported as update, the older version only if it is newer than the current
+        // version
+        assert_eq!(
+            vec!["rollback"],
+            report
+                .programs_with_available_updates
+                .iter()
+                .map(|program| program.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "v1.1.0",
+            report.programs_with_available_updates[0].latest_version
+        );
+        let keep = db.get_program("keep").await.unwrap().unwrap();
+        assert_eq!("v1.2.0", keep.latest_version);
+        assert_eq!(Some("v1.1.0".to_string()), keep.older_upstream_version);
+        // the older version is stored, but no notification is sent for it
+        for name in ["rollback", "installed"] {
+            assert_eq!(
+                "v1.1.0",
+                db.get_program(name).await.unwrap().unwrap().latest_version
+            );
+            assert!(db.get_notification_info(name).await.unwrap().unwrap().sent);
+        }
+        assert!(
+            db.get_version_history("installed", None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[sqlx::test]
+    fn test_check_for_updates_concurrent(pool: SqlitePool) {
+        let db = db::tests::db(pool);
+        let mut server = mockito::Server::new_async().await;
+        for (name, status) in [("c", 200), ("a", 200), ("b", 404), ("d", 200)] {
+            db.insert_program(
+                &Program::builder()
+                    .name(name)
+                    .current_version("v1.0.0")
+                    .provider(Provider::Github(GithubProvider::new(&format!(
+                        "LMH01/{name}"
+                    ))))
+                    .build()
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+            server
+                .mock(
+                    "GET",
+                    format!("/repos/LMH01/{name}/releases/latest").as_str(),
+                )
+                .with_status(status)
+                .with_body(r#"{ "tag_name": "v1.1.0", "assets": [] }"#)
+                .create_async()
+                .await;
+        }
+        server
+            .mock("GET", "/repos/LMH01/b")
+            .with_status(404)
+            .create_async()
+            .await;
+        let auth = AuthContext::from(GithubApiConfig {
+            api_url: server.url(),
+            ..Default::default()
+        });
+        let report = check_for_updates(
+            &db,
+            &http::client(),
+            None,
+            &auth,
+            MessageOutput::Silent.callback(),
+            UpdateCheckType::Timed,
+            CheckOptions {
+                concurrent: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        // the results are in the same order as for a sequential check
+        assert_eq!(
+            vec!["a", "c", "d"],
+            report
+                .programs_with_available_updates
+                .iter()
+                .map(|program| program.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(1, report.errors.len());
+        assert_eq!("b", report.errors[0].0);
+    }
+
+    #[sqlx::test]
+    fn test_check_for_updates_offline(pool: SqlitePool) {
+        let db = db::tests::db(pool);
+        db.insert_program(
+            &Program::builder()
+                .name("simple_update_checker")
+                .current_version("v1.0.0")
+                .provider(Provider::Github(GithubProvider::new(
+                    "LMH01/simple_update_checker",
+                )))
+                .build()
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+        // nothing is listening on port 1, so the connection is refused
+        let auth = AuthContext::from(GithubApiConfig {
+            api_url: "http://127.0.0.1:1".to_string(),
+            ..Default::default()
+        });
+        let report = check_for_updates(
+            &db,
+            &http::client(),
+            None,
+            &auth,
+            MessageOutput::Silent.callback(),
+            UpdateCheckType::Timed,
+            CheckOptions::default(),
+        )
+        .await
+        .unwrap();
+        assert!(report.offline);
+        assert!(report.errors.is_empty());
+        assert!(
+            db.get_all_update_checks(None, false)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[sqlx::test]
+    fn test_check_for_updates_partially_offline(pool: SqlitePool) {
+        let db = db::tests::db(pool);
+        let mut server = mockito::Server::new_async().await;
+        for (name, provider) in [
+            (
+                "a_unreachable",
+                Provider::Github(GithubProvider::new("LMH01/simple_update_checker")),
+            ),
+            (
+                "b_reachable",
+                Provider::Homebrew(HomebrewProvider {
+                    package: "ripgrep".to_string(),
+                    kind: HomebrewKind::Formula,
+                }),
+            ),
+        ] {
+            db.insert_program(
+                &Program::builder()
+                    .name(name)
+                    .current_version("14.1.0")
+                    .provider(provider)
+                    .build()
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        }
+        server
+            .mock("GET", "/formula/ripgrep.json")
+            .with_status(200)
+            .with_body(r#"{ "name": "ripgrep", "versions": { "stable": "14.1.1" } }"#)
+            .create_async()
+            .await;
+        // only the github api is unreachable, nothing is listening on port 1
+        let auth = AuthContext {
+            github: GithubApiConfig {
+                api_url: "http://127.0.0.1:1".to_string(),
+                ..Default::default()
+            },
+            apis: ProviderApiConfig {
+                homebrew_api_url: server.url(),
+                ..Default::default()
+            },
+        };
+        let report = check_for_updates(
+            &db,
+            &http::client(),
+            None,
+            &auth,
+            MessageOutput::Silent.callback(),
+            UpdateCheckType::Timed,
+            CheckOptions::default(),
+        )
+        .await
+        .unwrap();
+        assert!(!report.offline);
+        assert_eq!(2, report.checked);
+        assert_eq!(
+            vec!["a_unreachable"],
+            report
+                .errors
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert!(db.get_last_error("a_unreachable").await.unwrap().is_some());
+        assert_eq!(1, report.programs_with_available_updates.len());
+        assert_eq!(
+            1,
+            db.get_all_update_checks(None, false).await.unwrap().len()
+        );
+    }
+
+    #[sqlx::test]
+    fn test_check_for_updates_cancelled(pool: SqlitePool) {
+        let db = db::tests::db(pool);
+        db.insert_program(
+            &Program::builder()
+                .name("simple_update_checker")
+                .current_version("v1.0.0")
+                .provider(Provider::Github(GithubProvider::new(
+                    "LMH01/simple_update_checker",
+                )))
+                .build()
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        // no request is sent, so the update check does not fail although nothing is listening on port 1
+        let auth = AuthContext::from(GithubApiConfig {
+            api_url: "http://127.0.0.1:1".to_string(),
+            ..Default::default()
+        });
+        let report = check_for_updates(
+            &db,
+            &http::client(),
+            None,
+            &auth,
+            MessageOutput::Silent.callback(),
+            UpdateCheckType::Timed,
+            CheckOptions {
+                cancel: Some(cancel),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert!(!report.offline);
+        assert!(report.errors.is_empty());
+        assert!(report.programs_with_available_updates.is_empty());
+        assert_eq!(
+            1,
+            db.get_all_update_checks(None, false).await.unwrap().len()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_github_get_rotates_access_tokens() {
+        let mut server = mockito::Server::new_async().await;
+        let rate_limited = |mock: mockito::Mock| {
+            mock.with_status(403)
+                .with_header("x-ratelimit-remaining", "0")
+                .with_header("x-ratelimit-reset", "1760695200")
+        };
+        let unauthenticated = rate_limited(
+            server
+                .mock("GET", "/repos/LMH01/alpha_tui")
+                .match_header("Authorization", mockito::Matcher::Missing),
+        )
+        .expect(1)
+        .create_async()
+        .await;
+        let first = rate_limited(
+            server
+                .mock("GET", "/repos/LMH01/alpha_tui")
+                .match_header("Authorization", "Bearer first"),
+        )
+        .expect(1)
+        .create_async()
+        .await;
+        let second = server
+            .mock("GET", "/repos/LMH01/alpha_tui")
+            .match_header("Authorization", "Bearer second")
+            .with_status(200)
+            .with_body("{}")
+            .expect(2)
+            .create_async()
+            .await;
+        let github_api_config = GithubApiConfig {
+            api_url: server.url(),
+            fallback_access_tokens: vec![
+                Secret::from("first".to_string()),
+                Secret::from("second".to_string()),
+            ],
+            ..Default::default()
+        };
+        let url = format!("{}/repos/LMH01/alpha_tui", server.url());
+        let client = http::client();
+        github_get(&client, &url, &github_api_config).await.unwrap();
+        // the next request starts with the token that was rotated to
+        github_get(&client, &url, &github_api_config.clone())
+            .await
+            .unwrap();
+        unauthenticated.assert_async().await;
+        first.assert_async().await;
+        second.assert_async().await;
+
+        // the error is returned when the rate limit of all tokens is exceeded
+        let github_api_config = GithubApiConfig {
+            api_url: server.url(),
+            fallback_access_tokens: vec![Secret::from("first".to_string())],
+            ..Default::default()
+        };
+        let res = github_get(&client, &url, &github_api_config).await;
+        assert!(matches!(res, Err(ProviderError::RateLimited { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_github_rate_limits() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/rate_limit")
+            .match_header("Authorization", "Bearer token")
+            .with_status(200)
+            .with_body(
+                r#"{ "resources": {
+                    "core": { "limit": 5000, "used": 1, "remaining": 4999, "reset": 1760695200 },
+                    "search": { "limit": 30, "used": 0, "remaining": 30, "reset": 1760691660 },
+                    "graphql": { "limit": 5000, "used": 0, "remaining": 5000, "reset": 1760695200 }
+                } }"#,
+            )
+            .create_async()
+            .await;
+        let github_api_config = GithubApiConfig {
+            api_url: server.url(),
+            access_token: Some(Secret::from("token".to_string())),
+            ..Default::default()
+        };
+        let res = github_rate_limits(&http::client(), &github_api_config)
+            .await
+            .unwrap();
+        mock.assert_async().await;
+        assert_eq!(3, res.len());
+        assert_eq!(
+            GithubRateLimit {
+                resource: "core".to_string(),
+                remaining: 4999,
+                limit: 5000,
+                reset: DateTime::from_timestamp(1_760_695_200, 0)
+                    .unwrap()
+                    .naive_utc(),
+            },
+            res[0]
+        );
+        assert_eq!("search", res[1].resource);
+        assert_eq!(30, res[1].remaining);
+    }
+
+    #[tokio::test]
+    async fn test_github_request_headers() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/repos/LMH01/simple_update_checker/releases/latest")
+            .match_header("User-Agent", USER_AGENT)
+            .match_header("Accept", "application/vnd.github+json")
+            .match_header("X-GitHub-Api-Version", "2022-11-28")
+            .with_status(200)
+            .with_body(r#"{ "tag_name": "v1.7.0", "assets": [] }"#)
+            .create_async()
+            .await;
+        let auth = AuthContext::from(GithubApiConfig {
+            api_url: server.url(),
+            ..Default::default()
+        });
+        let provider = Provider::Github(GithubProvider::new("LMH01/simple_update_checker"));
+        let res = provider
+            .check_for_latest_version(&http::client(), &auth)
+            .await;
+        mock.assert_async().await;
+        assert_eq!("v1.7.0", res.unwrap().version);
+    }
+
+    #[sqlx::test]
+    fn test_check_for_updates_renamed_repository(pool: SqlitePool) {
+        let db = db::tests::db(pool);
+        let mut server = mockito::Server::new_async().await;
+        for (name, repository) in [
+            ("alpha_tui", "LMH01/alpha_tui"),
+            ("unchanged", "LMH01/Unchanged"),
+        ] {
+            db.insert_program(
+                &Program::builder()
+                    .name(name)
+                    .current_version("v1.0.0")
+                    .provider(Provider::Github(GithubProvider::new(repository)))
+                    .build()
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        }
+        server
+            .mock("GET", "/repos/LMH01/alpha_tui/releases/latest")
+            .with_status(200)
+            .with_body(
+                r#"{ "tag_name": "v1.0.0", "html_url": "https://github.com/alpha-tui/alpha_tui/releases/tag/v1.0.0", "assets": [] }"#,
+            )
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/LMH01/Unchanged/releases/latest")
+            .with_status(200)
+            .with_body(
+                r#"{ "tag_name": "v1.0.0", "html_url": "https://github.com/LMH01/unchanged/releases/tag/v1.0.0", "assets": [] }"#,
+            )
+            .create_async()
+            .await;
+        let repository =
+            async |name: &str| match db.get_program(name).await.unwrap().unwrap().provider {
+                Provider::Github(github) => github.repository,
+                Provider::GithubCommit(github_commit) => github_commit.repository,
+                Provider::Homebrew(homebrew) => homebrew.package,
+                Provider::FDroid(fdroid) => fdroid.package,
+                Provider::Command(command) => command.command,
+                Provider::Custom(custom) => custom.provider().serialize_config(),
+            };
+
+        let mut auth = AuthContext::from(GithubApiConfig {
+            api_url: server.url(),
+            auto_follow_renames: false,
+            ..Default::default()
+        });
+        let check = async |auth: &AuthContext| {
+            check_for_updates(
+                &db,
+                &http::client(),
+                None,
+                auth,
+                MessageOutput::Silent.callback(),
+                UpdateCheckType::Timed,
+                CheckOptions::default(),
+            )
+            .await
+            .unwrap()
+        };
+        check(&auth).await;
+        assert_eq!("LMH01/alpha_tui", repository("alpha_tui").await);
+
+        auth.github.auto_follow_renames = true;
+        let report = check(&auth).await;
+        assert!(report.errors.is_empty());
+        assert_eq!("alpha-tui/alpha_tui", repository("alpha_tui").await);
+        assert_eq!("LMH01/Unchanged", repository("unchanged").await);
+    }
+
+    #[tokio::test]
+    async fn test_github_commit_latest_version() {
+        let mut server = mockito::Server::new_async().await;
+        for (branch, sha) in [
+            ("HEAD", "1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b"),
+            ("dev", "9f8e7d6c5b4a3f2e1d0c9b8a7f6e5d4c3b2a1f0e"),
+        ] {
+            server
+                .mock(
+                    "GET",
+                    format!("/repos/LMH01/alpha_tui/commits/{branch}").as_str(),
+                )
+                .with_status(200)
+                .with_body(format!(
+                    r#"{{ "sha": "{sha}", "html_url": "https://github.com/LMH01/alpha_tui/commit/{sha}" }}"#
+                ))
+                .create_async()
+                .await;
+        }
+        let auth = AuthContext::from(GithubApiConfig {
+            api_url: server.url(),
+            ..Default::default()
+        });
+        let check = async |branch: Option<&str>| {
+            Provider::GithubCommit(GithubCommitProvider {
+                repository: "LMH01/alpha_tui".to_string(),
+                branch: branch.map(str::to_string),
+            })
+            .check_for_latest_version(&http::client(), &auth)
+            .await
+            .unwrap()
+        };
+        assert_eq!(
+            LatestVersion {
+                version: "1a2b3c4".to_string(),
+                renamed_repository: None,
+                published_at: None,
+            },
+            check(None).await
+        );
+        assert_eq!("9f8e7d6", check(Some("dev")).await.version);
+    }
+
+    #[tokio::test]
+    async fn test_homebrew_latest_version() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/formula/ripgrep.json")
+            .with_status(200)
+            .with_body(
+                r#"{ "name": "ripgrep", "versions": { "stable": "14.1.1", "head": "HEAD", "bottle": true } }"#,
+            )
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/cask/firefox.json")
+            .with_status(200)
+            .with_body(r#"{ "token": "firefox", "version": "131.0.3" }"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/formula/missing.json")
+            .with_status(404)
+            .create_async()
+            .await;
+        let auth = AuthContext {
+            apis: ProviderApiConfig {
+                homebrew_api_url: server.url(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let check = async |package: &str, kind: HomebrewKind| {
+            Provider::Homebrew(HomebrewProvider {
+                package: package.to_string(),
+                kind,
+            })
+            .check_for_latest_version(&http::client(), &auth)
+            .await
+        };
+        assert_eq!(
+            "14.1.1",
+            check("ripgrep", HomebrewKind::Formula)
+                .await
+                .unwrap()
+                .version
+        );
+        assert_eq!(
+            "131.0.3",
+            check("firefox", HomebrewKind::Cask).await.unwrap().version
+        );
+        assert!(matches!(
+            check("missing", HomebrewKind::Formula).await,
+            Err(ProviderError::HomebrewPackageNotFound { kind: HomebrewKind::Formula, package }) if package == "missing"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fdroid_latest_version() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/packages/org.fdroid.fdroid")
+            .with_status(200)
+            .with_body(
+                r#"{ "packageName": "org.fdroid.fdroid", "suggestedVersionCode": 1020050, "packages": [{ "versionName": "1.21.0-alpha1", "versionCode": 1021001 }, { "versionName": "1.20.0", "versionCode": 1020050 }] }"#,
+            )
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/packages/org.example.app")
+            .with_status(200)
+            .with_body(
+                r#"{ "packageName": "org.example.app", "packages": [{ "versionName": "2.0", "versionCode": 20 }, { "versionName": "2.1", "versionCode": 21 }] }"#,
+            )
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/packages/org.example.missing")
+            .with_status(404)
+            .create_async()
+            .await;
+        let auth = AuthContext {
+            apis: ProviderApiConfig {
+                fdroid_api_url: server.url(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let provider = |package: &str| {
+            Provider::FDroid(FDroidProvider {
+                package: package.to_string(),
+            })
+        };
+        let check = async |package: &str| {
+            provider(package)
+                .check_for_latest_version(&http::client(), &auth)
+                .await
+        };
+        // the suggested version is used instead of the newer alpha version
+        assert_eq!("1.20.0", check("org.fdroid.fdroid").await.unwrap().version);
+        assert_eq!("2.1", check("org.example.app").await.unwrap().version);
+        assert!(matches!(
+            check("org.example.missing").await,
+            Err(ProviderError::FDroidPackageNotFound(package)) if package == "org.example.missing"
+        ));
+        assert_eq!(
+            "1.20.0",
+            provider("org.fdroid.fdroid")
+                .check_for_latest_version_in_major(&http::client(), &auth, 1)
+                .await
+                .unwrap()
+                .latest_version
+        );
+    }
+
+    #[tokio::test]
+    async fn test_command_latest_version() {
+        let check = async |command: &str, regex: &str| {
+            Provider::Command(CommandProvider {
+                command: command.to_string(),
+                regex: regex.to_string(),
+            })
+            .check_for_latest_version(&http::client(), &AuthContext::default())
+            .await
+        };
+        assert_eq!(
+            "1.31.2",
+            check("echo 'Client Version: v1.31.2'", r"v(\d+\.\d+\.\d+)")
+                .await
+                .unwrap()
+                .version
+        );
+        // the whole match is used without capture group
+        assert_eq!(
+            "v1.31.2",
+            check("echo 'Client Version: v1.31.2'", r"v[\d.]+")
+                .await
+                .unwrap()
+                .version
+        );
+        assert!(matches!(
+            check("echo 'no version'", r"\d+").await,
+            Err(ProviderError::NoCommandOutputMatch(_))
+        ));
+        assert!(matches!(
+            check("exit 1", r"\d+").await,
+            Err(ProviderError::CommandFailed(_))
+        ));
+        assert!(matches!(
+            check("echo 1", r"(").await,
+            Err(ProviderError::InvalidRegex(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_github_latest_release_not_found() {
+        let mut server = mockito::Server::new_async().await;
+        for repo in ["LMH01/no_releases", "LMH01/missing"] {
+            server
+                .mock("GET", format!("/repos/{repo}/releases/latest").as_str())
+                .with_status(404)
+                .create_async()
+                .await;
+        }
+        server
+            .mock("GET", "/repos/LMH01/no_releases")
+            .with_status(200)
+            .with_body(r#"{ "full_name": "LMH01/no_releases" }"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/LMH01/missing")
+            .with_status(404)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/LMH01/private/releases/latest")
+            .with_status(401)
+            .create_async()
+            .await;
+        let auth = AuthContext::from(GithubApiConfig {
+            api_url: server.url(),
+            ..Default::default()
+        });
+        let check_with_token = |repo: &str, access_token: Option<&str>| {
+            let auth = AuthContext::from(GithubApiConfig {
+                access_token: access_token.map(|token| Secret::from(token.to_string())),
+                ..auth.github.clone()
+            });
+            let github = GithubProvider::new(repo);
+            async move {
+                github
+                    .check_for_latest_version(&http::client(), &auth)
+                    .await
             }
+        };
+        let check = |repo: &str| check_with_token(repo, None);
+        assert!(matches!(
+            check("LMH01/no_releases").await,
+            Err(ProviderError::NoReleases(repo)) if repo == "LMH01/no_releases"
+        ));
+        // without access token the repository may be private
+        let res = check("LMH01/missing").await;
+        assert!(matches!(
+            &res,
+            Err(ProviderError::RepositoryNotFoundUnauthenticated(repo)) if repo == "LMH01/missing"
+        ));
+        assert!(
+            res.unwrap_err()
+                .to_string()
+                .contains("it may be private; configure a GitHub token")
+        );
+        assert!(matches!(
+            check_with_token("LMH01/missing", Some("token")).await,
+            Err(ProviderError::RepositoryNotFound(repo)) if repo == "LMH01/missing"
+        ));
+        assert!(matches!(
+            check("LMH01/private").await,
+            Err(ProviderError::AccessDenied)
+        ));
+    }
 
-            // if update check was performed manually we don't want so sent a notification when timed mode is run
-            // so we set notification sent to true
-            if update_check_type == UpdateCheckType::Manual {
-                if let Some(check_args) = &check_args {
-                    if !check_args.allow_notification {
-                        db.set_notification_sent(&program.name, true).await?;
-                    }
-                }
+    #[tokio::test]
+    async fn test_github_release_selector() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/repos/LMH01/simple_update_checker/releases")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "per_page".to_string(),
+                "100".to_string(),
+            ))
+            .with_status(200)
+            .with_body(
+                r#"[
+                    { "tag_name": "v2.0.0-rc.2", "draft": true, "prerelease": true, "assets": [] },
+                    { "tag_name": "v2.0.0-rc.1", "prerelease": true, "assets": [] },
+                    { "tag_name": "v1.9.0", "assets": [{ "name": "app-windows.zip" }] },
+                    { "tag_name": "v1.8.0", "assets": [{ "name": "app-linux.tar.gz" }] }
+                ]"#,
+            )
+            .create_async()
+            .await;
+        let auth = AuthContext::from(GithubApiConfig {
+            api_url: server.url(),
+            ..Default::default()
+        });
+        let check = async |release_selector: ReleaseSelector, pattern: Option<&str>| {
+            GithubProvider {
+                release_selector,
+                required_asset_pattern: pattern.map(str::to_string),
+                ..GithubProvider::new("LMH01/simple_update_checker")
             }
+            .check_for_latest_version(&http::client(), &auth)
+            .await
+        };
+        assert_eq!(
+            "v2.0.0-rc.1",
+            check(ReleaseSelector::Newest, None).await.unwrap().version
+        );
+        assert_eq!(
+            "v1.9.0",
+            check(ReleaseSelector::NewestStable, None)
+                .await
+                .unwrap()
+                .version
+        );
+        assert_eq!(
+            "v1.8.0",
+            check(ReleaseSelector::NewestStable, Some("*linux*"))
+                .await
+                .unwrap()
+                .version
+        );
+        assert!(matches!(
+            check(ReleaseSelector::Newest, Some("*macos*")).await,
+            Err(ProviderError::AssetNotAvailable { tag, .. }) if tag == "v2.0.0-rc.1"
+        ));
+    }
 
-            programs_with_available_updates.push(program);
-        } else if print_messages {
-            println!("{}: no update found", program.name);
-        }
+    #[test]
+    fn test_github_update_provider() {
+        let github = GithubProvider {
+            release_selector: ReleaseSelector::NewestStable,
+            ..GithubProvider::new("LMH01/simple_update_checker")
+        };
+        assert_eq!("github", github.identifier());
+        assert_eq!(
+            github,
+            serde_json::from_str::<GithubProvider>(&github.serialize_config()).unwrap()
+        );
+        assert_eq!(
+            "https://github.com/LMH01/simple_update_checker/releases/tag/v1.2.0",
+            github.web_url("v1.2.0").unwrap()
+        );
+        let provider = Provider::Github(github);
+        assert_eq!("github", provider.update_provider().identifier());
     }
 
-    // add entry to database that update check was performed
-    db.insert_update_check_history(&UpdateCheckHistoryEntry::from_now(
-        update_check_type,
-        programs_with_available_updates.clone(),
-    ))
-    .await?;
+    #[tokio::test]
+    async fn test_github_latest_matching_version() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/repos/LMH01/simple_update_checker/releases")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "per_page".to_string(),
+                "100".to_string(),
+            ))
+            .with_status(200)
+            .with_body(
+                r#"[
+                    { "tag_name": "v2.1.0", "assets": [] },
+                    { "tag_name": "v1.9.3", "assets": [] },
+                    { "tag_name": "v2.0.0", "assets": [] },
+                    { "tag_name": "v1.9.2", "assets": [] }
+                ]"#,
+            )
+            .create_async()
+            .await;
+        let auth = AuthContext::from(GithubApiConfig {
+            api_url: server.url(),
+            ..Default::default()
+        });
+        let provider = Provider::Github(GithubProvider::new("LMH01/simple_update_checker"));
+        let check = async |constraint: &str| {
+            provider
+                .check_for_latest_matching_version(
+                    &http::client(),
+                    &auth,
+                    &constraint.parse().unwrap(),
+                )
+                .await
+        };
+        assert_eq!("v1.9.3", check("^1").await.unwrap().version);
+        assert_eq!("v2.1.0", check("v2.*").await.unwrap().version);
+        assert!(matches!(
+            check("^3").await,
+            Err(ProviderError::NoVersionMatchingConstraint(constraint)) if constraint == "^3"
+        ));
+    }
 
-    Ok(programs_with_available_updates)
+    #[tokio::test]
+    async fn test_github_latest_version_in_major_skips_prereleases() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/repos/LMH01/simple_update_checker/releases")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "per_page".to_string(),
+                "100".to_string(),
+            ))
+            .with_status(200)
+            .with_body(
+                r#"[
+                    { "tag_name": "v4.0.0", "assets": [] },
+                    { "tag_name": "v3.3.0-rc.1", "prerelease": true, "assets": [] },
+                    { "tag_name": "v3.2.0", "assets": [] }
+                ]"#,
+            )
+            .create_async()
+            .await;
+        let auth = AuthContext::from(GithubApiConfig {
+            api_url: server.url(),
+            ..Default::default()
+        });
+        let provider = Provider::Github(GithubProvider::new("LMH01/simple_update_checker"));
+        let res = provider
+            .check_for_latest_version_in_major(&http::client(), &auth, 3)
+            .await;
+        mock.assert_async().await;
+        assert_eq!(
+            PinnedVersion {
+                latest_version: "v3.2.0".to_string(),
+                newer_major_version: Some("v4.0.0".to_string()),
+                renamed_repository: None,
+                published_at: None,
+            },
+            res.unwrap()
+        );
+    }
+
+    #[test]
+    fn test_github_api_config_access_token_env() {
+        let github_api_config = GithubApiConfig {
+            access_token: Some(Secret::from("global_token".to_string())),
+            ..Default::default()
+        };
+        let mut github = GithubProvider::new("LMH01/simple_update_checker");
+        let res = github.api_config(&github_api_config).unwrap();
+        assert_eq!(
+            Some("global_token"),
+            res.access_token.as_ref().map(Secret::expose)
+        );
+
+        // PATH is set in every test environment
+        github.access_token_env = Some("PATH".to_string());
+        let res = github.api_config(&github_api_config).unwrap();
+        assert_eq!(
+            std::env::var("PATH").ok().as_deref(),
+            res.access_token.as_ref().map(Secret::expose)
+        );
+
+        github.access_token_env = Some("SIMPLE_UPDATE_CHECKER_NOT_SET".to_string());
+        let res = github.api_config(&github_api_config);
+        assert!(matches!(
+            res,
+            Err(ProviderError::MissingAccessToken(name)) if name == "SIMPLE_UPDATE_CHECKER_NOT_SET"
+        ));
+    }
+
+    #[test]
+    fn test_latest_version_in_major() {
+        let versions = ["v4.1.0", "v4.0.0", "v3.10.0", "v3.9.2", "nightly", "v2.5.0"]
+            .map(String::from)
+            .to_vec();
+        assert_eq!(
+            Some(PinnedVersion {
+                latest_version: "v3.10.0".to_string(),
+                newer_major_version: Some("v4.1.0".to_string()),
+                renamed_repository: None,
+                published_at: None,
+            }),
+            latest_version_in_major(&versions, 3)
+        );
+        assert_eq!(
+            Some(PinnedVersion {
+                latest_version: "v4.1.0".to_string(),
+                newer_major_version: None,
+                renamed_repository: None,
+                published_at: None,
+            }),
+            latest_version_in_major(&versions, 4)
+        );
+        assert_eq!(None, latest_version_in_major(&versions, 5));
+    }
 }
@@ -0,0 +1,461 @@
+use std::{cmp::Ordering, fmt::Display, str::FromStr};
+
+use globset::Glob;
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use sqlx::types::chrono::NaiveDate;
+
+use crate::Identifier;
+
+/// Defines how two versions of a program are compared to decide which one is newer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionScheme {
+    /// Semantic versions like `v1.10.2`.
+    Semver,
+    /// Calendar versions like `2024.11.02`, the segments are compared numerically.
+    Calver,
+    /// Dates like `2024.03.15`, `2024-03-15` or `20240315`, compared chronologically.
+    Date,
+    /// Plain numbers or build numbers with a prefix like `r123`.
+    Numeric,
+    /// Versions are compared as strings.
+    Lexical,
+    /// Versions are not ordered, every version that differs is treated as newer.
+    #[default]
+    None,
+}
+
+impl VersionScheme {
+    /// Checks if `candidate` is newer than `current`.
+    ///
+    /// If one of the versions can not be parsed with this scheme, every version that differs is treated as newer.
+    #[must_use]
+    pub fn is_newer(self, candidate: &str, current: &str) -> bool {
+        match self.ordering(candidate, current) {
+            Some(ordering) => ordering == Ordering::Greater,
+            None => candidate != current,
+        }
+    }
+
+    /// Checks if `candidate` is older than `current`.
+    ///
+    /// Returns `false` if one of the versions can not be parsed with this scheme, as it can not be told which one is older.
+    #[must_use]
+    pub fn is_older(self, candidate: &str, current: &str) -> bool {
+        self.ordering(candidate, current) == Some(Ordering::Less)
+    }
+
+    /// Returns the ordering of `candidate` compared to `current`, if both can be parsed with this scheme.
+    fn ordering(self, candidate: &str, current: &str) -> Option<Ordering> {
+        match self {
+            Self::Semver => parse_semver(candidate)
+                .zip(parse_semver(current))
+                .map(|(a, b)| a.cmp(&b)),
+            Self::Calver => parse_calver(candidate)
+                .zip(parse_calver(current))
+                .map(|(a, b)| a.cmp(&b)),
+            Self::Date => parse_date(candidate)
+                .zip(parse_date(current))
+                .map(|(a, b)| a.cmp(&b)),
+            Self::Numeric => parse_numeric(candidate)
+                .zip(parse_numeric(current))
+                .map(|(a, b)| a.cmp(&b)),
+            Self::Lexical => Some(candidate.cmp(current)),
+            Self::None => None,
+        }
+    }
+}
+
+impl Identifier for VersionScheme {
+    fn identifier(&self) -> String {
+        match self {
+            Self::Semver => "semver".to_string(),
+            Self::Calver => "calver".to_string(),
+            Self::Date => "date".to_string(),
+            Self::Numeric => "numeric".to_string(),
+            Self::Lexical => "lexical".to_string(),
+            Self::None => "none".to_string(),
+        }
+    }
+}
+
+impl Display for VersionScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.identifier())
+    }
+}
+
+impl FromStr for VersionScheme {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "semver" => Ok(Self::Semver),
+            "calver" => Ok(Self::Calver),
+            "date" => Ok(Self::Date),
+            "numeric" => Ok(Self::Numeric),
+            "lexical" => Ok(Self::Lexical),
+            "none" => Ok(Self::None),
+            _ => Err(anyhow::anyhow!(
+                "VersionScheme could not be parsed from {s}"
+            )),
+        }
+    }
+}
+
+/// Constraint that a version has to satisfy to be considered as latest version.
+///
+/// The constraint is either a semantic version requirement like `^1` or `>=1.2, <2`,
+/// or a glob pattern like `v1.*` that is matched against the version.
+///
+/// Serialized with serde as the string the constraint was parsed from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct VersionConstraint {
+    constraint: String,
+    matcher: ConstraintMatcher,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum ConstraintMatcher {
+    Semver(VersionReq),
+    Glob(Glob),
+}
+
+impl VersionConstraint {
+    /// Checks if `version` satisfies the constraint.
+    ///
+    /// Versions that can not be parsed as semantic version never satisfy a semantic version requirement.
+    #[must_use]
+    pub fn matches(&self, version: &str) -> bool {
+        match &self.matcher {
+            ConstraintMatcher::Semver(requirement) => {
+                parse_semver(version).is_some_and(|version| requirement.matches(&version))
+            }
+            ConstraintMatcher::Glob(glob) => {
+                let matcher = glob.compile_matcher();
+                // the glob may be written with or without the leading v of the version
+                matcher.is_match(version)
+                    || version
+                        .strip_prefix(['v', 'V'])
+                        .is_some_and(|version| matcher.is_match(version))
+            }
+        }
+    }
+}
+
+impl Display for VersionConstraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.constraint)
+    }
+}
+
+impl FromStr for VersionConstraint {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let matcher = match VersionReq::parse(s) {
+            Ok(requirement) => ConstraintMatcher::Semver(requirement),
+            Err(_) => match Glob::new(s) {
+                Ok(glob) => ConstraintMatcher::Glob(glob),
+                Err(e) => anyhow::bail!(
+                    "Version constraint {s} is neither a semantic version requirement nor a glob pattern: {e}"
+                ),
+            },
+        };
+        Ok(Self {
+            constraint: s.to_string(),
+            matcher,
+        })
+    }
+}
+
+impl TryFrom<String> for VersionConstraint {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> std::result::Result<Self, Self::Error> {
+        Self::from_str(&value)
+    }
+}
+
+impl From<VersionConstraint> for String {
+    fn from(value: VersionConstraint) -> Self {
+        value.constraint
+    }
+}
+
+/// Parses `version` as semantic version.
+///
+/// A leading `v` is ignored and missing minor or patch versions are treated as `0`,
+/// so that tags like `v1.2` can be parsed as well.
+pub fn parse_semver(version: &str) -> Option<Version> {
+    let version = version.trim();
+    let version = version.strip_prefix(['v', 'V']).unwrap_or(version);
+    if let Ok(version) = Version::parse(version) {
+        return Some(version);
+    }
+    // pad the version core, pre-release and build metadata are kept as is
+    let (core, suffix) = version.split_at(version.find(['-', '+']).unwrap_or(version.len()));
+    let parts = core.split('.').count();
+    if parts >= 3 {
+        return None;
+    }
+    Version::parse(&format!("{core}{}{suffix}", ".0".repeat(3 - parts))).ok()
+}
+
+/// Returns `version` without a leading `v`, e.g. `1.2.3` for `v1.2.3`, so that versions of different providers are shown
+/// consistently.
+///
+/// Only used to show versions, versions are always compared in the form in which they are stored. Versions that do not
+/// start with `v` followed by a digit (e.g. `very-new`) are returned unchanged.
+pub fn normalize_for_display(version: &str) -> &str {
+    match version.strip_prefix(['v', 'V']) {
+        Some(stripped) if stripped.starts_with(|c: char| c.is_ascii_digit()) => stripped,
+        _ => version,
+    }
+}
+
+/// Checks if the version core (major, minor and patch version) of `candidate` is newer than the one of `current`.
+///
+/// Pre-releases of the same version core are not treated as newer. Returns `false` if one of the versions can not be
+/// parsed as semantic version.
+pub fn is_newer_release(candidate: &str, current: &str) -> bool {
+    let core = |version: &str| parse_semver(version).map(|v| (v.major, v.minor, v.patch));
+    match (core(candidate), core(current)) {
+        (Some(candidate), Some(current)) => candidate > current,
+        _ => false,
+    }
+}
+
+/// Parses a calendar version like `2024.11.02` or `2024.11.02.1` into its numeric segments.
+///
+/// A leading `v` is ignored, segments can be separated by `.`, `-` or `_`. Leading zeros of segments are ignored.
+/// Trailing segments that are zero are removed, so that a missing patch counter equals a patch counter of zero
+/// (`2024.11` equals `2024.11.0`).
+fn parse_calver(version: &str) -> Option<Vec<u64>> {
+    numeric_segments(segments(version), 1)
+}
+
+/// Parses a version that starts with a date like `2024.03.15`, `2024-03-15` or `20240315` into the date and the
+/// numeric segments that follow it (e.g. the `1` of `2024.03.15.1`).
+///
+/// A leading `v` is ignored, segments can be separated by `.`, `-` or `_`. Trailing segments after the date that are
+/// zero are removed, like for calendar versions.
+fn parse_date(version: &str) -> Option<(NaiveDate, Vec<u64>)> {
+    let mut segments = segments(version);
+    let first = segments.next()?;
+    let date = if first.len() == 8 {
+        NaiveDate::parse_from_str(first, "%Y%m%d").ok()?
+    } else if first.len() == 4 {
+        let year = first.parse().ok()?;
+        let month = segments.next()?.parse().ok()?;
+        let day = segments.next()?.parse().ok()?;
+        NaiveDate::from_ymd_opt(year, month, day)?
+    } else {
+        return None;
+    };
+    Some((date, numeric_segments(segments, 0)?))
+}
+
+/// Splits `version` into its segments, separated by `.`, `-` or `_`. Whitespace and a leading `v` are ignored.
+fn segments(version: &str) -> impl Iterator<Item = &str> {
+    let version = version.trim();
+    let version = version.strip_prefix(['v', 'V']).unwrap_or(version);
+    version.split(['.', '-', '_'])
+}
+
+/// Parses all `segments` as numbers, `None` if a segment is not numeric.
+///
+/// Trailing segments that are zero are removed, but at least `min_len` segments are kept.
+fn numeric_segments<'a>(
+    segments: impl Iterator<Item = &'a str>,
+    min_len: usize,
+) -> Option<Vec<u64>> {
+    let mut numbers = segments
+        .map(|segment| segment.parse().ok())
+        .collect::<Option<Vec<u64>>>()?;
+    while numbers.len() > min_len && numbers.last() == Some(&0) {
+        numbers.pop();
+    }
+    Some(numbers)
+}
+
+/// Parses a plain number or a build number with a non numeric prefix like `r123`.
+fn parse_numeric(version: &str) -> Option<u64> {
+    version
+        .trim()
+        .trim_start_matches(|c: char| !c.is_ascii_digit())
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use semver::Version;
+
+    use sqlx::types::chrono::NaiveDate;
+
+    use super::{
+        VersionConstraint, VersionScheme, is_newer_release, normalize_for_display, parse_calver,
+        parse_date, parse_semver,
+    };
+
+    #[test]
+    fn test_normalize_for_display() {
+        assert_eq!("1.2.3", normalize_for_display("v1.2.3"));
+        assert_eq!("1.2.3", normalize_for_display("V1.2.3"));
+        assert_eq!("1.2.3", normalize_for_display("1.2.3"));
+        assert_eq!("very-new", normalize_for_display("very-new"));
+        assert_eq!("v", normalize_for_display("v"));
+        assert_eq!("a1b2c3d", normalize_for_display("a1b2c3d"));
+    }
+
+    #[test]
+    fn test_parse_semver() {
+        assert_eq!(Some(Version::new(1, 2, 3)), parse_semver("1.2.3"));
+        assert_eq!(Some(Version::new(1, 2, 3)), parse_semver("v1.2.3"));
+        assert_eq!(Some(Version::new(1, 2, 0)), parse_semver("v1.2"));
+        assert_eq!(Some(Version::new(4, 0, 0)), parse_semver("4"));
+        assert_eq!(
+            Some(Version::parse("2.0.0-rc.1").unwrap()),
+            parse_semver("v2.0-rc.1")
+        );
+    }
+
+    #[test]
+    fn test_parse_semver_invalid() {
+        assert_eq!(None, parse_semver("nightly"));
+        assert_eq!(None, parse_semver("1.2.3.4"));
+        assert_eq!(None, parse_semver(""));
+    }
+
+    #[test]
+    fn test_version_constraint() {
+        let constraint: VersionConstraint = "^1".parse().unwrap();
+        assert!(constraint.matches("v1.9.2"));
+        assert!(!constraint.matches("v2.0.0"));
+        assert!(!constraint.matches("v1.10.0-rc.1"));
+        assert!(!constraint.matches("nightly"));
+        let constraint: VersionConstraint = ">=1.2, <2".parse().unwrap();
+        assert!(constraint.matches("1.2.0"));
+        assert!(!constraint.matches("1.1.0"));
+        assert_eq!(">=1.2, <2", constraint.to_string());
+    }
+
+    #[test]
+    fn test_version_constraint_glob() {
+        let constraint: VersionConstraint = "v1.*-lts".parse().unwrap();
+        assert!(constraint.matches("v1.4-lts"));
+        assert!(!constraint.matches("v2.0-lts"));
+        let constraint: VersionConstraint = "v2024.*".parse().unwrap();
+        assert!(constraint.matches("v2024.11.02"));
+        assert!(!constraint.matches("2025.01.01"));
+        assert!("[".parse::<VersionConstraint>().is_err());
+    }
+
+    #[test]
+    fn test_is_newer_release() {
+        assert!(is_newer_release("v1.3.0", "v1.2.0"));
+        assert!(is_newer_release("v1.3.0-rc.1", "v1.2.5"));
+        assert!(!is_newer_release("v1.3.0-rc.2", "v1.3.0-rc.1"));
+        assert!(!is_newer_release("v1.3.0", "v1.3.0-rc.1"));
+        assert!(!is_newer_release("v1.2.0", "v1.3.0"));
+        assert!(!is_newer_release("nightly", "v1.3.0"));
+    }
+
+    #[test]
+    fn test_version_scheme_semver() {
+        assert!(VersionScheme::Semver.is_newer("1.10", "1.9"));
+        assert!(VersionScheme::Semver.is_newer("v2.0.0", "v2.0.0-rc.1"));
+        assert!(!VersionScheme::Semver.is_newer("v1.9.0", "v1.10.0"));
+        assert!(!VersionScheme::Semver.is_newer("v1.0.0", "v1.0.0"));
+        // versions that can not be parsed are compared for inequality
+        assert!(VersionScheme::Semver.is_newer("nightly", "v1.0.0"));
+    }
+
+    #[test]
+    fn test_version_scheme_calver() {
+        assert!(!VersionScheme::Calver.is_newer("2024.2", "2024.10"));
+        assert!(VersionScheme::Calver.is_newer("2024.10", "2024.2"));
+        assert!(VersionScheme::Calver.is_newer("2024.11.02", "2024.11.1"));
+        assert!(VersionScheme::Calver.is_newer("2024.11.02.1", "2024.11.02"));
+        assert!(!VersionScheme::Calver.is_newer("v2024.01.05", "2024.1.5"));
+    }
+
+    #[test]
+    fn test_parse_calver() {
+        assert_eq!(Some(vec![2021, 12, 17]), parse_calver("2021.12.17"));
+        assert_eq!(Some(vec![2024, 11, 2, 1]), parse_calver("v2024.11.02.1"));
+        assert_eq!(Some(vec![2024, 11, 2, 1]), parse_calver("2024-11-02_1"));
+        assert_eq!(Some(vec![2024, 11]), parse_calver("2024.11.0"));
+        assert_eq!(Some(vec![2024, 10]), parse_calver("2024.10"));
+        assert_eq!(None, parse_calver("2024.11.rc1"));
+        assert_eq!(None, parse_calver("nightly"));
+    }
+
+    #[test]
+    fn test_version_scheme_calver_patch_counter() {
+        // youtube-dl style versions
+        assert!(VersionScheme::Calver.is_newer("2021.12.17", "2021.06.06"));
+        assert!(VersionScheme::Calver.is_newer("2021.12.17.1", "2021.12.17"));
+        assert!(!VersionScheme::Calver.is_newer("2021.12.17", "2021.12.17.1"));
+        // a missing patch counter equals a patch counter of zero
+        assert!(!VersionScheme::Calver.is_newer("2024.11.0", "2024.11"));
+        assert!(!VersionScheme::Calver.is_older("2024.11.0", "2024.11"));
+        // leading zeros are ignored
+        assert!(!VersionScheme::Calver.is_newer("2024.09.01", "2024.9.1"));
+    }
+
+    #[test]
+    fn test_parse_date() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        assert_eq!(Some((date, vec![])), parse_date("2024.03.15"));
+        assert_eq!(Some((date, vec![])), parse_date("v2024-3-15"));
+        assert_eq!(Some((date, vec![])), parse_date("20240315"));
+        assert_eq!(Some((date, vec![2])), parse_date("20240315.2"));
+        assert_eq!(Some((date, vec![1])), parse_date("2024_03_15_1"));
+        assert_eq!(Some((date, vec![])), parse_date("2024.03.15.0"));
+        assert_eq!(None, parse_date("2024.02.30"));
+        assert_eq!(None, parse_date("2024.03"));
+        assert_eq!(None, parse_date("20241315"));
+        assert_eq!(None, parse_date("v1.2.3"));
+    }
+
+    #[test]
+    fn test_version_scheme_date() {
+        assert!(VersionScheme::Date.is_newer("2024.03.16", "2024.03.15"));
+        assert!(VersionScheme::Date.is_newer("2024.10.01", "2024.9.30"));
+        assert!(VersionScheme::Date.is_newer("2024.03.15.1", "2024.03.15"));
+        // the format of the tags changed
+        assert!(VersionScheme::Date.is_newer("2024.03.16", "20240315"));
+        assert!(!VersionScheme::Date.is_newer("20240315", "2024.03.16"));
+        assert!(!VersionScheme::Date.is_newer("20240315", "2024-03-15"));
+        assert!(VersionScheme::Date.is_older("20240314", "2024-03-15"));
+    }
+
+    #[test]
+    fn test_version_scheme_numeric() {
+        assert!(VersionScheme::Numeric.is_newer("r123", "r99"));
+        assert!(VersionScheme::Numeric.is_newer("10", "9"));
+        assert!(!VersionScheme::Numeric.is_newer("build-7", "build-8"));
+    }
+
+    #[test]
+    fn test_version_scheme_is_older() {
+        assert!(VersionScheme::Semver.is_older("v1.1.0", "v1.2.0"));
+        assert!(!VersionScheme::Semver.is_older("v1.2.0", "v1.2.0"));
+        assert!(!VersionScheme::Semver.is_older("nightly", "v1.2.0"));
+        assert!(VersionScheme::Calver.is_older("2024.2", "2024.10"));
+        // without a scheme it can not be told which version is older
+        assert!(!VersionScheme::None.is_older("v1.1.0", "v1.2.0"));
+    }
+
+    #[test]
+    fn test_version_scheme_lexical_and_none() {
+        assert!(!VersionScheme::Lexical.is_newer("10", "9"));
+        assert!(VersionScheme::Lexical.is_newer("b", "a"));
+        assert!(VersionScheme::None.is_newer("v1.0.0", "v1.1.0"));
+        assert!(!VersionScheme::None.is_newer("v1.0.0", "v1.0.0"));
+    }
+}